@@ -0,0 +1,59 @@
+use yew::{classes, html, Component, Context, Html, Properties};
+use crate::components::store::scope_store;
+use crate::sl::diff::{diff_answer, is_exact, DiffKind};
+use crate::sl::i18n::{translate, translate_format};
+
+#[derive(Properties, PartialEq, Clone, Debug)]
+pub struct AnswerDiffProps {
+    pub entered: String,
+    pub expected: String,
+}
+
+/// The `AnswerDiff` component highlights the differences between the learner's answer and the expected answer.
+///
+/// Each run of characters is wrapped in a `span` styled by its `DiffKind`: correctly typed characters are
+/// shown plainly, missing characters are underlined, extra characters are struck through and characters
+/// with the wrong accent are marked separately. When the answer was typed exactly, nothing is rendered.
+///
+/// The colours are lost on screen readers, so the highlighted answer is hidden from them and the expected
+/// answer is read out instead.
+///
+/// ## Usage:
+/// Rendered by the `Study` page in `StudyMode::Outcome` once the expected answer is known.
+pub struct AnswerDiff;
+
+impl Component for AnswerDiff {
+    type Message = ();
+    type Properties = AnswerDiffProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let segments = diff_answer(&props.entered, &props.expected);
+
+        if is_exact(&segments) {
+            return html! {};
+        }
+
+        let lang = scope_store(ctx.link()).state().settings.language.clone();
+        let spans: Vec<Html> = segments.iter().map(|segment| {
+            let title = match segment.kind {
+                DiffKind::Equal => "".to_string(),
+                DiffKind::Insert => translate(&lang, "missing"),
+                DiffKind::Delete => translate(&lang, "extra"),
+                DiffKind::Accent => translate(&lang, "accent"),
+            };
+            html! { <span class={classes!(segment.kind.css_class())} title={title}>{ segment.text.clone() }</span> }
+        }).collect();
+
+        html! {
+            <>
+                <p class="answer-diff" aria-hidden="true">{ for spans }</p>
+                <p class="visually-hidden">{ translate_format(&lang, "The expected answer is {}", &[&props.expected]) }</p>
+            </>
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use yew::{function_component, html, Callback, Html, Properties};
+use yew_router::hooks::use_navigator;
+use yew_router::prelude::Link;
+use crate::components::store::use_selector;
+use crate::route::Route;
+use crate::sl::gql::FetchError;
+use crate::sl::i18n::translate;
+
+#[derive(Properties, PartialEq, Clone, Debug)]
+pub struct ErrorPanelProps {
+    /// The failure to explain.
+    pub error: FetchError,
+
+    /// Called when the learner wants to try again, `None` when there is nothing to retry.
+    #[prop_or_default]
+    pub on_retry: Option<Callback<()>>,
+}
+
+/// The `ErrorPanel` component explains a failure to the learner and offers a way out.
+///
+/// It shows a friendly message for the kind of failure in the learner's interface language, with buttons to
+/// try again, go back to the previous page or go home. The error message itself is kept in an expandable
+/// "Technical details" section for developers.
+#[function_component(ErrorPanel)]
+pub fn error_panel(props: &ErrorPanelProps) -> Html {
+    let lang = use_selector(|state| state.settings.language.clone());
+    let navigator = use_navigator();
+    let tr = |text: &str| translate(&lang, text);
+
+    let on_back = Callback::from(move |_| {
+        if let Some(navigator) = &navigator {
+            navigator.back();
+        }
+    });
+
+    html! {
+        <div class="error-panel" role="alert">
+            <h2>{ tr(props.error.kind.title()) }</h2>
+            <p>{ tr(props.error.kind.advice()) }</p>
+            <div class="actions">
+                if let Some(on_retry) = props.on_retry.clone() {
+                    <button type="button" onclick={Callback::from(move |_| on_retry.emit(()))}>{ tr("Try again") }</button>
+                }
+                <button type="button" onclick={on_back}>{ tr("Back") }</button>
+                <Link<Route> to={Route::Home}>{ tr("Home") }</Link<Route>>
+            </div>
+            <details>
+                <summary>{ tr("Technical details") }</summary>
+                <pre>{ format!("{:?}: {}", props.error.kind, props.error.message()) }</pre>
+            </details>
+        </div>
+    }
+}
@@ -0,0 +1,71 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_state, Callback, Html, Properties};
+use crate::components::error_panel::ErrorPanel;
+use crate::components::store::{use_selector, use_store};
+use crate::sl::export::{download, fetch_all_vocab, ExportFormat};
+use crate::sl::gql::FetchError;
+use crate::sl::i18n::translate;
+use crate::sl::vocab::VocabFilter;
+
+#[derive(Properties, PartialEq, Clone, Debug)]
+pub struct ExportButtonsProps {
+    /// Which vocabulary to export, all of it by default.
+    #[prop_or_default]
+    pub filter: VocabFilter,
+}
+
+/// The `ExportButtons` component downloads the learner's vocabulary with its statistics as CSV or JSON.
+///
+/// Clicking a button fetches every item matching `filter`, page by page, and downloads the file named after
+/// today's date. The buttons are disabled while an export is running, and a failed export is explained below them
+/// with an `ErrorPanel`.
+#[function_component(ExportButtons)]
+pub fn export_buttons(props: &ExportButtonsProps) -> Html {
+    let store = use_store();
+    let awesome_id = use_selector(|state| state.learner_id());
+    let lang = use_selector(|state| state.settings.language.clone());
+    let exporting = use_state(|| false);
+    let error = use_state(|| None::<FetchError>);
+
+    let on_export = {
+        let exporting = exporting.clone();
+        let error = error.clone();
+        let filter = props.filter.clone();
+        Callback::from(move |format: ExportFormat| {
+            if *exporting {
+                return;
+            }
+            exporting.set(true);
+            error.set(None);
+
+            let exporting = exporting.clone();
+            let error = error.clone();
+            let filter = filter.clone();
+            let store = store.clone();
+            spawn_local(async move {
+                let date: String = js_sys::Date::new_0().to_iso_string().as_string().unwrap_or_default().chars().take(10).collect();
+                let result = fetch_all_vocab(&store, awesome_id, filter).await
+                    .and_then(|entries| format.export(&entries).map_err(FetchError::from))
+                    .and_then(|contents| download(&format.file_name(&date), format.mime_type(), &contents)
+                        .map_err(FetchError::from));
+                if let Err(err) = result {
+                    error.set(Some(err));
+                }
+                exporting.set(false);
+            });
+        })
+    };
+
+    html! {
+        <div class="export">
+            <button disabled={*exporting} onclick={on_export.reform(|_| ExportFormat::Csv)}>{ translate(&lang, "Export CSV") }</button>
+            <button disabled={*exporting} onclick={on_export.reform(|_| ExportFormat::Json)}>{ translate(&lang, "Export JSON") }</button>
+            if *exporting {
+                <span>{ translate(&lang, "Exporting...") }</span>
+            }
+            if let Some(error) = (*error).clone() {
+                <ErrorPanel {error}/>
+            }
+        </div>
+    }
+}
@@ -1 +1,8 @@
-pub mod prompt;
\ No newline at end of file
+pub mod answer_diff;
+pub mod error_panel;
+pub mod export_buttons;
+pub mod notes_editor;
+pub mod prompt;
+pub mod shortcuts;
+pub mod store;
+pub mod theme;
@@ -0,0 +1,90 @@
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Event, HtmlTextAreaElement, InputEvent};
+use yew::{function_component, html, use_state, Callback, Html, Properties};
+use crate::components::store::use_selector;
+use crate::sl::i18n::{translate, translate_format};
+
+#[derive(Properties, PartialEq, Clone, Debug)]
+pub struct NotesEditorProps {
+    /// The notes currently stored for the vocab study.
+    pub notes: String,
+
+    /// Whether a save is in flight.
+    #[prop_or_default]
+    pub saving: bool,
+
+    /// The error of the last failed save, if any.
+    #[prop_or_default]
+    pub error: Option<String>,
+
+    /// Called with the edited notes when the learner saves them.
+    pub on_save: Callback<String>,
+}
+
+/// The `NotesEditor` component lets the learner view and edit their notes for a vocabulary item inline.
+///
+/// The notes are shown as text with an "Edit notes" action. While editing, a text area holds a draft
+/// that is only handed to `on_save` when the learner saves; cancelling discards it. Saving itself is
+/// left to the owner of the challenge, which passes the progress back through `saving` and `error`.
+#[function_component(NotesEditor)]
+pub fn notes_editor(props: &NotesEditorProps) -> Html {
+    let lang = use_selector(|state| state.settings.language.clone());
+    let tr = |text: &str| translate(&lang, text);
+    let editing = use_state(|| false);
+    let draft = use_state(|| props.notes.clone());
+
+    let on_edit = {
+        let editing = editing.clone();
+        let draft = draft.clone();
+        let notes = props.notes.clone();
+        Callback::from(move |_| {
+            draft.set(notes.clone());
+            editing.set(true);
+        })
+    };
+
+    let on_input = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let event: Event = e.dyn_into().unwrap_throw();
+            let target: HtmlTextAreaElement = event.target().unwrap_throw().dyn_into().unwrap_throw();
+            draft.set(target.value());
+        })
+    };
+
+    let on_save = {
+        let editing = editing.clone();
+        let draft = draft.clone();
+        let on_save = props.on_save.clone();
+        Callback::from(move |_| {
+            on_save.emit(draft.trim().to_string());
+            editing.set(false);
+        })
+    };
+
+    let on_cancel = {
+        let editing = editing.clone();
+        Callback::from(move |_| editing.set(false))
+    };
+
+    html! {
+        <div class="notes">
+            if *editing {
+                <label for="user_notes">{ tr("Your notes") }</label>
+                <textarea id="user_notes" value={(*draft).clone()} oninput={on_input} />
+                <button type="button" onclick={on_save}>{ tr("Save notes") }</button>
+                <button type="button" onclick={on_cancel}>{ tr("Cancel") }</button>
+            } else {
+                if !props.notes.is_empty() {
+                    <p>{ translate_format(&lang, "Your Notes: {}", &[&props.notes]) }</p>
+                }
+                <button type="button" disabled={props.saving} aria-busy={props.saving.to_string()} onclick={on_edit}>
+                    { tr(if props.saving { "Saving..." } else if props.notes.is_empty() { "Add notes" } else { "Edit notes" }) }
+                </button>
+            }
+            if let Some(error) = &props.error {
+                <p class="error" role="alert">{ translate_format(&lang, "Your notes could not be saved: {}", &[error]) }</p>
+            }
+        </div>
+    }
+}
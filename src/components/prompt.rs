@@ -1,111 +1,111 @@
-use yew::{Component, Context, Html, html, Properties};
-use crate::debug_object;
+use yew::{function_component, html, use_effect_with, use_memo, use_mut_ref, use_state, Callback, Html, Properties};
+use crate::components::store::use_selector;
+use crate::sl::hints::{build_hints, Hint, HintKind, DEFAULT_HINT_ORDER};
+use crate::sl::i18n::{translate, translate_format};
 use crate::sl::study::Challenge;
 
 #[derive(Properties, PartialEq, Clone, Debug)]
 pub struct PromptProps {
-    pub challenge: Challenge
-}
-
-pub enum Msg {
-    Help,
-}
-pub struct Prompt {
-    props: PromptProps,
-    available_hints: Vec<String>,
-    display_hints: Vec<String>,
-}
-
-impl Prompt {
-
-    pub fn determine_hints(&mut self, props: PromptProps) -> Vec<String> {
-        let mut hints = Vec::new();
-
-        let challenge = props.challenge;
-
-        if !challenge.pos.is_empty() {
-            hints.push(
-                format!("    Part of Speech: {}", challenge.pos)
-            );
-        }
-
-        if !challenge.infinitive.is_empty() {
-            hints.push(
-                format!("    Infinitive: {}", challenge.infinitive)
-            );
-        }
+    pub challenge: Challenge,
 
-        if !challenge.hint.is_empty() {
-            hints.push(
-                format!("    Other Hints: {}", challenge.hint)
-            );
-        }
+    /// The hint kinds offered, in the order they are revealed.
+    #[prop_or_else(|| DEFAULT_HINT_ORDER.to_vec())]
+    pub hint_order: Vec<HintKind>,
 
-        if !challenge.user_notes.is_empty() {
-            hints.push(
-                format!("    Your Notes: {}", challenge.user_notes)
-            );
-        }
+    /// Called with the kind of every hint the learner reveals.
+    #[prop_or_default]
+    pub on_hint: Callback<HintKind>,
 
-        hints
-    }
+    /// Counts the hints requested from outside, e.g. with a keyboard shortcut. Every change reveals the next hint.
+    #[prop_or_default]
+    pub hint_requests: u32,
 }
 
-impl Component for Prompt {
-    type Message = Msg;
-    type Properties = PromptProps;
-
-    fn create(ctx: &Context<Self>) -> Self {
-
-        Self {
-            props: ctx.props().clone(),
-            available_hints: Vec::new(),
-            display_hints: Vec::new(),
-        }
-    }
-
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-
-        match msg {
-            Msg::Help  => {
+/// Selects the hints to display out of the first `revealed` hints.
+///
+/// Masked letter hints reveal progressively, so only the latest one is kept.
+pub fn displayed_hints(hints: &[Hint], revealed: usize) -> Vec<Hint> {
+    let mut display: Vec<Hint> = Vec::new();
 
-                if let Some(hint) = self.available_hints.pop() {
-                    self.display_hints.push(hint);
-                }
-
-                true
+    for hint in hints.iter().take(revealed) {
+        match display.last_mut() {
+            Some(last) if last.kind == HintKind::MaskedLetters && hint.kind == HintKind::MaskedLetters => {
+                *last = hint.clone();
             }
+            _ => display.push(hint.clone()),
         }
     }
 
-    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
-        if self.props.challenge != ctx.props().challenge {
-            // Update component's state based on the new challenge.
-            self.props = ctx.props().clone();
-            self.available_hints = self.determine_hints(self.props.clone());
+    display
+}
 
-            // Return true to indicate that the component needs to re-render with the new props.
-            true
-        } else {
-            false
-        }
+/// The `Prompt` component presents a `Challenge` to the learner and reveals hints on request.
+///
+/// The hints are derived from the `challenge` prop and the `hint_order`, so they are available from
+/// the very first render. The number of revealed hints is kept in state together with the
+/// `vocab_study_id` it belongs to: when a challenge for another vocab study is passed in, the count
+/// starts over, while re-renders for the same challenge keep the hints already revealed.
+///
+/// The hints are shown in a polite live region, so screen readers announce each one as it is revealed,
+/// and the question has the id `challenge_prompt` so the answer box can refer to it. The labels are in
+/// the learner's interface language.
+///
+/// ## Properties:
+/// - `challenge`: The challenge to present.
+/// - `hint_order`: The hint kinds to offer, in the order they are revealed.
+/// - `on_hint`: Called with the kind of each hint as it is revealed, so the caller can track hint usage.
+/// - `hint_requests`: Reveals the next hint whenever it changes, like the "Give me a hint" link.
+#[function_component(Prompt)]
+pub fn prompt(props: &PromptProps) -> Html {
+    let lang = use_selector(|state| state.settings.language.clone());
+    let vocab_study_id = props.challenge.vocab_study_id;
+    let revealed = use_state(|| (vocab_study_id, 0usize));
+    let handled_requests = use_mut_ref(|| props.hint_requests);
+    let hints = use_memo(
+        (props.challenge.clone(), props.hint_order.clone()),
+        |(challenge, hint_order)| build_hints(challenge, hint_order),
+    );
+
+    let num_revealed = if revealed.0 == vocab_study_id { revealed.1 } else { 0 };
+    let display: Vec<Html> = displayed_hints(&hints, num_revealed).iter()
+        .map(|hint| html! { <p class="hint">{ format!("{}: {}", translate(&lang, hint.kind.label()), hint.text) }</p> })
+        .collect();
+
+    let on_help = {
+        let revealed = revealed.clone();
+        let hints = hints.clone();
+        let on_hint = props.on_hint.clone();
+        Callback::from(move |_: ()| {
+            if let Some(hint) = hints.get(num_revealed) {
+                on_hint.emit(hint.kind);
+                revealed.set((vocab_study_id, num_revealed + 1));
+            }
+        })
+    };
+
+    {
+        let on_help = on_help.clone();
+        use_effect_with(props.hint_requests, move |requests| {
+            if *handled_requests.borrow() != *requests {
+                *handled_requests.borrow_mut() = *requests;
+                on_help.emit(());
+            }
+        });
     }
 
-    fn view(&self, ctx: &Context<Self>) -> Html {
-        let hints: Vec<Html> = self.display_hints.iter().map(|hint| html! { <p>{ hint }</p> }).collect();
-
-        debug_object!(hints);
-
-        html! {
-            <div>
-                <p> { format!("Translate: {}", self.props.challenge.first_lang.clone()) } </p>
-                <p> { format!("    Words in phrase: {}", self.props.challenge.num_learning_words.clone()) } </p>
-                { for hints }
-
-                if !self.available_hints.is_empty() {
-                    <a onclick={ctx.link().callback(|_| Msg::Help)}>{ "give me a hint" }</a>
-                }
+    html! {
+        <div>
+            <p id="challenge_prompt"> { translate_format(&lang, "Translate: {}", &[&props.challenge.first_lang]) } </p>
+            <p id="challenge_words"> { format!("    {}: {}", translate(&lang, "Words in phrase"), props.challenge.num_learning_words) } </p>
+            <div id="challenge_hints" class="hints" aria-live="polite">
+                { for display }
             </div>
-        }
+
+            if num_revealed < hints.len() {
+                <button type="button" class="hint-link" aria-controls="challenge_hints" onclick={on_help.reform(|_| ())}>
+                    { translate(&lang, "Give me a hint") }
+                </button>
+            }
+        </div>
     }
-}
\ No newline at end of file
+}
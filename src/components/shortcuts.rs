@@ -0,0 +1,267 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Element, Event, HtmlElement, HtmlInputElement, KeyboardEvent};
+use yew::{function_component, html, use_effect_with, use_mut_ref, use_state, Callback, Children, ContextProvider, Html, Properties};
+use yew_router::hooks::use_navigator;
+use crate::components::store::use_selector;
+use crate::route::Route;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::shortcuts::{ignores_key, normalize_key, KeyBuffer, KeySequence, ShortcutAction, ShortcutConflict, ShortcutMap,
+                           SHORTCUTS_STORAGE_KEY};
+use crate::sl::storage::{load_json, save_json, StorageArea};
+
+/// The shortcuts a page handles itself, on top of the global ones.
+///
+/// ## Fields:
+/// - `actions`: The page actions the page handles.
+/// - `on_action`: Called with every page action typed while the page is shown.
+#[derive(Clone)]
+pub struct PageShortcuts {
+    pub actions: Vec<ShortcutAction>,
+    pub on_action: Callback<ShortcutAction>,
+}
+
+/// Context shared by the `ShortcutsProvider`, with which pages register the shortcuts they handle.
+///
+/// Only one page is shown at a time, so a page registers in `create` and unregisters in `destroy`.
+#[derive(Clone, Default)]
+pub struct ShortcutRegistry(Rc<RefCell<Option<PageShortcuts>>>);
+
+impl PartialEq for ShortcutRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl ShortcutRegistry {
+    pub fn register(&self, actions: Vec<ShortcutAction>, on_action: Callback<ShortcutAction>) {
+        *self.0.borrow_mut() = Some(PageShortcuts { actions, on_action });
+    }
+
+    pub fn unregister(&self) {
+        *self.0.borrow_mut() = None;
+    }
+
+    /// The actions available right now: the global ones and those of the registered page.
+    fn active(&self) -> Vec<ShortcutAction> {
+        let mut actions = ShortcutAction::GLOBAL.to_vec();
+        if let Some(page) = self.0.borrow().as_ref() {
+            actions.extend(page.actions.iter().copied());
+        }
+        actions
+    }
+
+    /// Hands a page action to the registered page.
+    fn emit(&self, action: ShortcutAction) {
+        let page = self.0.borrow().clone();
+        if let Some(page) = page {
+            page.on_action.emit(action);
+        }
+    }
+}
+
+/// The learner's shortcuts from local storage, or the defaults when none are stored or the stored ones
+/// conflict.
+pub fn load_shortcuts() -> ShortcutMap {
+    load_json::<ShortcutMap>(StorageArea::Local, SHORTCUTS_STORAGE_KEY)
+        .filter(|map| map.conflicts().is_empty())
+        .unwrap_or_default()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ShortcutsProviderProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// The `ShortcutsProvider` component listens to the keyboard for the whole application.
+///
+/// It wraps the routed pages and provides them a `ShortcutRegistry`. Key presses are matched against the
+/// learner's `ShortcutMap` with a `KeyBuffer`, so sequences such as `g p` work. Global actions are handled
+/// here: navigation and the help overlay, which lists the shortcuts and lets the learner change them. Page
+/// actions are handed to the page that registered them.
+///
+/// Keys typed into text fields are left alone, so answers can be typed freely. `Escape` leaves the field,
+/// after which the shortcuts work again.
+#[function_component(ShortcutsProvider)]
+pub fn shortcuts_provider(props: &ShortcutsProviderProps) -> Html {
+    let registry = (*use_state(ShortcutRegistry::default)).clone();
+    let buffer = use_mut_ref(KeyBuffer::default);
+    let map = use_state(load_shortcuts);
+    let help_open = use_state(|| false);
+    let navigator = use_navigator();
+
+    {
+        let registry = registry.clone();
+        let buffer = buffer.clone();
+        let help_open = help_open.clone();
+        let navigator = navigator.clone();
+        use_effect_with(((*map).clone(), *help_open), move |(map, is_open)| {
+            let map = map.clone();
+            let is_open = *is_open;
+            let listener = Closure::<dyn Fn(KeyboardEvent)>::new(move |e: KeyboardEvent| {
+                if e.ctrl_key() || e.alt_key() || e.meta_key() {
+                    return;
+                }
+
+                let key = normalize_key(&e.key());
+                let target: Option<Element> = e.target().and_then(|target| target.dyn_into().ok());
+                if let Some(target) = &target {
+                    let editable = target.dyn_ref::<HtmlElement>().is_some_and(|element| element.is_content_editable());
+                    if ignores_key(&target.tag_name(), editable, &key) {
+                        if key == "Escape" {
+                            if let Some(element) = target.dyn_ref::<HtmlElement>() {
+                                element.blur().unwrap_or_default();
+                            }
+                        }
+                        return;
+                    }
+                }
+
+                if key == "Escape" && is_open {
+                    help_open.set(false);
+                    return;
+                }
+
+                let action = buffer.borrow_mut().press(&map, &registry.active(), &key, js_sys::Date::now());
+                let Some(action) = action else {
+                    return;
+                };
+                e.prevent_default();
+
+                let route = match action {
+                    ShortcutAction::Help => {
+                        help_open.set(!is_open);
+                        return;
+                    }
+                    ShortcutAction::GoHome => Route::Home,
+                    ShortcutAction::GoStudy => Route::Study,
+                    ShortcutAction::GoVocab => Route::Vocab,
+                    ShortcutAction::GoProfile => Route::Profile,
+                    _ => {
+                        registry.emit(action);
+                        return;
+                    }
+                };
+                if let Some(navigator) = &navigator {
+                    help_open.set(false);
+                    navigator.push(&route);
+                }
+            });
+
+            let document = web_sys::window().and_then(|window| window.document());
+            if let Some(document) = &document {
+                document.add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref()).unwrap_or_default();
+            }
+
+            move || {
+                if let Some(document) = &document {
+                    document.remove_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref()).unwrap_or_default();
+                }
+            }
+        });
+    }
+
+    let on_change = {
+        let map = map.clone();
+        Callback::from(move |changed: ShortcutMap| {
+            save_json(StorageArea::Local, SHORTCUTS_STORAGE_KEY, &changed).unwrap_or_default();
+            map.set(changed);
+        })
+    };
+    let on_close = {
+        let help_open = help_open.clone();
+        Callback::from(move |_| help_open.set(false))
+    };
+
+    html! {
+        <ContextProvider<ShortcutRegistry> context={registry}>
+            { props.children.clone() }
+            if *help_open {
+                <ShortcutHelp map={(*map).clone()} {on_change} {on_close}/>
+            }
+        </ContextProvider<ShortcutRegistry>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ShortcutHelpProps {
+    /// The learner's shortcuts.
+    pub map: ShortcutMap,
+
+    /// Called with the changed shortcuts when the learner rebinds an action.
+    pub on_change: Callback<ShortcutMap>,
+
+    /// Called when the learner closes the help.
+    pub on_close: Callback<()>,
+}
+
+/// The `ShortcutHelp` component is an overlay listing the keyboard shortcuts.
+///
+/// Each action has a field with its key sequences, separated by commas, which the learner can edit.
+/// A change that conflicts with another shortcut is refused and the conflict is shown instead.
+#[function_component(ShortcutHelp)]
+pub fn shortcut_help(props: &ShortcutHelpProps) -> Html {
+    let lang = use_selector(|state| state.settings.language.clone());
+    let tr = |text: &str| translate(&lang, text);
+    let error = use_state(|| None::<ShortcutConflict>);
+
+    let rows: Html = ShortcutAction::ALL.iter().map(|&action| {
+        let keys: Vec<String> = props.map.keys_for(action).iter().map(|keys| keys.to_string()).collect();
+
+        let on_rebind = {
+            let map = props.map.clone();
+            let on_change = props.on_change.clone();
+            let error = error.clone();
+            Callback::from(move |e: Event| {
+                let target: HtmlInputElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+                let keys: Vec<KeySequence> = target.value().split(',').filter_map(KeySequence::parse).collect();
+                match map.rebind(action, keys) {
+                    Ok(changed) => {
+                        error.set(None);
+                        on_change.emit(changed);
+                    }
+                    Err(conflict) => error.set(Some(conflict)),
+                }
+            })
+        };
+
+        html! {
+            <tr>
+                <th>{ tr(action.label()) }</th>
+                <td><input type="text" aria-label={tr(action.label())} value={keys.join(", ")} onchange={on_rebind} /></td>
+            </tr>
+        }
+    }).collect();
+
+    let on_reset = {
+        let on_change = props.on_change.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            error.set(None);
+            on_change.emit(ShortcutMap::default());
+        })
+    };
+
+    html! {
+        <div class="shortcut-help">
+            <div class="dialog" role="dialog" aria-modal="true" aria-labelledby="shortcut_help_title">
+                <h2 id="shortcut_help_title">{ tr("Keyboard Shortcuts") }</h2>
+                <p>{ tr("Separate alternative keys with commas and the keys of a sequence with spaces, e.g. \"g p\". Shortcuts are off while typing an answer: press Esc to leave the answer box.") }</p>
+                <table class="summary">{ rows }</table>
+                if let Some(conflict) = (*error).clone() {
+                    <p class="error" role="alert">
+                        { translate_format(&lang, "\"{}\" ({}) conflicts with \"{}\" ({})", &[
+                            &conflict.first.keys, &tr(conflict.first.action.label()),
+                            &conflict.second.keys, &tr(conflict.second.action.label()),
+                        ]) }
+                    </p>
+                }
+                <button onclick={on_reset}>{ tr("Reset to defaults") }</button>
+                <button onclick={props.on_close.reform(|_| ())}>{ tr("Close") }</button>
+            </div>
+        </div>
+    }
+}
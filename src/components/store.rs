@@ -0,0 +1,83 @@
+use yew::html::Scope;
+use yew::{function_component, hook, html, use_context, use_effect_with, use_memo, use_state_eq, Callback, Children, Component, ContextProvider, Html, Properties};
+use crate::sl::settings::{save_settings, Settings};
+use crate::sl::store::{AppState, Store};
+
+/// The store of the surrounding `StoreProvider`, or a detached store with the state restored from local
+/// storage when there is none, e.g. when a page is rendered on its own. Changes to a detached store are
+/// not saved.
+#[hook]
+pub fn use_store() -> Store {
+    let store = use_context::<Store>();
+    let detached = use_memo((), |_| Store::new(AppState::load()));
+    store.unwrap_or_else(|| (*detached).clone())
+}
+
+/// Selects a part of the app-wide state, re-rendering the component only when that part changes.
+#[hook]
+pub fn use_selector<T, F>(selector: F) -> T
+where
+    T: PartialEq + Clone + 'static,
+    F: Fn(&AppState) -> T + 'static,
+{
+    let store = use_store();
+    let selected = use_state_eq(|| selector(&store.state()));
+
+    {
+        let selected = selected.clone();
+        use_effect_with(store, move |store| {
+            selected.set(selector(&store.state()));
+            let subscription = store.select(selector, Callback::from(move |value| selected.set(value)));
+            move || drop(subscription)
+        });
+    }
+
+    (*selected).clone()
+}
+
+/// The store for a struct component, from the surrounding `StoreProvider` or detached like with `use_store`.
+pub fn scope_store<C: Component>(link: &Scope<C>) -> Store {
+    link.context::<Store>(Callback::noop())
+        .map(|(store, _)| store)
+        .unwrap_or_else(|| Store::new(AppState::load()))
+}
+
+/// Sets the `lang` of the document, so screen readers pronounce the interface in the right language.
+fn set_document_language(language: &str) {
+    if let Some(root) = web_sys::window().and_then(|window| window.document()).and_then(|document| document.document_element()) {
+        root.set_attribute("lang", language).unwrap_or_default();
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct StoreProviderProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// The `StoreProvider` component makes the app-wide `Store` available to every page.
+///
+/// The state is restored from local storage once, when the application starts, and kept while the
+/// learner navigates. The provided `Store` never changes, so the provider does not re-render the pages:
+/// function components subscribe to the part of the state they show with `use_selector`, struct
+/// components with `Store::select`. The settings are saved whenever they change, and the interface
+/// language is set as the `lang` of the document.
+#[function_component(StoreProvider)]
+pub fn store_provider(props: &StoreProviderProps) -> Html {
+    let store = use_memo((), |_| Store::new(AppState::load()));
+
+    use_effect_with(store.clone(), |store| {
+        set_document_language(&store.state().settings.language);
+        let subscription = store.select(|state| state.settings.clone(), Callback::from(|settings: Settings| {
+            save_settings(&settings).unwrap_or_default();
+            set_document_language(&settings.language);
+        }));
+        move || drop(subscription)
+    });
+
+    html! {
+        <ContextProvider<Store> context={(*store).clone()}>
+            { props.children.clone() }
+        </ContextProvider<Store>>
+    }
+}
@@ -0,0 +1,106 @@
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Event, HtmlSelectElement, MediaQueryList};
+use yew::{function_component, html, use_effect_with, use_state, Callback, Children, Html, Properties};
+use crate::components::store::{use_selector, use_store};
+use crate::sl::i18n::translate;
+use crate::sl::store::Action;
+use crate::sl::theme::{FontSize, Theme, ThemeSettings, DARK_SCHEME_QUERY};
+
+/// The browser's dark colour scheme media query, `None` outside a browser.
+fn dark_scheme_query() -> Option<MediaQueryList> {
+    web_sys::window()?.match_media(DARK_SCHEME_QUERY).ok()?
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ThemeProviderProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// The `ThemeProvider` component applies the learner's theme to the whole application.
+///
+/// The colours and font sizes are CSS custom properties in `app.css`, selected by the `data-theme` and
+/// `data-font-size` attributes this component sets on the root element. The theme is part of the learner's
+/// `Settings` in the app-wide store, so the component re-renders only when the theme changes. Until the
+/// learner picks a theme, it follows `prefers-color-scheme`, including when the device switches between
+/// light and dark.
+#[function_component(ThemeProvider)]
+pub fn theme_provider(props: &ThemeProviderProps) -> Html {
+    let settings = use_selector(|state| state.settings.theme);
+    let prefers_dark = use_state(|| dark_scheme_query().is_some_and(|query| query.matches()));
+
+    {
+        let prefers_dark = prefers_dark.clone();
+        use_effect_with((), move |_| {
+            let query = dark_scheme_query();
+            let listener = Closure::<dyn Fn(Event)>::new(move |_: Event| {
+                prefers_dark.set(dark_scheme_query().is_some_and(|query| query.matches()));
+            });
+            if let Some(query) = &query {
+                query.add_event_listener_with_callback("change", listener.as_ref().unchecked_ref()).unwrap_or_default();
+            }
+
+            move || {
+                if let Some(query) = &query {
+                    query.remove_event_listener_with_callback("change", listener.as_ref().unchecked_ref()).unwrap_or_default();
+                }
+            }
+        });
+    }
+
+    use_effect_with((settings, *prefers_dark), |(settings, prefers_dark)| {
+        if let Some(root) = web_sys::window().and_then(|window| window.document()).and_then(|document| document.document_element()) {
+            root.set_attribute("data-theme", settings.theme.attribute(*prefers_dark)).unwrap_or_default();
+            root.set_attribute("data-font-size", settings.font_size.attribute()).unwrap_or_default();
+        }
+    });
+
+    html! {
+        <>{ props.children.clone() }</>
+    }
+}
+
+/// The `ThemePicker` component lets the learner choose the theme and the font size.
+///
+/// The choice is dispatched to the app-wide store, whose provider saves it with the other settings.
+#[function_component(ThemePicker)]
+pub fn theme_picker() -> Html {
+    let store = use_store();
+    let current = use_selector(|state| state.settings.theme);
+    let lang = use_selector(|state| state.settings.language.clone());
+
+    let on_theme = {
+        let store = store.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+            let theme = Theme::ALL.get(target.selected_index().max(0) as usize).copied().unwrap_or_default();
+            store.dispatch(Action::ChangeTheme(ThemeSettings { theme, ..current }));
+        })
+    };
+
+    let on_font_size = Callback::from(move |e: Event| {
+        let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+        let font_size = FontSize::ALL.get(target.selected_index().max(0) as usize).copied().unwrap_or_default();
+        store.dispatch(Action::ChangeTheme(ThemeSettings { font_size, ..current }));
+    });
+
+    html! {
+        <div class="filters theme-picker">
+            <label>{ translate(&lang, "Theme") }{ " " }
+                <select onchange={on_theme}>
+                    { for Theme::ALL.iter().map(|theme| html! {
+                        <option selected={*theme == current.theme}>{ translate(&lang, theme.label()) }</option>
+                    }) }
+                </select>
+            </label>
+            <label>{ translate(&lang, "Text size") }{ " " }
+                <select onchange={on_font_size}>
+                    { for FontSize::ALL.iter().map(|size| html! {
+                        <option selected={*size == current.font_size}>{ translate(&lang, size.label()) }</option>
+                    }) }
+                </select>
+            </label>
+        </div>
+    }
+}
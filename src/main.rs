@@ -2,6 +2,9 @@
 
 use yew::{function_component, html, Html};
 use yew_router::prelude::*;
+use yew_study::components::shortcuts::ShortcutsProvider;
+use yew_study::components::store::StoreProvider;
+use yew_study::components::theme::ThemeProvider;
 use yew_study::route::{switch, Route};
 
 /// The `Main` component serving as the root of the Yew-based web application.
@@ -20,6 +23,14 @@ use yew_study::route::{switch, Route};
 ///   a consistent layout structure across different pages. This can be expanded to include
 ///   site-wide elements like navigation bars or footers.
 /// - **I18N Support**: Language models are loaded and usable throughout the entire component set.
+/// - **Keyboard Shortcuts**: The `ShortcutsProvider` listens to the keyboard for every page, handling the
+///   global shortcuts such as `g p` for the profile and `?` for the help, and handing page shortcuts to the
+///   page that registered them.
+/// - **App-Wide State**: The `StoreProvider` shares a `Store` with every page, holding the selected learner,
+///   their cached statistics, their `Settings` and an unfinished study session, so they survive navigation.
+///   Pages subscribe to the parts they show and only re-render when those change.
+/// - **Themes**: The `ThemeProvider` applies the learner's light, dark or high-contrast theme and text size,
+///   following the device's colour scheme until the learner picks a theme.
 ///
 /// ## Usage:
 /// The `Main` component is used as the entry point for rendering the application's UI.
@@ -29,6 +40,14 @@ use yew_study::route::{switch, Route};
 /// ## Example Routes:
 /// - `/`: Renders the `Home` component as the landing page.
 /// - `/study`: Renders the `Study` page for vocabulary activities.
+/// - `/summary`: Renders the `Summary` page at the end of a study session.
+/// - `/vocab`: Renders the `Vocab` browser listing the learner's vocabulary.
+/// - `/vocab/:vocab_study_id`: Renders the `VocabDetail` page for a single word.
+/// - `/add-word`: Renders the `AddVocab` form for adding a new word.
+/// - `/import`: Renders the `Import` page for adding words from a CSV, TSV or Anki text file.
+/// - `/profile`: Renders the `Profile` page with the learner's goals, streaks and study calendar.
+/// - `/settings`: Renders the `SettingsPage` with the learner's study and display preferences.
+/// - `/debug/schedule`: Renders the `ScheduleDebug` view of the client-side review schedule.
 /// - `/404`: Renders the `PageNotFound` component for unmatched routes.
 ///
 /// Note: To add or modify routes, adjust the `Route` enum and the `switch` function accordingly.
@@ -36,9 +55,15 @@ use yew_study::route::{switch, Route};
 fn app() -> Html {
     html! {
         <BrowserRouter>
-                <main>
-                    <Switch<Route> render={switch} />
-                </main>
+            <StoreProvider>
+                <ThemeProvider>
+                    <ShortcutsProvider>
+                        <main>
+                            <Switch<Route> render={switch} />
+                        </main>
+                    </ShortcutsProvider>
+                </ThemeProvider>
+            </StoreProvider>
         </BrowserRouter>
     }
 }
@@ -61,7 +86,11 @@ fn app() -> Html {
 ///
 /// ## Pages:
 /// - **Home**: The landing page of the application, accessible at the root `/` path.
-/// - **Study**: A study page to engage with vocabulary activities, accessible at `/study`.
+/// - **Study**: A study page to engage with vocabulary activities, accessible at `/study`. The session
+///   length can be set with `/study?items=20` or `/study?minutes=5`.
+/// - **Summary**: The results of the last study session, accessible at `/summary`.
+/// - **Vocab**: A sortable, filterable table of the learner's vocabulary, accessible at `/vocab`.
+/// - **VocabDetail**: Everything about a single word, accessible at `/vocab/:vocab_study_id`.
 /// - **NotFound**: A fallback page for unmatched routes, providing user feedback for broken or
 ///   incorrect URLs.
 ///
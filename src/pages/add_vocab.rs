@@ -0,0 +1,260 @@
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, InputEvent};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+use crate::components::error_panel::ErrorPanel;
+use crate::components::store::scope_store;
+use crate::route::Route;
+use crate::sl::gql::FetchError;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::new_vocab::{create_vocab, remember_challenges, FieldError, NewVocab, VocabField};
+use crate::sl::session::{KNOWN_CHALLENGES_STORAGE_KEY, NEW_WORDS_STORAGE_KEY};
+use crate::sl::storage::{load_json, save_json, StorageArea};
+use crate::sl::store::Store;
+use crate::sl::study::Challenge;
+use crate::sl::vocab::LANGUAGES;
+
+/// Messages handled by the `AddVocab` page.
+///
+/// ## Variants:
+/// - `Update(VocabField, String)`: Replaces the value of a form field.
+/// - `Submit`: Validates the form, checks for duplicates and submits the new word.
+/// - `AddAnyway`: Submits the new word even though it looks like a duplicate.
+/// - `Created(Challenge)`: Queues the created word for the next study batch and clears the form.
+/// - `FetchError(FetchError)`: Explains a failed request with an `ErrorPanel`.
+pub enum Msg {
+    Update(VocabField, String),
+    Submit,
+    AddAnyway,
+    Created(Challenge),
+    FetchError(FetchError),
+}
+
+/// The `AddVocab` page, a form to add a new word the learner wants to study.
+///
+/// The form is validated before it is submitted through the `createVocab` mutation. Words are compared
+/// with the challenges the learner has already seen in this browser session, and a likely duplicate has to
+/// be confirmed before it is added. A created word is queued in session storage so the study page puts
+/// it at the front of the next batch it fetches.
+///
+/// ## Fields:
+/// - `vocab`: The values of the form.
+/// - `errors`: The validation errors of the last submit.
+/// - `duplicate`: A known challenge that looks like the word being added, awaiting confirmation.
+/// - `saving`: Whether the word is being submitted.
+/// - `added`: The words added since the page was opened.
+/// - `error`: The failure to explain, if any.
+/// - `store`: The app-wide store holding the selected learner and the cached query results.
+/// - `lang`: The interface language.
+pub struct AddVocab {
+    vocab: NewVocab,
+    errors: Vec<FieldError>,
+    duplicate: Option<Challenge>,
+    saving: bool,
+    added: Vec<Challenge>,
+    error: Option<FetchError>,
+    store: Store,
+    lang: String,
+}
+
+impl AddVocab {
+
+    /// Translates an interface text into the learner's interface language.
+    fn tr(&self, text: &str) -> String {
+        translate(&self.lang, text)
+    }
+
+    /// Submits the form, reporting back with `Msg::Created` or `Msg::FetchError`.
+    fn submit(&mut self, link: html::Scope<Self>, awesome_id: i32) {
+        let vocab = self.vocab.clone();
+        self.saving = true;
+        self.duplicate = None;
+        self.error = None;
+
+        let store = self.store.clone();
+        spawn_local(async move {
+            match create_vocab(&store, awesome_id, vocab).await {
+                Ok(challenge) => link.send_message(Msg::Created(challenge)),
+                Err(err) => link.send_message(Msg::FetchError(err)),
+            }
+        });
+    }
+
+    /// The validation errors of a field.
+    fn field_errors(&self, field: VocabField) -> Html {
+        self.errors.iter().filter(|error| error.field == field).map(|error| html! {
+            <p class="error">{ self.tr(&error.message) }</p>
+        }).collect()
+    }
+
+    /// A labelled text input bound to a field.
+    fn text_input(&self, ctx: &Context<Self>, label: &str, field: VocabField, value: &str) -> Html {
+        let on_input = ctx.link().callback(move |e: InputEvent| {
+            let event: Event = e.dyn_into().unwrap_throw();
+            let target: HtmlInputElement = event.target().unwrap_throw().dyn_into().unwrap_throw();
+            Msg::Update(field, target.value())
+        });
+
+        html! {
+            <div class="field">
+                <label>{ self.tr(label) }{ " " }
+                    <input type="text" value={value.to_string()} oninput={on_input} />
+                </label>
+                { self.field_errors(field) }
+            </div>
+        }
+    }
+
+    /// A labelled language select bound to a field.
+    fn language_select(&self, ctx: &Context<Self>, label: &str, field: VocabField, selected: &str) -> Html {
+        let on_change = ctx.link().callback(move |e: Event| {
+            let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+            Msg::Update(field, target.value())
+        });
+
+        html! {
+            <div class="field">
+                <label>{ self.tr(label) }{ " " }
+                    <select onchange={on_change}>
+                        { for LANGUAGES.iter().map(|(code, _)| html! {
+                            <option value={*code} selected={selected == *code}>{ self.tr(code) }</option>
+                        }) }
+                    </select>
+                </label>
+                { self.field_errors(field) }
+            </div>
+        }
+    }
+}
+
+impl Component for AddVocab {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        Self {
+            vocab: NewVocab::default(),
+            errors: Vec::new(),
+            duplicate: None,
+            saving: false,
+            added: Vec::new(),
+            error: None,
+            lang: store.state().settings.language.clone(),
+            store,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Update(field, value) => {
+                self.vocab.set(field, value);
+                self.duplicate = None;
+                true
+            }
+            Msg::Submit => {
+                if self.saving {
+                    return false;
+                }
+
+                self.errors = self.vocab.validate();
+                if !self.errors.is_empty() {
+                    return true;
+                }
+
+                let known: Vec<Challenge> = load_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY).unwrap_or_default();
+                match self.vocab.find_duplicate(&known) {
+                    Some(duplicate) => self.duplicate = Some(duplicate.clone()),
+                    None => self.submit(ctx.link().clone(), self.store.state().learner_id()),
+                }
+                true
+            }
+            Msg::AddAnyway => {
+                if !self.saving {
+                    self.submit(ctx.link().clone(), self.store.state().learner_id());
+                }
+                true
+            }
+            Msg::Created(challenge) => {
+                let mut new_words: Vec<Challenge> = load_json(StorageArea::Session, NEW_WORDS_STORAGE_KEY).unwrap_or_default();
+                new_words.push(challenge.clone());
+                let mut known = load_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY).unwrap_or_default();
+                remember_challenges(&mut known, std::slice::from_ref(&challenge));
+
+                if let Err(err) = save_json(StorageArea::Session, NEW_WORDS_STORAGE_KEY, &new_words)
+                    .and_then(|_| save_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY, &known)) {
+                    self.error = Some(err.into());
+                }
+
+                // Keep the language pair, learners usually add several words of the same pair in a row.
+                self.vocab = NewVocab {
+                    known_lang_code: self.vocab.known_lang_code.clone(),
+                    learning_lang_code: self.vocab.learning_lang_code.clone(),
+                    ..NewVocab::default()
+                };
+                self.errors = Vec::new();
+                self.saving = false;
+                self.added.push(challenge);
+                true
+            }
+            Msg::FetchError(err) => {
+                self.saving = false;
+                self.error = Some(err);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_submit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::Submit
+        });
+
+        let added: Html = self.added.iter().map(|challenge| html! {
+            <li>
+                <Link<Route> to={Route::VocabDetail { vocab_study_id: challenge.vocab_study_id }}>
+                    { format!("{} → {}", challenge.first_lang, challenge.learning) }
+                </Link<Route>>
+            </li>
+        }).collect();
+
+        html! {
+            <section>
+                <h2>{ self.tr("Add a Word") }</h2>
+                <form class="add-vocab" onsubmit={on_submit}>
+                    { self.language_select(ctx, "From", VocabField::KnownLang, &self.vocab.known_lang_code) }
+                    { self.language_select(ctx, "To", VocabField::LearningLang, &self.vocab.learning_lang_code) }
+                    { self.text_input(ctx, "Word", VocabField::FirstLang, &self.vocab.first_lang) }
+                    { self.text_input(ctx, "Translation", VocabField::Learning, &self.vocab.learning) }
+                    { self.text_input(ctx, "Part of speech", VocabField::Pos, &self.vocab.pos) }
+                    { self.text_input(ctx, "Infinitive", VocabField::Infinitive, &self.vocab.infinitive) }
+                    { self.text_input(ctx, "Hint", VocabField::Hint, &self.vocab.hint) }
+                    <button type="submit" disabled={self.saving}>
+                        { self.tr(if self.saving { "Adding..." } else { "Add word" }) }
+                    </button>
+                </form>
+
+                if let Some(duplicate) = &self.duplicate {
+                    <div class="duplicate">
+                        <p>{ translate_format(&self.lang, "You are already studying \"{}\" → \"{}\".", &[&duplicate.first_lang, &duplicate.learning]) }</p>
+                        <button onclick={ctx.link().callback(|_| Msg::AddAnyway)}>{ self.tr("Add anyway") }</button>
+                    </div>
+                }
+
+                if let Some(error) = self.error.clone() {
+                    <ErrorPanel {error}/>
+                }
+
+                if !self.added.is_empty() {
+                    <h3>{ self.tr("Added") }</h3>
+                    <p>{ self.tr("These words will come up in your next study batch.") }</p>
+                    <ul>{ added }</ul>
+                    <Link<Route> to={Route::Study}>{ self.tr("Study them now") }</Link<Route>>
+                }
+                <Link<Route> to={Route::Vocab}>{ self.tr("Back to my words") }</Link<Route>>
+            </section>
+        }
+    }
+}
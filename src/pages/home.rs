@@ -1,30 +1,130 @@
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, InputEvent};
 use yew::prelude::*;
 use yew_router::prelude::Link;
+use yew_router::scope_ext::RouterScopeExt;
+use crate::components::error_panel::ErrorPanel;
+use crate::components::store::scope_store;
 use crate::route::Route;
+use crate::sl::awesome::{fetch_awesome_profile, forget_learner, peek_awesome_profile, select_learner, AwesomeProfile};
+use crate::sl::goals::{current_streak, day_end_ms, day_number, ActivityLog, DailyGoal, ACTIVITY_STORAGE_KEY, GOAL_STORAGE_KEY};
+use crate::sl::gql::{ErrorKind, FetchError};
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::scheduler::{num_due_by, Schedule, SCHEDULE_STORAGE_KEY};
+use crate::sl::session::{SessionQuery, DECK_STORAGE_KEY, RECENT_MISTAKES_STORAGE_KEY};
+use crate::sl::storage::{learner_key, load_json, save_json, StorageArea};
+use crate::sl::store::{Action, Store, Subscription};
+use crate::sl::study::Challenge;
 
-pub struct Home;
+/// Messages handled by the `Home` page.
+///
+/// ## Variants:
+/// - `StoreChanged`: Re-renders after the learner, their statistics or the interface language changed.
+/// - `ProfileLoaded(AwesomeProfile)`: Keeps the fetched statistics of the selected learner in the store.
+/// - `UpdateLearnerId(String)`: Updates the learner number typed by an anonymous visitor.
+/// - `SelectLearner`: Checks the typed learner number by fetching their statistics.
+/// - `LearnerFound(AwesomeProfile)`: Selects the checked learner and shows their dashboard.
+/// - `LearnerNotFound(i32)`: Tells the visitor that no learner has the typed number.
+/// - `ForgetLearner`: Forgets the selected learner, going back to the welcome content.
+/// - `Start(SessionQuery)`: Starts a study session of the given length.
+/// - `PractiseMistakes`: Starts a study session made up of the recent mistakes.
+/// - `FetchError(FetchError)`: Explains a failed request with an `ErrorPanel`.
+pub enum Msg {
+    StoreChanged,
+    ProfileLoaded(AwesomeProfile),
+    UpdateLearnerId(String),
+    SelectLearner,
+    LearnerFound(AwesomeProfile),
+    LearnerNotFound(i32),
+    ForgetLearner,
+    Start(SessionQuery),
+    PractiseMistakes,
+    FetchError(FetchError),
+}
+
+/// The study modes offered as quick-start buttons, with their labels to translate.
+fn quick_starts() -> Vec<(&'static str, SessionQuery)> {
+    vec![
+        ("Quick: 5 words", SessionQuery::items(5)),
+        ("Standard: 10 words", SessionQuery::items(10)),
+        ("Long: 20 words", SessionQuery::items(20)),
+        ("Timed: 5 minutes", SessionQuery::minutes(5)),
+    ]
+}
 
 /// The `Home` component of the application, representing the homepage.
 ///
-/// This component displays the welcome message, a logo, and a brief introduction to the application. It also
-/// includes a link to navigate to the `Study` page. The component is stateless, with no message handling or properties.
+/// For anonymous visitors, it displays the welcome message, a logo and a brief introduction to the
+/// application, with a link to the `Study` page and a field to select their learner number. Once a learner
+/// is selected, it becomes their dashboard: their `AwesomeProfile` statistics, the words due today in the
+/// review schedule, their streak, their recent mistakes and quick-start buttons for each study mode.
 ///
-/// ## Implementation Details:
-/// - `create`: Initializes the component. As there are no properties or state, it simply returns an instance of `Self`.
-/// - `view`: Defines the HTML structure of the homepage, including static text content, an image, and a navigation link.
+/// The selected learner and their statistics are kept in the app-wide store. Cached statistics are shown
+/// right away, and only fetched again once they went stale, not every time the learner comes back to this page.
 ///
-/// ## Usage:
-/// This component is intended to be rendered as the main content of the application's root route. It provides
-/// users with an overview of the application and an entry point to the `Study` page.
-impl Component for Home {
-    type Message = ();
-    type Properties = ();
+/// ## Fields:
+/// - `store`: The app-wide store holding the selected learner and their statistics.
+/// - `learner_input`: The learner number typed by an anonymous visitor.
+/// - `mistakes`: The challenges the learner missed most recently.
+/// - `err_msg`: A message about the typed learner number, empty when there is none.
+/// - `error`: The failure to explain, if any.
+/// - `_subscription`: Keeps the page subscribed to the parts of the store it shows.
+pub struct Home {
+    store: Store,
+    learner_input: String,
+    mistakes: Vec<Challenge>,
+    err_msg: String,
+    error: Option<FetchError>,
+    _subscription: Subscription,
+}
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self
+impl Home {
+
+    /// Fetches the statistics of a learner, reporting back with `Msg::ProfileLoaded` or `Msg::FetchError`.
+    ///
+    /// Cached statistics are reported right away, and only fetched again when they are stale.
+    fn load_profile(&self, link: html::Scope<Self>, awesome_id: i32) {
+        if let Some(cached) = peek_awesome_profile(&self.store, awesome_id) {
+            link.send_message(Msg::ProfileLoaded(cached.value));
+            if !cached.stale {
+                return;
+            }
+        }
+
+        let store = self.store.clone();
+        spawn_local(async move {
+            match fetch_awesome_profile(&store, awesome_id).await {
+                Ok(profile) => link.send_message(Msg::ProfileLoaded(profile)),
+                Err(err) => link.send_message(Msg::FetchError(err)),
+            }
+        });
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    /// Saves `deck` for the study page, if any, and navigates to it with `query`.
+    fn start(&mut self, ctx: &Context<Self>, query: SessionQuery, deck: Option<Vec<Challenge>>) -> bool {
+        if let Some(deck) = deck {
+            if let Err(err) = save_json(StorageArea::Session, DECK_STORAGE_KEY, &deck) {
+                self.error = Some(err.into());
+                return true;
+            }
+        }
+
+        if let Some(navigator) = ctx.link().navigator() {
+            navigator.push_with_query(&Route::Study, &query).unwrap_or_default();
+        }
+        false
+    }
+
+    /// The welcome content shown to anonymous visitors.
+    fn welcome(&self, ctx: &Context<Self>) -> Html {
+        let on_input = ctx.link().callback(|e: InputEvent| {
+            let event: Event = e.dyn_into().unwrap_throw();
+            let target: HtmlInputElement = event.target().unwrap_throw().dyn_into().unwrap_throw();
+            Msg::UpdateLearnerId(target.value())
+        });
+        let lang = self.store.state().settings.language.clone();
+
         html! {
             <section>
                 <div>
@@ -32,14 +132,202 @@ impl Component for Home {
                         <figure>
                             <img src="logo.jpg" class="logo" />
                         </figure>
-                        <h1>{ "Welcome to Grow My Vocab!" }</h1>
-                        <p>{ "Expand your vocabulary with fun and engaging exercises every day." }</p>
+                        <h1>{ translate(&lang, "Welcome to Grow My Vocab!") }</h1>
+                        <p>{ translate(&lang, "Expand your vocabulary with fun and engaging exercises every day.") }</p>
                     </div>
                 </div>
                 <Link<Route> classes={classes!("navbar-item")} to={Route::Study}>
-                            { "Learn" }
+                            { translate(&lang, "Learn") }
                 </Link<Route>>
+                <div class="filters">
+                    <label>{ translate(&lang, "Already learning? Your learner number") }{ " " }
+                        <input type="number" min="1" value={self.learner_input.clone()} oninput={on_input} />
+                    </label>
+                    <button onclick={ctx.link().callback(|_| Msg::SelectLearner)}>{ translate(&lang, "Continue") }</button>
+                </div>
+                if !self.err_msg.is_empty() {
+                    <p class="error">{ self.err_msg.clone() }</p>
+                }
+                if let Some(error) = self.error.clone() {
+                    <ErrorPanel {error}/>
+                }
+            </section>
+        }
+    }
+
+    /// The dashboard of the selected learner.
+    fn dashboard(&self, ctx: &Context<Self>) -> Html {
+        let now = js_sys::Date::now();
+        let timezone_offset = js_sys::Date::new_0().get_timezone_offset();
+        let today = day_number(now, timezone_offset);
+
+        let awesome_id = self.store.state().learner_id();
+        let goal: DailyGoal = load_json(StorageArea::Local, &learner_key(GOAL_STORAGE_KEY, awesome_id)).unwrap_or_default();
+        let log: ActivityLog = load_json(StorageArea::Local, &learner_key(ACTIVITY_STORAGE_KEY, awesome_id)).unwrap_or_default();
+        let schedule: Schedule = load_json(StorageArea::Local, &learner_key(SCHEDULE_STORAGE_KEY, awesome_id)).unwrap_or_default();
+        let activity = log.get(&today).copied().unwrap_or_default();
+        let profile = self.store.state().profile.clone();
+        let lang = self.store.state().settings.language.clone();
+        let tr = |text: &str| translate(&lang, text);
+
+        let stats = match &profile {
+            Some(profile) => html! {
+                <table class="summary">
+                    <tr><th>{ tr("Words known") }</th><td>{ profile.num_known }</td></tr>
+                    <tr><th>{ tr("Correct answers") }</th><td>{ profile.num_correct }</td></tr>
+                    <tr><th>{ tr("Incorrect answers") }</th><td>{ profile.num_incorrect }</td></tr>
+                    <tr><th>{ tr("Accuracy") }</th><td>{ format!("{:.0}%", profile.total_percentage) }</td></tr>
+                </table>
+            },
+            None if self.error.is_none() => html! { <p>{ tr("Loading...") }</p> },
+            None => html! {},
+        };
+
+        let mistakes: Html = self.mistakes.iter().map(|challenge| html! {
+            <li>
+                <Link<Route> to={Route::VocabDetail { vocab_study_id: challenge.vocab_study_id }}>
+                    { challenge.first_lang.clone() }
+                </Link<Route>>
+            </li>
+        }).collect();
+
+        let name = profile.as_ref().map(|profile| profile.name.clone()).unwrap_or_default();
+
+        html! {
+            <section>
+                <h1>{ if name.is_empty() { tr("Welcome back!") } else { translate_format(&lang, "Welcome back, {}!", &[&name]) } }</h1>
+                { stats }
+                <table class="summary">
+                    <tr><th>{ tr("Due today") }</th><td>{ num_due_by(&schedule, day_end_ms(today, timezone_offset)) }</td></tr>
+                    <tr><th>{ tr("Today") }</th><td>{ translate_format(&lang, "{} items of {}", &[&activity.items, &goal.display(&lang)]) }</td></tr>
+                    <tr><th>{ tr("Current streak") }</th><td>{ translate_format(&lang, "{} days", &[&current_streak(&log, &goal, today)]) }</td></tr>
+                </table>
+
+                if let Some(error) = self.error.clone() {
+                    <ErrorPanel {error}/>
+                }
+
+                <h3>{ tr("Start studying") }</h3>
+                <div class="quick-start">
+                    { for quick_starts().into_iter().map(|(label, query)| html! {
+                        <button onclick={ctx.link().callback(move |_| Msg::Start(query.clone()))}>{ tr(label) }</button>
+                    }) }
+                </div>
+
+                if !self.mistakes.is_empty() {
+                    <h3>{ tr("Recent mistakes") }</h3>
+                    <ul class="summary">{ mistakes }</ul>
+                    <button onclick={ctx.link().callback(|_| Msg::PractiseMistakes)}>{ tr("Practise mistakes") }</button>
+                }
+
+                <div>
+                    <Link<Route> classes={classes!("navbar-item")} to={Route::Vocab}>{ tr("My Words") }</Link<Route>>
+                    <Link<Route> classes={classes!("navbar-item")} to={Route::AddVocab}>{ tr("Add a Word") }</Link<Route>>
+                    <Link<Route> classes={classes!("navbar-item")} to={Route::Profile}>{ tr("My Progress") }</Link<Route>>
+                </div>
+                <button onclick={ctx.link().callback(|_| Msg::ForgetLearner)}>{ tr("Not you? Switch learner") }</button>
             </section>
         }
     }
 }
+
+impl Component for Home {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        let mistakes_key = learner_key(RECENT_MISTAKES_STORAGE_KEY, store.state().learner_id());
+        let subscription = store.select(
+            |state| (state.learner, state.profile.clone(), state.settings.language.clone()),
+            ctx.link().callback(|_| Msg::StoreChanged),
+        );
+
+        Self {
+            store,
+            learner_input: "".to_string(),
+            mistakes: load_json(StorageArea::Local, &mistakes_key).unwrap_or_default(),
+            err_msg: "".to_string(),
+            error: None,
+            _subscription: subscription,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::StoreChanged => true,
+            Msg::ProfileLoaded(profile) => {
+                self.store.dispatch(Action::ProfileLoaded(profile));
+                false
+            }
+            Msg::UpdateLearnerId(value) => {
+                self.learner_input = value;
+                false
+            }
+            Msg::SelectLearner => {
+                let Ok(awesome_id) = self.learner_input.trim().parse::<i32>() else {
+                    self.err_msg = translate(&self.store.state().settings.language, "Enter your learner number.");
+                    return true;
+                };
+
+                let link = ctx.link().clone();
+                let store = self.store.clone();
+                spawn_local(async move {
+                    match fetch_awesome_profile(&store, awesome_id).await {
+                        Ok(profile) => link.send_message(Msg::LearnerFound(profile)),
+                        // The backend answers with no person for an unknown learner number
+                        Err(err) if matches!(err.kind, ErrorKind::Decode | ErrorKind::Validation) => {
+                            link.send_message(Msg::LearnerNotFound(awesome_id))
+                        }
+                        Err(err) => link.send_message(Msg::FetchError(err)),
+                    }
+                });
+                self.err_msg = "".to_string();
+                self.error = None;
+                true
+            }
+            Msg::LearnerFound(profile) => {
+                if let Err(err) = select_learner(profile.id) {
+                    self.error = Some(err.into());
+                    return true;
+                }
+                self.store.dispatch(Action::SelectLearner(profile.id));
+                self.store.dispatch(Action::ProfileLoaded(profile));
+                *self = <Self as Component>::create(ctx);
+                true
+            }
+            Msg::LearnerNotFound(awesome_id) => {
+                self.err_msg = translate_format(&self.store.state().settings.language, "There is no learner number {}.", &[&awesome_id]);
+                true
+            }
+            Msg::ForgetLearner => {
+                forget_learner();
+                self.store.dispatch(Action::ForgetLearner);
+                *self = <Self as Component>::create(ctx);
+                true
+            }
+            Msg::Start(query) => self.start(ctx, query, None),
+            Msg::PractiseMistakes => {
+                let deck = self.mistakes.clone();
+                self.start(ctx, SessionQuery::items(deck.len()), Some(deck))
+            }
+            Msg::FetchError(err) => {
+                self.error = Some(err);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        match self.store.state().learner {
+            Some(_) => self.dashboard(ctx),
+            None => self.welcome(ctx),
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if let (true, Some(awesome_id)) = (first_render, self.store.state().learner) {
+            self.load_profile(ctx.link().clone(), awesome_id);
+        }
+    }
+}
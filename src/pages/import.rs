@@ -0,0 +1,333 @@
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+use crate::components::error_panel::ErrorPanel;
+use crate::components::store::scope_store;
+use crate::route::Route;
+use crate::sl::gql::FetchError;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::import::{map_rows, parse_import, valid_batches, ImportColumn, ImportRow, ImportTable};
+use crate::sl::new_vocab::{create_vocab_batch, remember_challenges, NewVocab};
+use crate::sl::session::KNOWN_CHALLENGES_STORAGE_KEY;
+use crate::sl::storage::{load_json, save_json, StorageArea};
+use crate::sl::store::Store;
+use crate::sl::study::Challenge;
+use crate::sl::vocab::LANGUAGES;
+
+/// Number of parsed rows shown in the preview.
+const PREVIEW_ROWS: usize = 50;
+
+/// Messages handled by the `Import` page.
+///
+/// ## Variants:
+/// - `FileLoaded(String, String)`: Parses a chosen file, holding its name and text.
+/// - `NoFile`: Tells the learner that no file was chosen.
+/// - `MapColumn(usize, ImportColumn)`: Changes the field a column is read into.
+/// - `KnownLang(String)`, `LearningLang(String)`: Changes the language pair of the imported words.
+/// - `Submit`: Submits the valid rows to the backend, one batch at a time.
+/// - `BatchDone(Vec<Challenge>)`: Records a created batch and submits the next one.
+/// - `FetchError(FetchError)`: Explains a failed request or file read with an `ErrorPanel` and stops the import.
+pub enum Msg {
+    FileLoaded(String, String),
+    NoFile,
+    MapColumn(usize, ImportColumn),
+    KnownLang(String),
+    LearningLang(String),
+    Submit,
+    BatchDone(Vec<Challenge>),
+    FetchError(FetchError),
+}
+
+/// The `Import` page, which adds the words of a CSV, TSV or Anki text export file.
+///
+/// The chosen file is read in the browser and parsed by `sl::import`. The columns are mapped to
+/// vocabulary fields, guessed from the header when the file has one, and can be changed before
+/// importing. The rows are previewed with their validation errors. Valid rows are submitted with the
+/// `createVocabBatch` mutation in batches of `IMPORT_BATCH_SIZE`, one batch after the other. When a batch
+/// fails, importing again only submits the rows after the words already created.
+///
+/// ## Fields:
+/// - `file_name`: The name of the chosen file.
+/// - `table`: The parsed file, `None` until a file is chosen.
+/// - `mapping`: The field each column is read into.
+/// - `known_lang_code`, `learning_lang_code`: The language pair of the imported words.
+/// - `pending`: The batches still to submit. Not empty while importing.
+/// - `imported`: The number of words of the file created so far, the first valid rows.
+/// - `err_msg`: Why the chosen file cannot be imported, empty when it can.
+/// - `error`: The failure to explain, if any.
+/// - `store`: The app-wide store holding the selected learner and the cached query results.
+/// - `lang`: The interface language.
+pub struct Import {
+    file_name: String,
+    table: Option<ImportTable>,
+    mapping: Vec<ImportColumn>,
+    known_lang_code: String,
+    learning_lang_code: String,
+    pending: Vec<Vec<NewVocab>>,
+    imported: usize,
+    err_msg: String,
+    error: Option<FetchError>,
+    store: Store,
+    lang: String,
+}
+
+impl Import {
+
+    /// Translates an interface text into the learner's interface language.
+    fn tr(&self, text: &str) -> String {
+        translate(&self.lang, text)
+    }
+
+    /// The rows of the parsed file mapped with the current mapping and language pair.
+    fn rows(&self) -> Vec<ImportRow> {
+        self.table.as_ref().map_or_else(Vec::new, |table| {
+            map_rows(table, &self.mapping, &self.known_lang_code, &self.learning_lang_code)
+        })
+    }
+
+    /// Submits the next pending batch, reporting back with `Msg::BatchDone` or `Msg::FetchError`.
+    fn submit_next(&self, link: html::Scope<Self>, awesome_id: i32) {
+        let Some(batch) = self.pending.first().cloned() else {
+            return;
+        };
+
+        let store = self.store.clone();
+        spawn_local(async move {
+            match create_vocab_batch(&store, awesome_id, batch).await {
+                Ok(created) => link.send_message(Msg::BatchDone(created)),
+                Err(err) => link.send_message(Msg::FetchError(err)),
+            }
+        });
+    }
+
+    /// A language select of the pair.
+    fn language_select(&self, selected: &str, on_change: Callback<Event>) -> Html {
+        html! {
+            <select onchange={on_change}>
+                { for LANGUAGES.iter().map(|(code, _)| html! {
+                    <option value={*code} selected={selected == *code}>{ self.tr(code) }</option>
+                }) }
+            </select>
+        }
+    }
+
+    /// The header row of the preview, with a select per column to choose the field it is read into.
+    fn mapping_header(&self, ctx: &Context<Self>) -> Html {
+        let header = self.table.as_ref().and_then(|table| table.header.clone()).unwrap_or_default();
+
+        self.mapping.iter().enumerate().map(|(index, selected)| {
+            let on_change = ctx.link().callback(move |e: Event| {
+                let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+                let column = ImportColumn::ALL.get(target.selected_index().max(0) as usize).copied()
+                    .unwrap_or(ImportColumn::Ignore);
+                Msg::MapColumn(index, column)
+            });
+
+            html! {
+                <th>
+                    if let Some(name) = header.get(index) {
+                        <span class="column-name">{ name.clone() }</span>
+                    }
+                    <select onchange={on_change}>
+                        { for ImportColumn::ALL.iter().map(|column| html! {
+                            <option selected={column == selected}>{ self.tr(column.label()) }</option>
+                        }) }
+                    </select>
+                </th>
+            }
+        }).collect()
+    }
+
+    fn preview(&self, ctx: &Context<Self>, rows: &[ImportRow]) -> Html {
+        let Some(table) = &self.table else {
+            return html! {};
+        };
+
+        let body: Html = table.rows.iter().zip(rows.iter()).take(PREVIEW_ROWS).map(|(line, row)| {
+            let errors: Vec<String> = row.errors.iter().map(|error| self.tr(&error.message)).collect();
+            html! {
+                <tr class={classes!((!row.is_valid()).then_some("invalid"))}>
+                    <td>{ row.line }</td>
+                    { for (0..self.mapping.len()).map(|index| html! {
+                        <td>{ line.cells.get(index).cloned().unwrap_or_default() }</td>
+                    }) }
+                    <td class="error">{ errors.join(" ") }</td>
+                </tr>
+            }
+        }).collect();
+
+        html! {
+            <table class="vocab import">
+                <thead>
+                    <tr>
+                        <th>{ self.tr("Line") }</th>
+                        { self.mapping_header(ctx) }
+                        <th>{ self.tr("Errors") }</th>
+                    </tr>
+                </thead>
+                <tbody>{ body }</tbody>
+            </table>
+        }
+    }
+}
+
+impl Component for Import {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        Self {
+            file_name: "".to_string(),
+            table: None,
+            mapping: Vec::new(),
+            known_lang_code: "en".to_string(),
+            learning_lang_code: "es".to_string(),
+            pending: Vec::new(),
+            imported: 0,
+            err_msg: "".to_string(),
+            error: None,
+            lang: store.state().settings.language.clone(),
+            store,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::FileLoaded(file_name, text) => {
+                self.imported = 0;
+                self.err_msg = "".to_string();
+                self.error = None;
+                match parse_import(&file_name, &text) {
+                    Ok(table) => {
+                        self.mapping = table.guess_mapping();
+                        self.table = Some(table);
+                    }
+                    Err(err) => {
+                        self.table = None;
+                        self.mapping = Vec::new();
+                        self.err_msg = err.message(&self.lang);
+                    }
+                }
+                self.file_name = file_name;
+                true
+            }
+            Msg::MapColumn(index, column) => {
+                if let Some(mapped) = self.mapping.get_mut(index) {
+                    *mapped = column;
+                }
+                true
+            }
+            Msg::KnownLang(code) => {
+                self.known_lang_code = code;
+                true
+            }
+            Msg::LearningLang(code) => {
+                self.learning_lang_code = code;
+                true
+            }
+            Msg::Submit => {
+                if !self.pending.is_empty() {
+                    return false;
+                }
+
+                self.pending = valid_batches(&self.rows(), self.imported);
+                self.error = None;
+                self.submit_next(ctx.link().clone(), self.store.state().learner_id());
+                true
+            }
+            Msg::BatchDone(created) => {
+                let mut known = load_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY).unwrap_or_default();
+                remember_challenges(&mut known, &created);
+                save_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY, &known).unwrap_or_default();
+
+                // Counted in rows of the file, so a retry knows where to resume
+                if !self.pending.is_empty() {
+                    self.imported += self.pending.remove(0).len();
+                }
+                self.submit_next(ctx.link().clone(), self.store.state().learner_id());
+                true
+            }
+            Msg::NoFile => {
+                self.err_msg = self.tr("No file was chosen.");
+                true
+            }
+            Msg::FetchError(err) => {
+                self.pending = Vec::new();
+                self.error = Some(err);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_file = ctx.link().callback_future(move |e: Event| async move {
+            let input: HtmlInputElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return Msg::NoFile;
+            };
+
+            match JsFuture::from(file.text()).await {
+                Ok(text) => Msg::FileLoaded(file.name(), text.as_string().unwrap_or_default()),
+                Err(err) => Msg::FetchError(err.into()),
+            }
+        });
+
+        let on_known = ctx.link().callback(|e: Event| {
+            let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+            Msg::KnownLang(target.value())
+        });
+        let on_learning = ctx.link().callback(|e: Event| {
+            let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+            Msg::LearningLang(target.value())
+        });
+
+        let rows = self.rows();
+        let num_valid = rows.iter().filter(|row| row.is_valid()).count();
+        let num_remaining = num_valid.saturating_sub(self.imported);
+        let importing = !self.pending.is_empty();
+
+        html! {
+            <section>
+                <h2>{ self.tr("Import Words") }</h2>
+                <p>{ self.tr("Choose a CSV or TSV file, or a deck exported from Anki as \"Notes in Plain Text\".") }</p>
+                <div class="filters">
+                    <label>{ self.tr("File") }{ " " }
+                        <input type="file" accept=".csv,.tsv,.tab,.txt" onchange={on_file} />
+                    </label>
+                    <label>{ self.tr("From") }{ " " }{ self.language_select(&self.known_lang_code, on_known) }</label>
+                    <label>{ self.tr("To") }{ " " }{ self.language_select(&self.learning_lang_code, on_learning) }</label>
+                </div>
+
+                if !self.err_msg.is_empty() {
+                    <p class="error">{ self.err_msg.clone() }</p>
+                }
+                if let Some(error) = self.error.clone() {
+                    <ErrorPanel {error}/>
+                }
+
+                if self.table.is_some() {
+                    <p>{ translate_format(&self.lang, "{}: {} rows, {} ready to import.", &[&self.file_name, &rows.len(), &num_valid]) }</p>
+                    if rows.len() > PREVIEW_ROWS {
+                        <p>{ translate_format(&self.lang, "Showing the first {} rows.", &[&PREVIEW_ROWS]) }</p>
+                    }
+                    { self.preview(ctx, &rows) }
+                    <button disabled={importing || num_remaining == 0} onclick={ctx.link().callback(|_| Msg::Submit)}>
+                        { if importing {
+                            translate_format(&self.lang, "Importing... {} of {}", &[&self.imported, &num_valid])
+                        } else {
+                            translate_format(&self.lang, "Import {} words", &[&num_remaining])
+                        } }
+                    </button>
+                }
+
+                if self.imported > 0 && !importing {
+                    <p>{ translate_format(&self.lang, "Imported {} words.", &[&self.imported]) }</p>
+                }
+                <Link<Route> to={Route::Vocab}>{ self.tr("Back to my words") }</Link<Route>>
+            </section>
+        }
+    }
+}
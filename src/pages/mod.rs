@@ -1,3 +1,11 @@
+pub mod add_vocab;
 pub mod home;
+pub mod import;
+pub mod schedule;
+pub mod settings;
 pub mod study;
-pub mod page_not_found;
\ No newline at end of file
+pub mod summary;
+pub mod vocab;
+pub mod vocab_detail;
+pub mod profile;
+pub mod page_not_found;
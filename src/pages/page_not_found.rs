@@ -1,4 +1,6 @@
 use yew::prelude::*;
+use crate::components::store::scope_store;
+use crate::sl::i18n::translate;
 
 pub struct PageNotFound;
 
@@ -25,16 +27,17 @@ impl Component for PageNotFound {
         Self
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let lang = scope_store(ctx.link()).state().settings.language.clone();
         html! {
             <section>
                 <div>
                     <div class="container">
                         <h1 class="title">
-                            { "Page not found" }
+                            { translate(&lang, "Page not found") }
                         </h1>
                         <h2 class="subtitle">
-                            { "Page page does not seem to exist" }
+                            { translate(&lang, "Page page does not seem to exist") }
                         </h2>
                     </div>
                 </div>
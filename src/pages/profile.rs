@@ -0,0 +1,225 @@
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+use crate::components::error_panel::ErrorPanel;
+use crate::components::export_buttons::ExportButtons;
+use crate::components::store::scope_store;
+use crate::route::Route;
+use crate::sl::goals::{current_streak, day_display, heatmap, local_day, longest_streak, ActivityLog, DailyGoal,
+                       ACTIVITY_STORAGE_KEY, GOAL_STORAGE_KEY, HEATMAP_WEEKS};
+use crate::sl::gql::FetchError;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::storage::{learner_key, load_json, save_json, StorageArea};
+use crate::sl::store::Store;
+use crate::sl::vocab::{fetch_vocab_page, VocabEntry, VocabPage, VocabQuery, WEAKEST_WORDS};
+
+/// Messages handled by the `Profile` page.
+///
+/// ## Variants:
+/// - `SetGoal(DailyGoal)`: Replaces the daily goal and saves it in local storage.
+/// - `WeakestLoaded(VocabPage)`: Shows the fetched words with the lowest accuracy.
+/// - `FetchError(FetchError)`: Explains a failed request with an `ErrorPanel`.
+pub enum Msg {
+    SetGoal(DailyGoal),
+    WeakestLoaded(VocabPage),
+    FetchError(FetchError),
+}
+
+/// The `Profile` page, showing the learner's daily goal, streaks and a calendar of their study activity.
+///
+/// The goal and the activity are kept in local storage for each learner: the study page adds every checked answer to the
+/// activity of its day. The calendar is a GitHub style heatmap of the last `HEATMAP_WEEKS` weeks, each day
+/// shaded by how much of the goal was reached. The words with the lowest accuracy link to their detail page, and
+/// the learner's whole vocabulary can be exported from here too.
+///
+/// ## Fields:
+/// - `store`: The app-wide store caching the learner's vocabulary.
+/// - `awesome_id`: The learner whose progress is shown.
+/// - `lang`: The interface language.
+/// - `goal`: The learner's daily goal.
+/// - `log`: The learner's study activity per day.
+/// - `weakest`: The `WEAKEST_WORDS` words with the lowest accuracy.
+/// - `error`: The failure to explain, if any.
+pub struct Profile {
+    store: Store,
+    awesome_id: i32,
+    lang: String,
+    goal: DailyGoal,
+    log: ActivityLog,
+    weakest: Vec<VocabEntry>,
+    error: Option<FetchError>,
+}
+
+impl Profile {
+
+    /// Translates an interface text into the learner's interface language.
+    fn tr(&self, text: &str) -> String {
+        translate(&self.lang, text)
+    }
+
+    /// The form to change the daily goal.
+    fn goal_form(&self, ctx: &Context<Self>) -> Html {
+        let goal = self.goal;
+        let (is_minutes, amount) = match goal {
+            DailyGoal::Items(items) => (false, items),
+            DailyGoal::Minutes(minutes) => (true, minutes),
+        };
+
+        let on_kind = ctx.link().callback(move |e: Event| {
+            let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+            match target.value().as_str() {
+                "minutes" => Msg::SetGoal(DailyGoal::Minutes(amount)),
+                _ => Msg::SetGoal(DailyGoal::Items(amount)),
+            }
+        });
+
+        let on_amount = ctx.link().callback(move |e: Event| {
+            let target: HtmlInputElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+            let amount = target.value().parse::<u32>().unwrap_or(amount).max(1);
+            match goal {
+                DailyGoal::Items(_) => Msg::SetGoal(DailyGoal::Items(amount)),
+                DailyGoal::Minutes(_) => Msg::SetGoal(DailyGoal::Minutes(amount)),
+            }
+        });
+
+        html! {
+            <div class="filters">
+                <label>{ self.tr("Daily goal") }{ " " }
+                    <input type="number" min="1" value={amount.to_string()} onchange={on_amount} />
+                </label>
+                <select onchange={on_kind}>
+                    <option value="items" selected={!is_minutes}>{ self.tr("items") }</option>
+                    <option value="minutes" selected={is_minutes}>{ self.tr("minutes") }</option>
+                </select>
+            </div>
+        }
+    }
+
+    /// Fetches the words with the lowest accuracy, reporting back with `Msg::WeakestLoaded` or `Msg::FetchError`.
+    fn load_weakest(&self, link: html::Scope<Self>) {
+        let store = self.store.clone();
+        let awesome_id = self.awesome_id;
+        spawn_local(async move {
+            match fetch_vocab_page(&store, awesome_id, VocabQuery::weakest(WEAKEST_WORDS)).await {
+                Ok(page) => link.send_message(Msg::WeakestLoaded(page)),
+                Err(err) => link.send_message(Msg::FetchError(err)),
+            }
+        });
+    }
+
+    fn heatmap_view(&self, today: i64) -> Html {
+        let weeks = heatmap(&self.log, &self.goal, today, HEATMAP_WEEKS);
+
+        html! {
+            <div class="heatmap">
+                { for weeks.iter().map(|week| html! {
+                    <div class="week">
+                        { for week.iter().map(|cell| {
+                            let title = translate_format(&self.lang, "{}: {} items, {} min", &[
+                                &day_display(cell.day), &cell.activity.items, &format!("{:.0}", cell.activity.minutes()),
+                            ]);
+                            html! { <span class={classes!("day", format!("level-{}", cell.level))} {title}></span> }
+                        }) }
+                    </div>
+                }) }
+            </div>
+        }
+    }
+}
+
+impl Component for Profile {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        let state = store.state();
+        let awesome_id = state.learner_id();
+        Self {
+            store: store.clone(),
+            awesome_id,
+            lang: state.settings.language.clone(),
+            goal: load_json(StorageArea::Local, &learner_key(GOAL_STORAGE_KEY, awesome_id)).unwrap_or_default(),
+            log: load_json(StorageArea::Local, &learner_key(ACTIVITY_STORAGE_KEY, awesome_id)).unwrap_or_default(),
+            weakest: vec![],
+            error: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::SetGoal(goal) => {
+                self.goal = goal;
+                if let Err(err) = save_json(StorageArea::Local, &learner_key(GOAL_STORAGE_KEY, self.awesome_id), &goal) {
+                    self.error = Some(err.into());
+                }
+                true
+            }
+            Msg::WeakestLoaded(page) => {
+                self.weakest = page.entries;
+                true
+            }
+            Msg::FetchError(err) => {
+                self.error = Some(err);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let today = local_day(js_sys::Date::now());
+        let activity = self.log.get(&today).copied().unwrap_or_default();
+        let progress = (self.goal.progress(&activity) * 100.0).min(100.0);
+
+        let weakest: Html = self.weakest.iter().map(|entry| html! {
+            <li>
+                <Link<Route> to={Route::VocabDetail { vocab_study_id: entry.vocab_study_id }}>
+                    { entry.first_lang.clone() }
+                </Link<Route>>
+                { format!(" ({:.0}%)", entry.percentage_correct) }
+            </li>
+        }).collect();
+
+        html! {
+            <section>
+                <h2>{ self.tr("Your Progress") }</h2>
+                { self.goal_form(ctx) }
+
+                if let Some(error) = self.error.clone() {
+                    <ErrorPanel {error}/>
+                }
+
+                <table class="summary">
+                    <tr><th>{ self.tr("Today") }</th><td>{ translate_format(&self.lang, "{} items, {} min ({}% of {})", &[
+                        &activity.items, &format!("{:.0}", activity.minutes()), &format!("{:.0}", progress), &self.goal.display(&self.lang),
+                    ]) }</td></tr>
+                    <tr><th>{ self.tr("Current streak") }</th><td>{ translate_format(&self.lang, "{} days", &[&current_streak(&self.log, &self.goal, today)]) }</td></tr>
+                    <tr><th>{ self.tr("Longest streak") }</th><td>{ translate_format(&self.lang, "{} days", &[&longest_streak(&self.log, &self.goal)]) }</td></tr>
+                </table>
+
+                <h3>{ self.tr("Study Calendar") }</h3>
+                { self.heatmap_view(today) }
+
+                if !self.weakest.is_empty() {
+                    <h3>{ self.tr("Hardest Words") }</h3>
+                    <ul class="summary">{ weakest }</ul>
+                }
+
+                <h3>{ self.tr("Export") }</h3>
+                <ExportButtons/>
+
+                <Link<Route> to={Route::Study}>{ self.tr("Learn") }</Link<Route>>
+                <Link<Route> to={Route::Vocab}>{ self.tr("My Words") }</Link<Route>>
+                <Link<Route> to={Route::Settings}>{ self.tr("Settings") }</Link<Route>>
+            </section>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.load_weakest(ctx.link().clone());
+        }
+    }
+}
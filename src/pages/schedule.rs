@@ -0,0 +1,106 @@
+use yew::prelude::*;
+use crate::components::store::scope_store;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::scheduler::{cached_history, due_display, Schedule, Scheduler, SCHEDULE_STORAGE_KEY};
+use crate::sl::session::KNOWN_CHALLENGES_STORAGE_KEY;
+use crate::sl::storage::{learner_key, load_json, StorageArea};
+use crate::sl::study::Challenge;
+
+/// Messages handled by the `ScheduleDebug` page.
+///
+/// ## Variants:
+/// - `Refresh`: Reloads the schedule from storage and recomputes the due times.
+pub enum Msg {
+    Refresh,
+}
+
+/// The `ScheduleDebug` page, a debug view of the client-side spaced repetition schedule.
+///
+/// It schedules the challenges remembered in this browser session the same way the study page does when
+/// the backend cannot be reached, and lists them in the order they would be studied with their SM-2 state
+/// and when each is next due. Items without a stored state show the state estimated from their cached statistics.
+///
+/// ## Fields:
+/// - `scheduler`: The scheduled challenges.
+/// - `now_ms`: The time the due times are relative to.
+/// - `lang`: The interface language.
+pub struct ScheduleDebug {
+    scheduler: Scheduler,
+    now_ms: f64,
+    lang: String,
+}
+
+impl ScheduleDebug {
+    fn load(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        let state = store.state();
+        let awesome_id = state.learner_id();
+        let known: Vec<Challenge> = load_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY).unwrap_or_default();
+        let schedule: Schedule = load_json(StorageArea::Local, &learner_key(SCHEDULE_STORAGE_KEY, awesome_id)).unwrap_or_default();
+        Self {
+            scheduler: Scheduler::new(known, &schedule, |vocab_study_id| cached_history(&store, vocab_study_id)),
+            now_ms: js_sys::Date::now(),
+            lang: state.settings.language.clone(),
+        }
+    }
+}
+
+impl Component for ScheduleDebug {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self::load(ctx)
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Refresh => {
+                *self = Self::load(ctx);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let tr = |text: &str| translate(&self.lang, text);
+        let rows: Html = self.scheduler.items().iter().map(|item| {
+            let last_reviewed = item.state.last_reviewed_ms
+                .and_then(|ms| js_sys::Date::new(&ms.into()).to_iso_string().as_string())
+                .unwrap_or_else(|| tr("never"));
+            html! {
+                <tr>
+                    <td>{ item.challenge.vocab_study_id }</td>
+                    <td>{ item.challenge.first_lang.clone() }</td>
+                    <td>{ item.state.repetitions }</td>
+                    <td>{ format!("{:.2}", item.state.ease) }</td>
+                    <td>{ translate_format(&self.lang, "{}d", &[&format!("{:.0}", item.state.interval_days)]) }</td>
+                    <td>{ last_reviewed }</td>
+                    <td>{ due_display(item.state.due_ms, self.now_ms, &self.lang) }</td>
+                </tr>
+            }
+        }).collect();
+
+        html! {
+            <section>
+                <h2>{ tr("Review Schedule") }</h2>
+                <p>{ translate_format(&self.lang, "{} cached words, {} due now.", &[&self.scheduler.items().len(), &self.scheduler.num_due(self.now_ms)]) }</p>
+                <button onclick={ctx.link().callback(|_| Msg::Refresh)}>{ tr("Refresh") }</button>
+                <table class="vocab">
+                    <thead>
+                        <tr>
+                            <th>{ tr("Vocab Study") }</th>
+                            <th>{ tr("Word") }</th>
+                            <th>{ tr("Repetitions") }</th>
+                            <th>{ tr("Ease") }</th>
+                            <th>{ tr("Interval") }</th>
+                            <th>{ tr("Last Reviewed") }</th>
+                            <th>{ tr("Next Due") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>{ rows }</tbody>
+                </table>
+            </section>
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+use crate::components::store::scope_store;
+use crate::components::theme::ThemePicker;
+use crate::route::Route;
+use crate::sl::hints::{HintKind, DEFAULT_HINT_ORDER};
+use crate::sl::i18n::{language_name, translate, translate_format, UI_LANGUAGES};
+use crate::sl::session::SessionLength;
+use crate::sl::settings::{Settings, MAX_BATCH_SIZE};
+use crate::sl::store::{Action, Store, Subscription};
+
+/// Messages handled by the `SettingsPage`.
+///
+/// ## Variants:
+/// - `SettingsChanged(Settings)`: Shows the settings after they were changed in the store.
+/// - `Change(Settings)`: Applies changed settings.
+/// - `Reset`: Restores the default settings.
+pub enum Msg {
+    SettingsChanged(Settings),
+    Change(Settings),
+    Reset,
+}
+
+/// The value of the select or input an event happened on.
+fn target_value(e: &Event) -> String {
+    let target = e.target().unwrap_throw();
+    match target.dyn_ref::<HtmlSelectElement>() {
+        Some(select) => select.value(),
+        None => target.unchecked_into::<HtmlInputElement>().value(),
+    }
+}
+
+/// Whether the checkbox an event happened on is checked.
+fn target_checked(e: &Event) -> bool {
+    e.target().unwrap_throw().unchecked_into::<HtmlInputElement>().checked()
+}
+
+/// The `SettingsPage`, where the learner changes their study preferences.
+///
+/// Every change is dispatched to the app-wide store right away, and saved in local storage by the
+/// `StoreProvider`.
+///
+/// ## Fields:
+/// - `settings`: The settings shown.
+/// - `store`: The app-wide store the settings are kept in.
+/// - `_subscription`: Keeps the page subscribed to changes of the settings, e.g. by the `ThemePicker`.
+pub struct SettingsPage {
+    settings: Settings,
+    store: Store,
+    _subscription: Subscription,
+}
+
+impl SettingsPage {
+
+    /// Builds a callback applying a change to the settings in the store when the event happens.
+    fn change<E: 'static>(&self, ctx: &Context<Self>, apply: impl Fn(&mut Settings, E) + 'static) -> Callback<E> {
+        let store = self.store.clone();
+        ctx.link().callback(move |e: E| {
+            let mut changed = store.state().settings.clone();
+            apply(&mut changed, e);
+            Msg::Change(changed)
+        })
+    }
+
+    /// Translates an interface text into the chosen interface language.
+    fn tr(&self, text: &str) -> String {
+        translate(&self.settings.language, text)
+    }
+
+    fn session_fields(&self, ctx: &Context<Self>) -> Html {
+        let (is_minutes, amount) = match self.settings.session_length {
+            SessionLength::Items(items) => (false, items),
+            SessionLength::Minutes(minutes) => (true, minutes as usize),
+        };
+
+        let on_amount = self.change(ctx, move |settings, e: Event| {
+            let amount = target_value(&e).parse::<usize>().unwrap_or(amount).max(1);
+            settings.session_length = if is_minutes { SessionLength::Minutes(amount as u32) } else { SessionLength::Items(amount) };
+        });
+        let on_unit = self.change(ctx, move |settings, e: Event| {
+            settings.session_length = match target_value(&e).as_str() {
+                "minutes" => SessionLength::Minutes(amount as u32),
+                _ => SessionLength::Items(amount),
+            };
+        });
+        let on_batch_size = self.change(ctx, |settings, e: Event| {
+            if let Ok(batch_size) = target_value(&e).parse::<usize>() {
+                settings.batch_size = batch_size;
+            }
+        });
+
+        html! {
+            <>
+                <div class="filters">
+                    <label>{ self.tr("Session length") }{ " " }
+                        <input type="number" min="1" value={amount.to_string()} onchange={on_amount} />
+                    </label>
+                    <select aria-label={self.tr("Session length unit")} onchange={on_unit}>
+                        <option value="items" selected={!is_minutes}>{ self.tr("words") }</option>
+                        <option value="minutes" selected={is_minutes}>{ self.tr("minutes") }</option>
+                    </select>
+                </div>
+                <div class="filters">
+                    <label>{ self.tr("Words fetched at a time") }{ " " }
+                        <input type="number" min="1" max={MAX_BATCH_SIZE.to_string()} value={self.settings.batch_size.to_string()} onchange={on_batch_size} />
+                    </label>
+                </div>
+            </>
+        }
+    }
+
+    /// The hints offered, in the order they are revealed, with a checkbox to offer each kind and a button
+    /// to move it up.
+    fn hint_fields(&self, ctx: &Context<Self>) -> Html {
+        let order = self.settings.hint_order.clone();
+        let unused: Vec<HintKind> = DEFAULT_HINT_ORDER.iter().filter(|kind| !order.contains(kind)).copied().collect();
+
+        let rows = order.iter().enumerate().map(|(index, kind)| (Some(index), *kind))
+            .chain(unused.into_iter().map(|kind| (None, kind)));
+
+        html! {
+            <ol class="hint-order">
+                { for rows.map(|(index, kind)| {
+                    let on_toggle = self.change(ctx, move |settings, e: Event| {
+                        if target_checked(&e) {
+                            settings.hint_order.push(kind);
+                        } else {
+                            settings.hint_order.retain(|used| *used != kind);
+                        }
+                    });
+                    let on_up = self.change(ctx, move |settings, _: MouseEvent| {
+                        if let Some(index) = index.filter(|index| *index > 0) {
+                            settings.hint_order.swap(index, index - 1);
+                        }
+                    });
+
+                    html! {
+                        <li>
+                            <label>
+                                <input type="checkbox" checked={index.is_some()} onchange={on_toggle} />
+                                { self.tr(kind.label()) }
+                            </label>
+                            if index.is_some_and(|index| index > 0) {
+                                <button type="button" aria-label={translate_format(&self.settings.language, "Move {} up", &[&self.tr(kind.label())])} onclick={on_up}>{ "↑" }</button>
+                            }
+                        </li>
+                    }
+                }) }
+            </ol>
+        }
+    }
+}
+
+impl Component for SettingsPage {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        let subscription = store.select(|state| state.settings.clone(), ctx.link().callback(Msg::SettingsChanged));
+
+        Self {
+            settings: store.state().settings.clone(),
+            store,
+            _subscription: subscription,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::SettingsChanged(settings) => {
+                self.settings = settings;
+                true
+            }
+            Msg::Change(settings) => {
+                self.store.dispatch(Action::ChangeSettings(settings));
+                false
+            }
+            Msg::Reset => {
+                self.store.dispatch(Action::ChangeSettings(Settings::default()));
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_auto_speak = self.change(ctx, |settings, e: Event| settings.auto_speak = target_checked(&e));
+        let on_auto_focus = self.change(ctx, |settings, e: Event| settings.auto_focus = target_checked(&e));
+        let on_language = self.change(ctx, |settings, e: Event| settings.language = target_value(&e));
+
+        html! {
+            <section class="settings">
+                <h2>{ self.tr("Settings") }</h2>
+
+                <h3>{ self.tr("Study") }</h3>
+                { self.session_fields(ctx) }
+                <div class="filters">
+                    <label>
+                        <input type="checkbox" checked={self.settings.auto_speak} onchange={on_auto_speak} />
+                        { self.tr("Read the answer aloud after checking") }
+                    </label>
+                </div>
+                <div class="filters">
+                    <label>
+                        <input type="checkbox" checked={self.settings.auto_focus} onchange={on_auto_focus} />
+                        { self.tr("Move the focus to the answer box and the next button") }
+                    </label>
+                </div>
+
+                <h3>{ self.tr("Hints") }</h3>
+                { self.hint_fields(ctx) }
+
+                <h3>{ self.tr("Display") }</h3>
+                <ThemePicker/>
+                <div class="filters">
+                    <label>{ self.tr("Interface language") }{ " " }
+                        <select onchange={on_language}>
+                            { for UI_LANGUAGES.iter().map(|lang| html! {
+                                <option value={*lang} selected={self.settings.language == *lang}>{ language_name(lang) }</option>
+                            }) }
+                        </select>
+                    </label>
+                </div>
+
+                <button type="button" onclick={ctx.link().callback(|_| Msg::Reset)}>{ self.tr("Reset to defaults") }</button>
+                <Link<Route> to={Route::Profile}>{ self.tr("My Progress") }</Link<Route>>
+            </section>
+        }
+    }
+}
@@ -1,11 +1,34 @@
-use std::vec::IntoIter;
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use wasm_bindgen_futures::spawn_local;
-use web_sys::{Event, FocusEvent, HtmlInputElement, InputEvent, KeyboardEvent, MouseEvent};
+use web_sys::{Event, FocusEvent, HtmlInputElement, InputEvent, KeyboardEvent};
 
-use yew::{html, Component, Context, Html, NodeRef};
+use yew::{classes, html, Callback, Component, Context, Html, NodeRef};
+use yew_router::prelude::Link;
+use yew_router::scope_ext::RouterScopeExt;
+use crate::components::answer_diff::AnswerDiff;
+use crate::components::error_panel::ErrorPanel;
+use crate::components::notes_editor::NotesEditor;
 use crate::components::prompt::Prompt;
-use crate::sl::study::{fetch_vocab_study_list, Challenge, check_vocab_answer};
+use crate::components::store::scope_store;
+use crate::components::shortcuts::ShortcutRegistry;
+use crate::route::Route;
+use crate::sl::hints::HintKind;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::gql::FetchError;
+use crate::sl::goals::{local_day, record_answer, ActivityLog, ACTIVITY_STORAGE_KEY};
+use crate::sl::new_vocab::remember_challenges;
+use crate::sl::request::{LatestRequest, RequestId};
+use crate::sl::scheduler::{answer_quality, cached_history, due_display, next_due_ms, record_review, ItemHistory, Schedule, Scheduler,
+                           SCHEDULE_STORAGE_KEY};
+use crate::sl::session::{remember_mistake, SessionQuery, StudySession, DECK_STORAGE_KEY, KNOWN_CHALLENGES_STORAGE_KEY,
+                         NEW_WORDS_STORAGE_KEY, RECENT_MISTAKES_STORAGE_KEY, SUMMARY_STORAGE_KEY};
+use crate::sl::settings::Settings;
+use crate::sl::shortcuts::ShortcutAction;
+use crate::sl::speech::speak;
+use crate::sl::storage::{learner_key, load_json, save_json, take_json, StorageArea};
+use crate::sl::store::{Action, Store};
+use crate::sl::study::{fetch_vocab_study_list, Challenge, check_vocab_answer, update_user_notes, CheckGuard, CheckResult, NotesSaves};
+use crate::sl::vocab::fetch_learning_texts;
 
 /// Enumeration of messages that drive the component logic in the study session.
 ///
@@ -17,31 +40,66 @@ use crate::sl::study::{fetch_vocab_study_list, Challenge, check_vocab_answer};
 /// ## Variants:
 /// - `UpdateList(Vec<Challenge>)`: Updates the internal list of challenges with a new set.
 ///   This typically happens after fetching a new set of vocabulary challenges from the server.
+///   The challenges are also remembered in session storage, to detect duplicates when adding words.
+///   When there is nothing left to study, the page shows that the learner is all caught up, or the summary
+///   if they already answered some challenges.
+/// - `UpdateCachedList(Vec<Challenge>, String)`: Like `UpdateList`, for challenges the scheduler picked from
+///   the remembered challenges because the backend could not be reached. Holds the fetch error, which is
+///   shown as a notice.
 /// - `UpdateAnswer(String)`: Updates the current answer based on user input. This allows
 ///   for real-time feedback or validation of the user's response.
 /// - `CheckAnswer`: Triggers the validation of the user's answer against the correct response.
 ///   The outcome of this check may result in updating the UI to show whether the answer was correct or not.
-/// - `ShowAnswerResponse(String)`: Updates the UI to display the result of the answer check.
-///   This could be a success message, a correction, or a hint for the user.
+///   Ignored while the answer is already being checked, so it is submitted only once.
+/// - `ShowAnswerResponse(CheckResult)`: Updates the UI to display the result of the answer check.
+///   This includes the feedback message, whether the answer was correct and the expected answer.
 /// - `NextChallenge`: Advances to the next challenge in the list, updating the UI to reflect
-///   the new challenge to be solved.
-/// - `FetchError(String)`: Displays an error message in the UI, typically used to indicate
-///   problems fetching challenges or submitting answers.
+///   the new challenge to be solved. Missed challenges come back a few positions later. Once the session
+///   is complete, navigates to the summary page instead.
+/// - `HintUsed(HintKind)`: Records that the learner revealed a hint for the current challenge.
+/// - `SaveNotes(String)`: Optimistically replaces the notes of the current challenge and saves them.
+/// - `NotesSaved(i32, u64, String)`: Confirms the notes saved for a vocab study, holding the vocab study id, the
+///   sequence number of the save and the notes as saved.
+/// - `NotesSaveFailed(i32, u64, FetchError)`: Restores the last saved notes of a vocab study after its latest
+///   save failed, holding the vocab study id, the sequence number of the save and the error.
+/// - `Shortcut(ShortcutAction)`: Handles a keyboard shortcut registered by the page: a hint while answering,
+///   the next challenge once the answer is checked, or reading the word aloud.
+/// - `FetchError(FetchError)`: Displays the failure in the UI, typically used to indicate
+///   problems fetching challenges.
+/// - `CheckFailed(FetchError)`: Displays the failure of an answer check, keeping its idempotency key to retry it.
+/// - `Retry`: Tries again after a failure: the answer check that failed, with the same idempotency key, or else
+///   fetching the next challenges.
+/// - `Response(RequestId, Box<Msg>)`: Wraps the message a challenge list fetch or an answer check sends
+///   back. It is handled only if the request is still the latest of its kind, so a slow response cannot
+///   overwrite the outcome of a later challenge.
 ///
 /// ## Usage:
 /// These messages are central to the reactive nature of the Yew framework, enabling the component
 /// to handle a variety of actions and update its state and presentation accordingly.
 pub enum Msg {
     UpdateList(Vec<Challenge>),
+    UpdateCachedList(Vec<Challenge>, String),
     UpdateAnswer(String),
     CheckAnswer,
-    ShowAnswerResponse(String),
+    ShowAnswerResponse(CheckResult),
     NextChallenge,
-    FetchError(String),
+    HintUsed(HintKind),
+    SaveNotes(String),
+    NotesSaved(i32, u64, String),
+    NotesSaveFailed(i32, u64, FetchError),
+    Shortcut(ShortcutAction),
+    FetchError(FetchError),
+    CheckFailed(FetchError),
+    Retry,
+    Response(RequestId, Box<Msg>),
 }
 
+#[derive(PartialEq, Clone, Copy)]
 pub enum StudyMode {
+    Loading,
+    Empty(Option<f64>),
     Challenge,
+    Checking,
     Outcome,
     Error
 }
@@ -54,18 +112,45 @@ pub enum StudyMode {
 ///
 /// ## Fields:
 /// - `study_mode`: An enumeration of the different modes the study session can be in,
-///    including presenting a new challenge (`Challenge`), showing the outcome of a user's response (`Outcome`),
-///    or displaying an error message (`Error`).
-/// - `iterator`: An iterator over a collection of `Challenge` items. This allows the application
-///    to sequentially present vocabulary challenges to the user.
+///   including loading challenges (`Loading`), having nothing to study (`Empty`), presenting a new challenge
+///   (`Challenge`), checking the answer (`Checking`), showing the outcome of a user's response (`Outcome`),
+///   or displaying an error (`Error`).
 /// - `challenge`: The current vocabulary challenge being presented to the user. It holds details
-///    like the vocabulary ID, study ID, and the prompt for the user.
-/// - `prompt`: The textual prompt derived from the current `challenge`, displayed to the user
-///    to solicit their response.
+///   like the vocabulary ID, study ID, and the prompt for the user.
+/// - `outcome_prompt`: The feedback message for the last checked answer.
+/// - `outcome_correct`: Whether the last checked answer was accepted, `None` when the backend
+///   does not report it.
+/// - `session`: The progress of the study session. It holds the queue of upcoming challenges, puts missed
+///   challenges back into it, decides when the session is complete and builds its summary.
 /// - `answer`: The user's response to the current `prompt`. This is updated based on user input.
-/// - `err_msg`: An error message to be displayed to the user in case of a problem,
-///    such as an issue fetching a new challenge or submitting a response.
-/// = `element_focus_ref`: Attaches to a html element to allow direct programmatic access
+/// - `hints_used`: The hints revealed for the current challenge, one entry per reveal. They are sent along
+///   with the answer and reduce the points it earns.
+/// - `hint_requests`: The number of hints requested with the keyboard, passed to the `Prompt` to reveal them.
+/// - `checked_answer`: The answer as it was when it was submitted for checking, used to build the
+///   character-level diff in the outcome view.
+/// - `expected_answer`: The correct answer for the current challenge, when the backend reports it or the challenge
+///   carries its `learning` text.
+/// - `notes_saves`: The notes saves in flight, so only the outcome of the latest save of a vocab study is applied.
+/// - `notes_error`: The error of the last failed notes save, shown next to the notes editor.
+/// - `cache_notice`: Why the challenges come from the cache rather than the backend, `None` when they don't.
+/// - `last_activity_ms`: When the page was opened or the last answer was checked, used to count the time
+///   spent on each answer towards the daily goal.
+/// - `error`: The failure to be displayed to the user in case of a problem,
+///   such as an issue fetching a new challenge or submitting a response.
+/// - `retryable`: Whether the failure comes from a request that may succeed when sent again, the only case
+///   where the learner is offered to try again.
+/// - `element_focus_ref`: Attaches to a html element to allow direct programmatic access
+/// - `focused_on`: The challenge, attempt and mode the focus was last moved for, so it only moves again when
+///   they change and not when e.g. a shortcut re-renders the page.
+/// - `shortcuts`: The registry the page's keyboard shortcuts are registered with, `None` outside a
+///   `ShortcutsProvider`.
+/// - `settings`: The learner's study preferences, as they were when the page was opened.
+/// - `store`: The app-wide store, which keeps the session when the learner leaves the page before it is
+///   complete.
+/// - `list_request`: The pending fetch of the next challenges, cancelled when the learner leaves the page.
+/// - `check_request`: The pending answer check, cancelled when the learner moves on or leaves the page.
+/// - `check_guard`: Refuses to submit an answer while another is being checked, and keeps the idempotency key
+///   of a failed check to reuse when the same answer is submitted again.
 ///
 /// ## Usage:
 /// The `Study` struct is instantiated as part of the Yew component lifecycle and is pivotal
@@ -73,12 +158,29 @@ pub enum StudyMode {
 /// and updates the UI to reflect the current state of the study session.
 pub struct Study {
     study_mode: StudyMode,
-    iterator: IntoIter<Challenge>,
     challenge: Challenge,
     outcome_prompt: String,
+    outcome_correct: Option<bool>,
+    session: StudySession,
     answer: String,
-    err_msg: String,
+    hints_used: Vec<HintKind>,
+    hint_requests: u32,
+    checked_answer: String,
+    expected_answer: Option<String>,
+    notes_saves: NotesSaves,
+    notes_error: Option<String>,
+    cache_notice: Option<String>,
+    last_activity_ms: f64,
+    error: Option<FetchError>,
+    retryable: bool,
     element_focus_ref: NodeRef,
+    focused_on: Option<(i32, usize, StudyMode)>,
+    shortcuts: Option<ShortcutRegistry>,
+    settings: Settings,
+    store: Store,
+    list_request: LatestRequest,
+    check_request: LatestRequest,
+    check_guard: CheckGuard,
 }
 
 /// `Study` represents a study session within a vocabulary learning web application. This component
@@ -88,14 +190,14 @@ pub struct Study {
 /// ## Implementation Details:
 /// - Utilizes asynchronous requests to interact with the backend for fetching challenges and validating responses.
 /// - Employs the `Component` trait from the Yew framework to implement reactive UI updates
-/// based on user interactions and asynchronous operations.
+///   based on user interactions and asynchronous operations.
 impl Study {
 
     /// Asynchronously fetches the next set of vocabulary study challenges.
     ///
     /// This function initiates a request to fetch a list of vocabulary study challenges for
     /// a specified `awesome_id` and up to a given `limit` of items. The function uses
-    /// `spawn_local` to run the fetching process asynchronously, allowing the Yew component to
+    /// a cancellable `LatestRequest` to run the fetching process asynchronously, allowing the Yew component to
     /// remain responsive during the data fetching operation.
     ///
     /// Upon successful fetching, it sends a `Msg::UpdateList` message with the retrieved
@@ -104,23 +206,45 @@ impl Study {
     /// it sends a `Msg::FetchError` message with the error message, allowing the component
     /// to handle and display the error appropriately.
     ///
+    /// Words the learner added since the last batch are taken from session storage and put at the
+    /// front of the list, so they are studied right away. When the backend cannot be reached, the
+    /// `Scheduler` picks the challenges due the earliest out of the remembered challenges instead.
+    ///
+    /// The answers are only fetched, with `fetch_learning_texts`, when the learner's hint order offers hints
+    /// built from them.
+    ///
     /// ## Parameters:
     /// - `link`: The component's `html::Scope<Self>` link, used to send messages back to the component.
     /// - `awesome_id`: The ID of the `AwesomePerson` for whom the vocabulary list is fetched.
     /// - `limit`: The maximum number of vocabulary challenges to fetch.
     ///
-    /// This function demonstrates handling asynchronous operations within a Yew component,
-    /// using `spawn_local` for non-blocking network requests and message passing to update
-    /// the component's state based on the results of those requests.
-    pub fn load_next_vocab_list(&self, link: html::Scope<Self>, awesome_id: i32, limit: i32) {
-        spawn_local(async move {
+    /// The fetch replaces any pending one and its messages are wrapped in `Msg::Response`, so only the
+    /// latest fetch updates the list. It is cancelled, aborting the request, when the page is destroyed.
+    pub fn load_next_vocab_list(&mut self, link: html::Scope<Self>, awesome_id: i32, limit: i32) {
+        let needs_learning = self.settings.hint_order.iter().any(HintKind::needs_learning);
+        let schedule_key = learner_key(SCHEDULE_STORAGE_KEY, awesome_id);
+        let store = self.store.clone();
+        self.list_request.start(|id| async move {
+            let link = link.callback(move |msg| Msg::Response(id, Box::new(msg)));
             let res = fetch_vocab_study_list(awesome_id, limit).await;
-            if res.is_err() {
-                let err_msg = res.err().clone().unwrap().to_string();
-                link.send_message(Msg::FetchError(err_msg.clone()));
+            if let Err(err) = &res {
+                let known: Vec<Challenge> = load_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY).unwrap_or_default();
+                if known.is_empty() {
+                    link.emit(Msg::FetchError(err.clone()));
+                } else {
+                    let schedule: Schedule = load_json(StorageArea::Local, &schedule_key).unwrap_or_default();
+                    let batch = Scheduler::new(known, &schedule, |vocab_study_id| cached_history(&store, vocab_study_id)).batch(limit.max(0) as usize);
+                    link.emit(Msg::UpdateCachedList(batch, err.message()));
+                }
             } else {
-                let list = res.unwrap_or_default();
-                link.send_message(Msg::UpdateList(list.clone()));
+                let mut list: Vec<Challenge> = take_json(StorageArea::Session, NEW_WORDS_STORAGE_KEY).unwrap_or_default();
+                let fetched = res.unwrap_or_default().into_iter()
+                    .filter(|challenge| !list.iter().any(|new| new.vocab_study_id == challenge.vocab_study_id));
+                list.extend(fetched.collect::<Vec<_>>());
+                if needs_learning {
+                    fetch_learning_texts(&store, &mut list).await;
+                }
+                link.emit(Msg::UpdateList(list.clone()));
             }
         });
     }
@@ -128,38 +252,142 @@ impl Study {
     /// Submits the user's answer for a vocabulary challenge to be checked and handles the response.
     ///
     /// This function sends the user's answer along with the corresponding challenge to the
-    /// server for verification. It processes the response asynchronously, leveraging `spawn_local`
+    /// server for verification. It processes the response asynchronously, leveraging a cancellable `LatestRequest`
     /// for non-blocking execution. Depending on the server's response, it either triggers an
     /// error message or updates the UI to show the result of the answer check.
     ///
     /// ## Parameters:
     /// - `link`: The `html::Scope<Self>` link for communicating with the Yew component.
+    /// - `awesome_id`: The learner who answered.
     /// - `answer`: The user's answer submitted for the challenge.
     /// - `challenge`: The `Challenge` struct containing details about the current vocabulary item.
+    /// - `hints_used`: The hints revealed before answering, passed along to the server.
+    /// - `idempotency_key`: Identifies the submission, so the server records it only once.
     ///
-    /// Upon failure, a `Msg::FetchError` message with the error message is sent to the component,
-    /// prompting error handling logic. On success, the correct or feedback message is displayed
+    /// Upon failure, a `Msg::CheckFailed` message with the error is sent to the component,
+    /// prompting error handling logic. On success, the `CheckResult` is passed on
     /// using a `Msg::ShowAnswerResponse` message, allowing the component to update accordingly.
-    pub fn get_answer_checked(&self, link: html::Scope<Self>, answer: String, challenge: Challenge) {
-        spawn_local(async move {
-            let res = check_vocab_answer(answer, challenge).await;
-            if res.is_err() {
-                let err_msg = res.err().clone().unwrap().to_string();
-                link.send_message(Msg::FetchError(err_msg.clone()));
+    ///
+    /// Both are wrapped in `Msg::Response`, so the outcome is dropped when a newer check started or the
+    /// learner moved on to the next challenge in the meantime.
+    pub fn get_answer_checked(&mut self, link: html::Scope<Self>, awesome_id: i32, answer: String, challenge: Challenge, hints_used: Vec<HintKind>, idempotency_key: String) {
+        let store = self.store.clone();
+        self.check_request.start(|id| async move {
+            let link = link.callback(move |msg| Msg::Response(id, Box::new(msg)));
+            let res = check_vocab_answer(&store, awesome_id, answer, challenge, hints_used, idempotency_key).await;
+            if let Err(err) = &res {
+                link.emit(Msg::CheckFailed(err.clone()));
 
             } else {
-                let response_prompt = res.unwrap_or_default();
-                link.send_message(Msg::ShowAnswerResponse(response_prompt));
+                let check_result = res.unwrap_or_default();
+                link.emit(Msg::ShowAnswerResponse(check_result));
+            }
+        });
+    }
+
+    /// Saves the user notes for a vocab study, reporting the outcome back to the component.
+    ///
+    /// ## Parameters:
+    /// - `link`: The `html::Scope<Self>` link for communicating with the Yew component.
+    /// - `vocab_study_id`: The vocab study the notes belong to.
+    /// - `notes`: The new notes.
+    /// - `seq`: The sequence number of the save, from `NotesSaves::begin`.
+    pub fn save_user_notes(&self, link: html::Scope<Self>, vocab_study_id: i32, notes: String, seq: u64) {
+        let store = self.store.clone();
+        spawn_local(async move {
+            match update_user_notes(&store, vocab_study_id, notes).await {
+                Ok(saved) => link.send_message(Msg::NotesSaved(vocab_study_id, seq, saved)),
+                Err(err) => link.send_message(Msg::NotesSaveFailed(vocab_study_id, seq, err)),
             }
         });
     }
+
+    /// Replaces the notes of the vocab study, both on the current challenge and on any queued copies.
+    fn set_notes(&mut self, vocab_study_id: i32, notes: &str) {
+        if self.challenge.vocab_study_id == vocab_study_id {
+            self.challenge.user_notes = notes.to_string();
+        }
+        self.session.update_notes(vocab_study_id, notes);
+    }
+
+    /// Translates an interface text into the learner's interface language.
+    fn tr(&self, text: &str) -> String {
+        translate(&self.settings.language, text)
+    }
+
+    /// Ends the study session, storing its summary for the summary page and navigating there.
+    ///
+    /// The summary is handed over through session storage, so it survives the navigation and a reload
+    /// of the summary page. If it cannot be stored, the error is shown instead.
+    fn finish_session(&mut self, ctx: &Context<Self>) {
+        let summary = self.session.summary(js_sys::Date::now());
+
+        match save_json(StorageArea::Session, SUMMARY_STORAGE_KEY, &summary) {
+            Ok(_) => {
+                if let Some(navigator) = ctx.link().navigator() {
+                    navigator.push(&Route::Summary);
+                }
+            }
+            Err(err) => {
+                // The summary is stored in the browser, fetching the challenges again would not help
+                self.error = Some(FetchError::from(err));
+                self.retryable = false;
+                self.study_mode = StudyMode::Error;
+            }
+        }
+    }
+
+    /// Running score of the session, e.g. `Score: 3 of 4`.
+    fn score_display(&self) -> String {
+        let correct = self.session.attempts.iter().filter(|a| a.correct == Some(true)).count();
+        translate_format(&self.settings.language, "Score: {} of {}", &[&correct, &self.session.attempts.len()])
+    }
+
+    /// The local storage key of a value kept for the learner studying.
+    fn learner_key(&self, key: &str) -> String {
+        learner_key(key, self.store.state().learner_id())
+    }
+
+    /// Counts the checked answer and the time spent on it towards today's activity in local storage.
+    fn record_activity(&mut self) {
+        let now = js_sys::Date::now();
+        let key = self.learner_key(ACTIVITY_STORAGE_KEY);
+        let mut log: ActivityLog = load_json(StorageArea::Local, &key).unwrap_or_default();
+        record_answer(&mut log, local_day(now), now - self.last_activity_ms);
+        save_json(StorageArea::Local, &key, &log).unwrap_or_default();
+        self.last_activity_ms = now;
+    }
+
+    /// CSS class for the outcome heading, colouring it by whether the last answer was accepted.
+    fn outcome_class(&self) -> &'static str {
+        match self.outcome_correct {
+            Some(true) => "outcome-correct",
+            Some(false) => "outcome-incorrect",
+            None => "outcome-unknown",
+        }
+    }
+
+    /// The text of the live region, read out by screen readers whenever it changes: the outcome of the
+    /// checked answer, or nothing while a challenge is shown since the answer box describes it.
+    fn announcement(&self) -> String {
+        match self.study_mode {
+            StudyMode::Loading => self.tr("Loading words…"),
+            StudyMode::Empty(_) => self.tr("You're all caught up!"),
+            StudyMode::Checking => self.tr("Checking…"),
+            StudyMode::Outcome => match &self.expected_answer {
+                Some(expected) if self.outcome_correct != Some(true) => format!("{} {}", self.outcome_prompt, translate_format(&self.settings.language, "The answer is {}.", &[expected])),
+                _ => self.outcome_prompt.clone(),
+            },
+            _ => "".to_string(),
+        }
+    }
 }
 
 /// The `Study` component manages the study session for vocabulary challenges.
 ///
 /// This component handles the display of vocabulary challenges, checks user answers,
-/// and navigates through the vocabulary study set. It operates in three modes: `Challenge`,
-/// `Outcome`, and `Error`, controlled by the `study_mode` state.
+/// and navigates through the vocabulary study set. It operates in six modes: `Loading`, `Empty`, `Challenge`,
+/// `Checking`, `Outcome`, and `Error`, controlled by the `study_mode` state.
 ///
 /// ## Lifecycle Methods:
 /// - `create`: Initializes the component with default values.
@@ -173,15 +401,49 @@ impl Component for Study {
     type Message = Msg;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let query = ctx.link().location()
+            .and_then(|location| location.query::<SessionQuery>().ok())
+            .unwrap_or_default();
+
+        let store = scope_store(ctx.link());
+        let settings = store.state().settings.clone();
+        // Opening the page without a query, e.g. coming back from the word details, resumes the kept session
+        let session = store.state().session.clone()
+            .filter(|_| query == SessionQuery::default())
+            .unwrap_or_else(|| StudySession::new(query.length_or(settings.session_length), js_sys::Date::now()).with_spacing(query.spacing()));
+
+        let shortcuts = ctx.link().context::<ShortcutRegistry>(Callback::noop()).map(|(registry, _)| registry);
+        if let Some(registry) = &shortcuts {
+            let actions = vec![ShortcutAction::Hint, ShortcutAction::Next, ShortcutAction::Speak];
+            registry.register(actions, ctx.link().callback(Msg::Shortcut));
+        }
+
         Self {
-            study_mode: StudyMode::Challenge,
-            iterator: Vec::new().into_iter(),
+            study_mode: StudyMode::Loading,
             challenge: Challenge::default(),
             outcome_prompt: "".to_string(),
+            outcome_correct: None,
+            session,
             answer: "".to_string(),
-            err_msg: "".to_string(),
+            hints_used: Vec::new(),
+            hint_requests: 0,
+            checked_answer: "".to_string(),
+            expected_answer: None,
+            notes_saves: NotesSaves::default(),
+            notes_error: None,
+            cache_notice: None,
+            last_activity_ms: js_sys::Date::now(),
+            error: None,
+            retryable: false,
             element_focus_ref: NodeRef::default(),
+            focused_on: None,
+            shortcuts,
+            settings,
+            store,
+            list_request: LatestRequest::default(),
+            check_request: LatestRequest::default(),
+            check_guard: CheckGuard::default(),
         }
     }
 
@@ -198,70 +460,232 @@ impl Component for Study {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::UpdateList(res) => {
-                self.iterator = res.into_iter();
-                self.challenge = self.iterator.next().unwrap_or_default();
+                let mut known = load_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY).unwrap_or_default();
+                remember_challenges(&mut known, &res);
+                save_json(StorageArea::Session, KNOWN_CHALLENGES_STORAGE_KEY, &known).unwrap_or_default();
+
+                let now = js_sys::Date::now();
+                self.session.enqueue(res);
+                self.hints_used = Vec::new();
                 self.answer = "".to_string();
-                self.err_msg = "".to_string();
-                self.study_mode = StudyMode::Challenge;
+                self.cache_notice = None;
+                self.error = None;
+                match self.session.next_challenge(now) {
+                    Some(challenge) => {
+                        self.challenge = challenge;
+                        self.study_mode = StudyMode::Challenge;
+                    }
+                    None if !self.session.attempts.is_empty() => self.finish_session(ctx),
+                    None => {
+                        let schedule: Schedule = load_json(StorageArea::Local, &self.learner_key(SCHEDULE_STORAGE_KEY)).unwrap_or_default();
+                        self.challenge = Challenge::default();
+                        self.study_mode = StudyMode::Empty(next_due_ms(&schedule, now));
+                    }
+                }
 
                 true
             }
+            Msg::UpdateCachedList(res, err) => {
+                self.update(ctx, Msg::UpdateList(res));
+                self.cache_notice = Some(err);
+                true
+            }
             Msg::UpdateAnswer(answer) => {
                 self.answer = answer;
-                self.err_msg = "".to_string();
+                self.error = None;
                 true
             }
             Msg::CheckAnswer => {
+                if !matches!(self.study_mode, StudyMode::Challenge) {
+                    return false;
+                }
+                let Some(submission) = self.check_guard.submit(&self.challenge, &self.answer, js_sys::Date::now(), js_sys::Math::random()) else {
+                    return false;
+                };
+
                 let link = ctx.link().clone();
-                self.get_answer_checked(link, self.answer.clone(), self.challenge.clone());
-                self.err_msg = "".to_string();
+                self.checked_answer = submission.answer.clone();
+                self.expected_answer = None;
+                self.outcome_correct = None;
+                self.study_mode = StudyMode::Checking;
+                self.get_answer_checked(link, self.store.state().learner_id(), submission.answer, self.challenge.clone(), self.hints_used.clone(), submission.idempotency_key);
+                self.error = None;
                 true
             },
-            Msg::ShowAnswerResponse(prompt) => {
-                self.outcome_prompt = prompt;
-                self.err_msg = "".to_string();
+            Msg::ShowAnswerResponse(check_result) => {
+                self.check_guard.finish();
+                self.session.record(&self.challenge, &check_result, &self.hints_used);
+                if let (Some(correct), Some(attempt)) = (check_result.correct, self.session.attempts.last()) {
+                    let key = self.learner_key(SCHEDULE_STORAGE_KEY);
+                    let mut schedule: Schedule = load_json(StorageArea::Local, &key).unwrap_or_default();
+                    let history = check_result.stats.as_ref().map(ItemHistory::from).unwrap_or_default();
+                    record_review(&mut schedule, self.challenge.vocab_study_id, &history, answer_quality(correct, attempt.score), js_sys::Date::now());
+                    save_json(StorageArea::Local, &key, &schedule).unwrap_or_default();
+                }
+                self.record_activity();
+                if check_result.correct == Some(false) {
+                    let key = self.learner_key(RECENT_MISTAKES_STORAGE_KEY);
+                    let mut mistakes = load_json(StorageArea::Local, &key).unwrap_or_default();
+                    remember_mistake(&mut mistakes, &self.challenge);
+                    save_json(StorageArea::Local, &key, &mistakes).unwrap_or_default();
+                }
+                self.outcome_prompt = check_result.feedback;
+                self.outcome_correct = check_result.correct;
+                // Backends without `checkAnswer` do not report the expected answer, the challenge may still know it
+                self.expected_answer = check_result.expected
+                    .or_else(|| Some(self.challenge.learning.clone()).filter(|learning| !learning.trim().is_empty()));
+
+                if let Some(stats) = check_result.stats {
+                    self.challenge.correct_attempts = stats.correct_attempts;
+                }
+
+                if self.settings.auto_speak {
+                    let expected = self.expected_answer.clone().unwrap_or_else(|| self.challenge.learning.clone());
+                    speak(&expected, &self.challenge.learning_lang_code).unwrap_or_default();
+                }
+
+                self.error = None;
                 self.study_mode = StudyMode::Outcome;
 
                 true
             },
             Msg::NextChallenge => {
                 let link = ctx.link().clone();
+                let now = js_sys::Date::now();
+                // A check still pending belongs to the challenge being left
+                self.check_request.cancel();
+                self.check_guard.reset();
 
-                if self.iterator.clone().count() == 0 {
-                    self.load_next_vocab_list(link, 1, 5);
-                } else {
-                    self.challenge = self.iterator.next().unwrap_or_default();
+                if self.session.is_complete(now) {
+                    self.finish_session(ctx);
+                } else if let Some(challenge) = self.session.next_challenge(now) {
+                    self.challenge = challenge;
+                    self.hints_used = Vec::new();
+                    self.notes_error = None;
                     self.answer = "".to_string();
-                    self.err_msg = "".to_string();
+                    self.error = None;
                     self.study_mode = StudyMode::Challenge;
+                } else if self.session.length_reached(now) {
+                    // No missed challenges are left to retry, so there is nothing more to serve
+                    self.finish_session(ctx);
+                } else {
+                    self.study_mode = StudyMode::Loading;
+                    self.load_next_vocab_list(link, self.store.state().learner_id(), self.settings.batch_size as i32);
                 }
 
                 true
             }
+            Msg::HintUsed(kind) => {
+                self.hints_used.push(kind);
+                false
+            }
+            Msg::SaveNotes(notes) => {
+                let link = ctx.link().clone();
+                let vocab_study_id = self.challenge.vocab_study_id;
+                let previous = self.challenge.user_notes.clone();
+
+                self.set_notes(vocab_study_id, &notes);
+                self.notes_error = None;
+                let seq = self.notes_saves.begin(vocab_study_id, &previous);
+                self.save_user_notes(link, vocab_study_id, notes, seq);
+                true
+            }
+            Msg::NotesSaved(vocab_study_id, seq, notes) => {
+                if self.notes_saves.succeed(vocab_study_id, seq, &notes) {
+                    self.set_notes(vocab_study_id, &notes);
+                }
+                true
+            }
+            Msg::NotesSaveFailed(vocab_study_id, seq, err) => {
+                // A newer save of the same notes is still in flight, its outcome decides
+                let Some(saved) = self.notes_saves.fail(vocab_study_id, seq) else {
+                    return false;
+                };
+                self.set_notes(vocab_study_id, &saved);
+                if self.challenge.vocab_study_id == vocab_study_id {
+                    self.notes_error = Some(self.tr(err.kind.title()));
+                }
+                true
+            }
+            Msg::Shortcut(action) => match (action, &self.study_mode) {
+                (ShortcutAction::Hint, StudyMode::Challenge) => {
+                    self.hint_requests += 1;
+                    true
+                }
+                (ShortcutAction::Next, StudyMode::Outcome) => self.update(ctx, Msg::NextChallenge),
+                (ShortcutAction::Speak, StudyMode::Challenge) => {
+                    speak(&self.challenge.first_lang, &self.challenge.known_lang_code).unwrap_or_default();
+                    false
+                }
+                (ShortcutAction::Speak, StudyMode::Outcome) => {
+                    let expected = self.expected_answer.clone().unwrap_or_else(|| self.challenge.learning.clone());
+                    speak(&expected, &self.challenge.learning_lang_code).unwrap_or_default();
+                    false
+                }
+                _ => false,
+            },
             Msg::FetchError(err) => {
-                self.err_msg = err;
+                self.retryable = err.kind.is_retryable();
+                self.error = Some(err);
                 self.study_mode = StudyMode::Error;
                 true
             },
+            Msg::CheckFailed(err) => {
+                self.check_guard.fail();
+                self.update(ctx, Msg::FetchError(err))
+            },
+            Msg::Retry => {
+                if !self.retryable {
+                    return false;
+                }
+                self.error = None;
+                if self.check_guard.has_failed() {
+                    self.study_mode = StudyMode::Challenge;
+                    self.update(ctx, Msg::CheckAnswer)
+                } else {
+                    self.study_mode = StudyMode::Loading;
+                    self.load_next_vocab_list(ctx.link().clone(), self.store.state().learner_id(), self.settings.batch_size as i32);
+                    true
+                }
+            }
+            Msg::Response(id, msg) => {
+                if self.list_request.finish(id) || self.check_request.finish(id) {
+                    self.update(ctx, *msg)
+                } else {
+                    false
+                }
+            }
         }
     }
 
     /// Renders the component based on the current study mode.
     ///
     /// Depending on the current `study_mode`, this function generates HTML to display
-    /// the appropriate UI elements for each study phase: Challenge, Outcome, or Error.
+    /// the appropriate UI elements for each study phase: Loading, Empty, Challenge, Checking, Outcome, or Error.
     /// It sets up event handlers for user interactions with the input field and buttons.
     ///
     /// ## Event Handlers:
     /// - `oninput`: Updates the answer as the user types.
     /// - `onblur`: Updates the answer when the input field loses focus.
     /// - `onkeypress`: Checks the answer if the Enter key is pressed, otherwise updates the answer.
-    /// - `onmouseover`: Automatically focuses the input field when hovered over.
+    ///
+    /// The other keys are keyboard shortcuts, handled by the `ShortcutsProvider` once the answer box is left.
+    ///
+    /// ## Accessibility:
+    /// The answer box is labelled and described by the question. The outcome of every checked answer is
+    /// announced by a polite live region that stays in the page across modes, and errors are shown as alerts.
     ///
     /// ## Study Modes:
+    /// - `StudyMode::Loading`: Displays a skeleton of the challenge while the challenges are fetched.
+    /// - `StudyMode::Empty`: Tells the learner they are all caught up, with when the next word is due for review
+    ///   and a link to add words.
     /// - `StudyMode::Challenge`: Displays the current challenge, allowing the user to enter an answer.
-    /// - `StudyMode::Outcome`: Displays the outcome after checking an answer, with a button to proceed to the next challenge.
-    /// - `StudyMode::Error`: Displays an error message if an issue occurs during the process.
+    /// - `StudyMode::Checking`: Keeps the challenge displayed while the answer is checked, with the answer box
+    ///   and the check button disabled and a spinner in the button.
+    /// - `StudyMode::Outcome`: Displays the outcome after checking an answer, highlighting the differences to the
+    ///   expected answer when it is known, with an inline editor for the learner's notes and a button to proceed
+    ///   to the next challenge.
+    /// - `StudyMode::Error`: Explains what went wrong with an `ErrorPanel`, offering to try again.
     ///
     /// ## Parameters:
     /// - `ctx`: The component's context, providing access to component's link for creating callbacks.
@@ -297,50 +721,113 @@ impl Component for Study {
             }
         });
 
-        let onmouseover = |e: MouseEvent| {
-            let event: Event = e.dyn_into().unwrap_throw();
-            let event_target = event.target().unwrap_throw();
-            let target: HtmlInputElement = event_target.dyn_into().unwrap_throw();
-
-            target
-                .focus()
-                .unwrap_or_default();
-        };
+        let checking = matches!(self.study_mode, StudyMode::Checking);
 
         html! {
             <section>
+                <div class="visually-hidden" role="status" aria-live="polite" aria-atomic="true">
+                    { self.announcement() }
+                </div>
                 <div>
                     {
                         match self.study_mode {
-                            StudyMode::Challenge => html! {
+                            StudyMode::Loading => html! {
+                                <div class="skeleton" aria-busy="true">
+                                    <div class="skeleton-line skeleton-heading"></div>
+                                    <div class="skeleton-line"></div>
+                                    <div class="skeleton-line skeleton-short"></div>
+                                    <div class="skeleton-line skeleton-input"></div>
+                                </div>
+                            },
+                            StudyMode::Empty(next_due) => html! {
+                                <div class="caught-up">
+                                    <h2>{ self.tr("You're all caught up!") }</h2>
+                                    <p>
+                                        {
+                                            match next_due {
+                                                Some(due_ms) => translate_format(&self.settings.language, "There is nothing to study right now. Next review: {}.", &[
+                                                    &due_display(due_ms, js_sys::Date::now(), &self.settings.language),
+                                                ]),
+                                                None => self.tr("There is nothing to study right now."),
+                                            }
+                                        }
+                                    </p>
+                                    <Link<Route> to={Route::AddVocab}>{ self.tr("Add words") }</Link<Route>>
+                                </div>
+                            },
+                            StudyMode::Challenge | StudyMode::Checking => html! {
                                 <>
-                                    <h2>{ "Let's Do This" }</h2>
-                                    <Prompt challenge={self.challenge.clone()}/>
+                                    <h2>{ self.tr("Let's Do This") }</h2>
+                                    if let Some(notice) = &self.cache_notice {
+                                        <p class="notice" role="status">{ translate_format(&self.settings.language, "Studying words cached in this browser, the server could not be reached: {}", &[notice]) }</p>
+                                    }
+                                    <Prompt
+                                        challenge={self.challenge.clone()}
+                                        hint_order={self.settings.hint_order.clone()}
+                                        on_hint={ctx.link().callback(Msg::HintUsed)}
+                                        hint_requests={self.hint_requests}/>
+                                    if self.session.attempts_for(self.challenge.vocab_study_id) > 0 {
+                                        <p class="retry">
+                                            { translate_format(&self.settings.language, "Attempt {} this session", &[&(self.session.attempts_for(self.challenge.vocab_study_id) + 1)]) }
+                                        </p>
+                                    }
 
                                     <p>
+                                        <label for="challenge_taken">{ self.tr("Your translation") }</label>
                                         <input
                                             id="challenge_taken"
                                             type="text"
+                                            autocomplete="off"
+                                            aria-describedby="challenge_prompt challenge_words"
+                                            disabled={checking}
                                             ref={self.element_focus_ref.clone()}
-                                            {onmouseover}
                                             {onblur}
                                             {onkeypress}
                                             {oninput}
                                         />
                                     </p>
-                                    <button onclick={ctx.link().callback(|_| Msg::CheckAnswer)}>{ "Check" }</button>
+                                    <button
+                                        type="button"
+                                        disabled={checking}
+                                        aria-busy={checking.to_string()}
+                                        onclick={ctx.link().callback(|_| Msg::CheckAnswer)}>
+                                        if checking {
+                                            <span class="spinner" aria-hidden="true"></span>
+                                            { self.tr("Checking…") }
+                                        } else {
+                                            { self.tr("Check") }
+                                        }
+                                    </button>
+                                    <p class="shortcut-tip">{ self.tr("Press Esc to leave the answer box, then ? for keyboard shortcuts.") }</p>
                                 </>
                             },
                             StudyMode::Outcome => html! {
                                 <>
-                                    <h2>{ self.outcome_prompt.clone() }</h2>
+                                    <h2 class={classes!(self.outcome_class())}>{ self.outcome_prompt.clone() }</h2>
+                                    if let Some(expected) = self.expected_answer.clone() {
+                                        <AnswerDiff entered={self.checked_answer.clone()} {expected}/>
+                                    }
                                     <button
+                                        type="button"
                                         ref={self.element_focus_ref.clone()}
-                                        onclick={ctx.link().callback(|_| Msg::NextChallenge)}>{ "Next" }</button>
+                                        onclick={ctx.link().callback(|_| Msg::NextChallenge)}>{ self.tr("Next") }</button>
+                                    <NotesEditor
+                                        notes={self.challenge.user_notes.clone()}
+                                        saving={self.notes_saves.is_saving(self.challenge.vocab_study_id)}
+                                        error={self.notes_error.clone()}
+                                        on_save={ctx.link().callback(Msg::SaveNotes)}/>
+                                    if !self.session.attempts.is_empty() {
+                                        <p class="score">{ self.score_display() }</p>
+                                    }
+                                    <Link<Route> to={Route::VocabDetail { vocab_study_id: self.challenge.vocab_study_id }}>
+                                        { self.tr("Word details") }
+                                    </Link<Route>>
                                 </>
                             },
                             StudyMode::Error => html! {
-                                <p> { self.err_msg.clone() } </p>
+                                if let Some(error) = self.error.clone() {
+                                    <ErrorPanel {error} on_retry={self.retryable.then(|| ctx.link().callback(|_| Msg::Retry))}/>
+                                }
                             },
                         }
                     }
@@ -362,20 +849,56 @@ impl Component for Study {
     ///   the first render after component creation, `false` for all subsequent renders.
     ///
     /// ## Behavior:
-    /// - On the first render (`first_render` is `true`), it initiates loading the next vocabulary list
-    ///   by calling `load_next_vocab_list`
-    /// .
-    /// - Regardless of the render, if an element reference (`element_focus_ref`) is set and points to a valid
-    ///   and present HTML element, it attempts to set focus to that element. This allows the user
-    ///  to stay in 'keyboard only' mode.
+    /// - On the first render (`first_render` is `true`), it starts the session with the deck handed over
+    ///   by the summary page, if any, or carries on with a resumed session. Failing both, it initiates loading
+    ///   the next vocabulary list by calling `load_next_vocab_list`.
+    /// - When the challenge, the attempt or the mode changed since the focus was last moved, if the learner
+    ///   wants the focus moved and an element reference (`element_focus_ref`) is set and points to a valid and
+    ///   present HTML element, it attempts to set focus to that element. This allows the user to stay in
+    ///   'keyboard only' mode, without pulling the focus back on renders that change nothing else.
     fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
         let link = ctx.link().clone();
         if first_render {
-            self.load_next_vocab_list(link, 1, 5);
+            let resumed = !self.session.attempts.is_empty() || !self.session.queue.is_empty();
+            match take_json::<Vec<Challenge>>(StorageArea::Session, DECK_STORAGE_KEY) {
+                Some(deck) if !deck.is_empty() => link.send_message(Msg::UpdateList(deck)),
+                _ if resumed => link.send_message(Msg::NextChallenge),
+                _ => self.load_next_vocab_list(link, self.store.state().learner_id(), self.settings.batch_size as i32),
+            }
         }
 
+        let focus_on = Some((self.challenge.vocab_study_id, self.session.attempts.len(), self.study_mode));
+        if !self.settings.auto_focus || self.focused_on == focus_on {
+            return;
+        }
+        self.focused_on = focus_on;
         if let Some(element) = self.element_focus_ref.cast::<web_sys::HtmlElement>() {
             element.focus().unwrap_throw();
         }
     }
+
+    /// Unregisters the page's keyboard shortcuts when the learner leaves the page, cancels the pending
+    /// requests, and keeps the session in the store unless it is finished. A challenge left unanswered,
+    /// including one whose answer was still being checked or failed to be checked, is put back at the front of
+    /// the queue.
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        if let Some(registry) = &self.shortcuts {
+            registry.unregister();
+        }
+        self.list_request.cancel();
+        self.check_request.cancel();
+
+        let now = js_sys::Date::now();
+        let mut session = self.session.clone();
+        let unanswered = matches!(self.study_mode, StudyMode::Challenge | StudyMode::Checking) || self.check_guard.has_failed();
+        if unanswered && self.challenge != Challenge::default() {
+            session.queue.push_front(self.challenge.clone());
+        }
+
+        if session.is_complete(now) || (session.length_reached(now) && session.queue.is_empty()) {
+            self.store.dispatch(Action::EndSession);
+        } else {
+            self.store.dispatch(Action::SaveSession(session));
+        }
+    }
 }
@@ -0,0 +1,112 @@
+use yew::prelude::*;
+use yew_router::prelude::Link;
+use yew_router::scope_ext::RouterScopeExt;
+use crate::components::store::scope_store;
+use crate::route::Route;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::session::{SessionQuery, SessionSummary, DECK_STORAGE_KEY, SUMMARY_STORAGE_KEY};
+use crate::sl::storage::{load_json, save_json, StorageArea};
+
+/// Messages handled by the `Summary` page.
+///
+/// ## Variants:
+/// - `StudyMissed`: Starts a new study session made up of the words missed in the summarized session.
+pub enum Msg {
+    StudyMissed,
+}
+
+/// The `Summary` page, shown at the end of a study session.
+///
+/// This component reads the `SessionSummary` stored by the `Study` page when the session completed and
+/// displays the number of items attempted, how many were correct and incorrect, the points earned after
+/// hint penalties, the time spent and the hardest words of the session.
+///
+/// ## Implementation Details:
+/// - `create`: Loads the summary of the last completed session from session storage.
+/// - `update`: On `Msg::StudyMissed`, stores the missed challenges as the deck of the next session and
+///   navigates to the `Study` page with a session length matching the number of missed words.
+/// - `view`: Renders the summary, or a pointer to the `Study` page when no session has been completed yet.
+pub struct Summary {
+    summary: Option<SessionSummary>,
+}
+
+impl Component for Summary {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            summary: load_json(StorageArea::Session, SUMMARY_STORAGE_KEY),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::StudyMissed => {
+                let missed = self.summary.as_ref().map(|s| s.missed.clone()).unwrap_or_default();
+
+                if !missed.is_empty() && save_json(StorageArea::Session, DECK_STORAGE_KEY, &missed).is_ok() {
+                    if let Some(navigator) = ctx.link().navigator() {
+                        navigator.push_with_query(&Route::Study, &SessionQuery::items(missed.len()))
+                            .unwrap_or_default();
+                    }
+                }
+
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let lang = scope_store(ctx.link()).state().settings.language.clone();
+        let tr = |text: &str| translate(&lang, text);
+        let Some(summary) = &self.summary else {
+            return html! {
+                <section>
+                    <h2>{ tr("No study session has been completed yet") }</h2>
+                    <Link<Route> to={Route::Study}>{ tr("Learn") }</Link<Route>>
+                </section>
+            };
+        };
+
+        let hardest: Vec<Html> = summary.hardest.iter().map(|word| {
+            let accuracy = word.stats.as_ref()
+                .map(|stats| translate_format(&lang, ", {}% correct overall", &[&format!("{:.0}", stats.percentage_correct)]))
+                .unwrap_or_default();
+
+            html! {
+                <li>
+                    <Link<Route> to={Route::VocabDetail { vocab_study_id: word.challenge.vocab_study_id }}>
+                        { word.challenge.first_lang.clone() }
+                    </Link<Route>>
+                    { translate_format(&lang, " (missed {}{})", &[&word.misses, &accuracy]) }
+                </li>
+            }
+        }).collect();
+
+        html! {
+            <section>
+                <h2>{ tr("Session Complete") }</h2>
+                <table class="summary">
+                    <tr><th>{ tr("Attempted") }</th><td>{ summary.attempted }</td></tr>
+                    <tr><th>{ tr("Correct") }</th><td>{ summary.correct }</td></tr>
+                    <tr><th>{ tr("Incorrect") }</th><td>{ summary.incorrect }</td></tr>
+                    <tr><th>{ tr("Points") }</th><td>{ translate_format(&lang, "{} of {}", &[&format!("{:.1}", summary.points), &summary.attempted]) }</td></tr>
+                    <tr><th>{ tr("Hints used") }</th><td>{ summary.hints_used }</td></tr>
+                    <tr><th>{ tr("Time spent") }</th><td>{ summary.elapsed_display() }</td></tr>
+                </table>
+
+                if !hardest.is_empty() {
+                    <h3>{ tr("Hardest Words") }</h3>
+                    <ul class="summary">{ for hardest }</ul>
+                }
+
+                if !summary.missed.is_empty() {
+                    <button onclick={ctx.link().callback(|_| Msg::StudyMissed)}>{ tr("Study missed words again") }</button>
+                }
+                <Link<Route> to={Route::Study}>{ tr("Keep studying") }</Link<Route>>
+                <Link<Route> to={Route::Home}>{ tr("Home") }</Link<Route>>
+            </section>
+        }
+    }
+}
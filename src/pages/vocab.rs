@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+use crate::components::error_panel::ErrorPanel;
+use crate::components::export_buttons::ExportButtons;
+use crate::components::store::scope_store;
+use crate::route::Route;
+use crate::sl::gql::FetchError;
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::request::{LatestRequest, RequestId};
+use crate::sl::store::Store;
+use crate::sl::study::VocabStats;
+use crate::sl::vocab::{fetch_vocab_page, fetch_vocab_stats, peek_vocab_page, peek_vocab_stats, VocabFilter, VocabPage, VocabQuery, VocabSort, LANGUAGES};
+
+/// Messages handled by the `Vocab` page.
+///
+/// ## Variants:
+/// - `PageLoaded(VocabPage)`: Shows a fetched or cached page of vocabulary.
+/// - `SortBy(VocabSort)`: Sorts by a column, flipping the direction when already sorted by it.
+/// - `GoToPage(usize)`: Fetches another page with the same sort order and filter.
+/// - `Filter(VocabFilter)`: Replaces the filter and fetches the first matching page.
+/// - `ToggleStats(i32)`: Shows or hides the statistics of a vocab study, fetching them on first use.
+/// - `StatsLoaded(i32, VocabStats)`: Stores the fetched statistics of a vocab study.
+/// - `FetchError(FetchError)`: Explains a failed request with an `ErrorPanel`.
+/// - `Response(RequestId, Box<Msg>)`: Wraps the message a page fetch sends back. It is handled only if the
+///   fetch is still the latest one, so a slow response for an older query cannot overwrite the table.
+pub enum Msg {
+    PageLoaded(VocabPage),
+    SortBy(VocabSort),
+    GoToPage(usize),
+    Filter(VocabFilter),
+    ToggleStats(i32),
+    StatsLoaded(i32, VocabStats),
+    FetchError(FetchError),
+    Response(RequestId, Box<Msg>),
+}
+
+/// The `Vocab` page, a browser for all the vocabulary the learner is studying.
+///
+/// The vocabulary is shown as a table, one page at a time. Sorting, paging and filtering are done by the
+/// backend: every change to the `VocabQuery` fetches the matching page again. Rows can be expanded to show
+/// the `VocabStats` of the vocab study, and each word links to its `VocabDetail` page. The vocabulary matching the filter, with
+/// its statistics, can be downloaded as CSV or JSON with the `ExportButtons`.
+///
+/// ## Fields:
+/// - `query`: The page, sort order and filter being shown.
+/// - `page`: The last fetched page, `None` while the first page is loading.
+/// - `stats`: The statistics fetched so far, keyed by vocab study id.
+/// - `expanded`: The vocab study whose statistics are shown, if any.
+/// - `error`: The failure to explain, if any.
+/// - `page_request`: The pending page fetch, cancelled when the query changes again or the page is left.
+/// - `store`: The app-wide store holding the selected learner and the cached query results.
+/// - `lang`: The interface language.
+pub struct Vocab {
+    query: VocabQuery,
+    page: Option<VocabPage>,
+    stats: HashMap<i32, VocabStats>,
+    expanded: Option<i32>,
+    error: Option<FetchError>,
+    page_request: LatestRequest,
+    store: Store,
+    lang: String,
+}
+
+impl Vocab {
+
+    /// Translates an interface text into the learner's interface language.
+    fn tr(&self, text: &str) -> String {
+        translate(&self.lang, text)
+    }
+
+    /// Fetches the page described by the current `query`, reporting back with `Msg::PageLoaded`
+    /// or `Msg::FetchError`.
+    ///
+    /// A cached page is reported right away, and only fetched again when it is stale. Loading a page cancels
+    /// the fetch for the previous query.
+    fn load_page(&mut self, link: html::Scope<Self>, awesome_id: i32) {
+        let query = self.query.clone();
+        self.page_request.cancel();
+        if let Some(cached) = peek_vocab_page(&self.store, awesome_id, query.clone()) {
+            link.send_message(Msg::PageLoaded(cached.value));
+            if !cached.stale {
+                return;
+            }
+        }
+
+        let store = self.store.clone();
+        self.page_request.start(|id| async move {
+            let link = link.callback(move |msg| Msg::Response(id, Box::new(msg)));
+            match fetch_vocab_page(&store, awesome_id, query).await {
+                Ok(page) => link.emit(Msg::PageLoaded(page)),
+                Err(err) => link.emit(Msg::FetchError(err)),
+            }
+        });
+    }
+
+    /// Fetches the statistics of a vocab study, reporting back with `Msg::StatsLoaded` or `Msg::FetchError`.
+    ///
+    /// Cached statistics are reported right away, and only fetched again when they are stale.
+    fn load_stats(&self, link: html::Scope<Self>, vocab_study_id: i32) {
+        let cached = peek_vocab_stats(&self.store, vocab_study_id);
+        if let Some(cached) = cached.as_ref() {
+            link.send_message(Msg::StatsLoaded(vocab_study_id, cached.value.clone()));
+        }
+        if cached.is_none_or(|cached| cached.stale) {
+            let store = self.store.clone();
+            spawn_local(async move {
+                match fetch_vocab_stats(&store, vocab_study_id).await {
+                    Ok(stats) => link.send_message(Msg::StatsLoaded(vocab_study_id, stats)),
+                    Err(err) => link.send_message(Msg::FetchError(err)),
+                }
+            });
+        }
+    }
+
+    /// A sortable column header, marked with an arrow when the table is sorted by it.
+    fn sort_header(&self, ctx: &Context<Self>, label: &str, sort: VocabSort) -> Html {
+        let arrow = match (self.query.sort == sort, self.query.descending) {
+            (true, false) => " ▲",
+            (true, true) => " ▼",
+            (false, _) => "",
+        };
+
+        html! {
+            <th class="sortable" onclick={ctx.link().callback(move |_| Msg::SortBy(sort))}>
+                { format!("{}{}", self.tr(label), arrow) }
+            </th>
+        }
+    }
+
+    /// The filter controls. Each control builds a new `VocabFilter` from the current one.
+    fn filters(&self, ctx: &Context<Self>) -> Html {
+        let filter = self.query.filter.clone();
+
+        let on_pos = {
+            let filter = filter.clone();
+            ctx.link().callback(move |e: Event| {
+                let pos = input_value(e);
+                Msg::Filter(VocabFilter { pos: (!pos.is_empty()).then_some(pos), ..filter.clone() })
+            })
+        };
+
+        let on_known = {
+            let filter = filter.clone();
+            ctx.link().callback(move |e: Event| {
+                let code = select_value(e);
+                Msg::Filter(VocabFilter { known_lang_code: (!code.is_empty()).then_some(code), ..filter.clone() })
+            })
+        };
+
+        let on_learning = {
+            let filter = filter.clone();
+            ctx.link().callback(move |e: Event| {
+                let code = select_value(e);
+                Msg::Filter(VocabFilter { learning_lang_code: (!code.is_empty()).then_some(code), ..filter.clone() })
+            })
+        };
+
+        let on_accuracy = {
+            let filter = filter.clone();
+            ctx.link().callback(move |e: Event| {
+                let (min_percentage, max_percentage) = match select_value(e).as_str() {
+                    "low" => (None, Some(50.0)),
+                    "medium" => (Some(50.0), Some(80.0)),
+                    "high" => (Some(80.0), None),
+                    _ => (None, None),
+                };
+                Msg::Filter(VocabFilter { min_percentage, max_percentage, ..filter.clone() })
+            })
+        };
+
+        let on_last_tested = {
+            let filter = filter.clone();
+            ctx.link().callback(move |e: Event| {
+                let not_tested_for_days = select_value(e).parse::<i32>().ok();
+                Msg::Filter(VocabFilter { not_tested_for_days, ..filter.clone() })
+            })
+        };
+
+        let languages = |selected: &Option<String>| -> Html {
+            LANGUAGES.iter().map(|(code, _)| html! {
+                <option value={*code} selected={selected.as_deref() == Some(*code)}>{ translate(&self.lang, code) }</option>
+            }).collect()
+        };
+
+        html! {
+            <div class="filters">
+                <label>{ self.tr("Part of speech") }{ " " }
+                    <input type="text" value={filter.pos.clone().unwrap_or_default()} onchange={on_pos} />
+                </label>
+                <label>{ self.tr("From") }{ " " }
+                    <select onchange={on_known}>
+                        <option value="">{ self.tr("Any") }</option>
+                        { languages(&filter.known_lang_code) }
+                    </select>
+                </label>
+                <label>{ self.tr("To") }{ " " }
+                    <select onchange={on_learning}>
+                        <option value="">{ self.tr("Any") }</option>
+                        { languages(&filter.learning_lang_code) }
+                    </select>
+                </label>
+                <label>{ self.tr("Accuracy") }{ " " }
+                    <select onchange={on_accuracy}>
+                        <option value="">{ self.tr("Any") }</option>
+                        <option value="low">{ self.tr("Below 50%") }</option>
+                        <option value="medium">{ self.tr("50% to 80%") }</option>
+                        <option value="high">{ self.tr("Above 80%") }</option>
+                    </select>
+                </label>
+                <label>{ self.tr("Last tested") }{ " " }
+                    <select onchange={on_last_tested}>
+                        <option value="">{ self.tr("Any time") }</option>
+                        <option value="7">{ self.tr("Over a week ago") }</option>
+                        <option value="30">{ self.tr("Over a month ago") }</option>
+                    </select>
+                </label>
+            </div>
+        }
+    }
+
+    /// The statistics row shown below an expanded vocabulary row.
+    fn stats_row(&self, vocab_study_id: i32) -> Html {
+        let content = match self.stats.get(&vocab_study_id) {
+            Some(stats) => html! {
+                { translate_format(&self.lang, "{} attempts, {} correct ({}%), last change {}%, last tested {}", &[
+                    &stats.attempts, &stats.correct_attempts, &format!("{:.0}", stats.percentage_correct),
+                    &format!("{:+.0}", stats.last_change), &stats.last_tested,
+                ]) }
+            },
+            None => html! { { self.tr("Loading...") } },
+        };
+
+        html! {
+            <tr class="stats"><td colspan="7">{ content }</td></tr>
+        }
+    }
+}
+
+/// Reads the value of the `input` element an event was fired on.
+fn input_value(e: Event) -> String {
+    let target: HtmlInputElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+    target.value().trim().to_string()
+}
+
+/// Reads the value of the `select` element an event was fired on.
+fn select_value(e: Event) -> String {
+    let target: HtmlSelectElement = e.target().unwrap_throw().dyn_into().unwrap_throw();
+    target.value()
+}
+
+impl Component for Vocab {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        Self {
+            query: VocabQuery::default(),
+            page: None,
+            stats: HashMap::new(),
+            expanded: None,
+            error: None,
+            page_request: LatestRequest::default(),
+            lang: store.state().settings.language.clone(),
+            store,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::PageLoaded(page) => {
+                self.page = Some(page);
+                self.error = None;
+                true
+            }
+            Msg::SortBy(sort) => {
+                self.query.sort_by(sort);
+                self.load_page(ctx.link().clone(), self.store.state().learner_id());
+                true
+            }
+            Msg::GoToPage(page) => {
+                self.query.page = page;
+                self.load_page(ctx.link().clone(), self.store.state().learner_id());
+                true
+            }
+            Msg::Filter(filter) => {
+                self.query.filter_by(filter);
+                self.load_page(ctx.link().clone(), self.store.state().learner_id());
+                true
+            }
+            Msg::ToggleStats(vocab_study_id) => {
+                if self.expanded == Some(vocab_study_id) {
+                    self.expanded = None;
+                } else {
+                    self.expanded = Some(vocab_study_id);
+                    if !self.stats.contains_key(&vocab_study_id) {
+                        self.load_stats(ctx.link().clone(), vocab_study_id);
+                    }
+                }
+                true
+            }
+            Msg::StatsLoaded(vocab_study_id, stats) => {
+                self.stats.insert(vocab_study_id, stats);
+                true
+            }
+            Msg::FetchError(err) => {
+                self.error = Some(err);
+                true
+            }
+            Msg::Response(id, msg) => {
+                if self.page_request.finish(id) {
+                    <Self as Component>::update(self, ctx, *msg)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let total = self.page.as_ref().map_or(0, |page| page.total.max(0) as usize);
+        let num_pages = self.query.num_pages(total);
+        let current = self.query.page;
+
+        let rows: Html = self.page.iter().flat_map(|page| page.entries.iter()).map(|entry| {
+            let vocab_study_id = entry.vocab_study_id;
+            let expanded = self.expanded == Some(vocab_study_id);
+            html! {
+                <>
+                    <tr>
+                        <td>
+                            <Link<Route> to={Route::VocabDetail { vocab_study_id }}>
+                                { entry.first_lang.clone() }
+                            </Link<Route>>
+                        </td>
+                        <td>{ entry.learning.clone() }</td>
+                        <td>{ entry.pos.clone() }</td>
+                        <td>{ format!("{} → {}", entry.known_lang_code, entry.learning_lang_code) }</td>
+                        <td>{ format!("{:.0}%", entry.percentage_correct) }</td>
+                        <td>{ entry.last_tested.clone() }</td>
+                        <td>
+                            <button type="button" class="toggle-stats" aria-expanded={expanded.to_string()}
+                                aria-label={translate_format(&self.lang, "Statistics for {}", &[&entry.first_lang])}
+                                onclick={ctx.link().callback(move |_| Msg::ToggleStats(vocab_study_id))}>
+                                { if expanded { "▾" } else { "▸" } }
+                            </button>
+                        </td>
+                    </tr>
+                    if expanded {
+                        { self.stats_row(vocab_study_id) }
+                    }
+                </>
+            }
+        }).collect();
+
+        html! {
+            <section>
+                <h2>{ self.tr("Your Vocabulary") }</h2>
+                <Link<Route> to={Route::AddVocab}>{ self.tr("Add a word") }</Link<Route>>
+                <Link<Route> to={Route::Import}>{ self.tr("Import words") }</Link<Route>>
+                <ExportButtons filter={self.query.filter.clone()}/>
+                { self.filters(ctx) }
+
+                if let Some(error) = self.error.clone() {
+                    <ErrorPanel {error}/>
+                }
+
+                if self.page.is_none() {
+                    if self.error.is_none() {
+                        <p>{ self.tr("Loading...") }</p>
+                    }
+                } else if total == 0 {
+                    <p>{ self.tr("No vocabulary matches these filters.") }</p>
+                } else {
+                    <table class="vocab">
+                        <thead>
+                            <tr>
+                                { self.sort_header(ctx, "Word", VocabSort::FirstLang) }
+                                { self.sort_header(ctx, "Translation", VocabSort::Learning) }
+                                { self.sort_header(ctx, "Part of Speech", VocabSort::Pos) }
+                                <th>{ self.tr("Languages") }</th>
+                                { self.sort_header(ctx, "Accuracy", VocabSort::PercentageCorrect) }
+                                { self.sort_header(ctx, "Last Tested", VocabSort::LastTested) }
+                                <th>{ self.tr("Statistics") }</th>
+                            </tr>
+                        </thead>
+                        <tbody>{ rows }</tbody>
+                    </table>
+
+                    <div class="pager">
+                        <button disabled={current == 0}
+                            onclick={ctx.link().callback(move |_| Msg::GoToPage(current.saturating_sub(1)))}>{ self.tr("Previous") }</button>
+                        <span>{ translate_format(&self.lang, "Page {} of {} ({} words)", &[&(current + 1), &num_pages, &total]) }</span>
+                        <button disabled={current + 1 >= num_pages}
+                            onclick={ctx.link().callback(move |_| Msg::GoToPage(current + 1))}>{ self.tr("Next") }</button>
+                    </div>
+                }
+            </section>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.load_page(ctx.link().clone(), self.store.state().learner_id());
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+use yew_router::scope_ext::RouterScopeExt;
+use crate::components::error_panel::ErrorPanel;
+use crate::components::notes_editor::NotesEditor;
+use crate::components::store::scope_store;
+use crate::route::Route;
+use crate::sl::gql::FetchError;
+use crate::sl::i18n::translate;
+use crate::sl::request::{LatestRequest, RequestId};
+use crate::sl::session::{SessionQuery, DECK_STORAGE_KEY};
+use crate::sl::storage::{save_json, StorageArea};
+use crate::sl::store::Store;
+use crate::sl::study::{fetch_challenge, peek_challenge, update_user_notes, Challenge, NotesSaves, VocabStats};
+use crate::sl::vocab::{fetch_vocab_stats, peek_vocab_stats};
+
+#[derive(Properties, PartialEq, Clone, Debug)]
+pub struct VocabDetailProps {
+    pub vocab_study_id: i32,
+}
+
+/// Messages handled by the `VocabDetail` page.
+///
+/// ## Variants:
+/// - `ChallengeLoaded(Challenge)`: Shows the fetched challenge data of the word.
+/// - `StatsLoaded(VocabStats)`: Shows the fetched statistics of the word.
+/// - `SaveNotes(String)`: Optimistically replaces the notes of the word and saves them.
+/// - `NotesSaved(i32, u64, String)`: Confirms the notes as saved, holding the vocab study id, the sequence
+///   number of the save and the notes as saved.
+/// - `NotesSaveFailed(i32, u64, FetchError)`: Restores the last saved notes after the latest save failed,
+///   holding the vocab study id, the sequence number of the save and the error.
+/// - `Practise`: Starts a study session made up of just this word.
+/// - `FetchError(FetchError)`: Explains a failed request with an `ErrorPanel`.
+/// - `Response(RequestId, Box<Msg>)`: Wraps the message a challenge or statistics fetch sends back. It is
+///   handled only if the fetch is still the latest of its kind, so a response for the previously shown word
+///   cannot overwrite this one.
+pub enum Msg {
+    ChallengeLoaded(Challenge),
+    StatsLoaded(VocabStats),
+    SaveNotes(String),
+    NotesSaved(i32, u64, String),
+    NotesSaveFailed(i32, u64, FetchError),
+    Practise,
+    FetchError(FetchError),
+    Response(RequestId, Box<Msg>),
+}
+
+/// The `VocabDetail` page, showing everything about a single word the learner is studying.
+///
+/// The page is addressed by vocab study id (`/vocab/:vocab_study_id`). It fetches the word's `Challenge`
+/// and its `VocabStats` side by side, shows both, lets the learner edit their notes and offers a focused
+/// study session for just this word.
+///
+/// ## Fields:
+/// - `challenge`: The word's challenge data, `None` while loading.
+/// - `stats`: The word's statistics, `None` while loading.
+/// - `notes_saves`: The notes saves in flight, so only the outcome of the latest save is applied.
+/// - `notes_error`: The error of the last failed notes save.
+/// - `error`: The failure to explain, if any.
+/// - `challenge_request`: The pending fetch of the challenge, cancelled when another word is shown.
+/// - `stats_request`: The pending fetch of the statistics, cancelled when another word is shown.
+/// - `store`: The app-wide store holding the selected learner and the cached query results.
+/// - `lang`: The interface language.
+pub struct VocabDetail {
+    challenge: Option<Challenge>,
+    stats: Option<VocabStats>,
+    notes_saves: NotesSaves,
+    notes_error: Option<String>,
+    error: Option<FetchError>,
+    challenge_request: LatestRequest,
+    stats_request: LatestRequest,
+    store: Store,
+    lang: String,
+}
+
+impl VocabDetail {
+
+    /// Translates an interface text into the learner's interface language.
+    fn tr(&self, text: &str) -> String {
+        translate(&self.lang, text)
+    }
+
+    /// Fetches the challenge and the statistics of the vocab study, reporting back with
+    /// `Msg::ChallengeLoaded`, `Msg::StatsLoaded` or `Msg::FetchError`.
+    ///
+    /// Cached results are reported right away, and only fetched again when they are stale. The fetches are
+    /// wrapped in `Msg::Response`, so only those for the word being shown are handled.
+    fn load(&mut self, link: html::Scope<Self>, vocab_study_id: i32) {
+        let cached_challenge = peek_challenge(&self.store, vocab_study_id);
+        let cached_stats = peek_vocab_stats(&self.store, vocab_study_id);
+
+        if let Some(cached) = cached_challenge.as_ref() {
+            link.send_message(Msg::ChallengeLoaded(cached.value.clone()));
+        }
+        if cached_challenge.is_none_or(|cached| cached.stale) {
+            let link = link.clone();
+            let store = self.store.clone();
+            self.challenge_request.start(|id| async move {
+                let link = link.callback(move |msg| Msg::Response(id, Box::new(msg)));
+                match fetch_challenge(&store, vocab_study_id).await {
+                    Ok(challenge) => link.emit(Msg::ChallengeLoaded(challenge)),
+                    Err(err) => link.emit(Msg::FetchError(err)),
+                }
+            });
+        }
+
+        if let Some(cached) = cached_stats.as_ref() {
+            link.send_message(Msg::StatsLoaded(cached.value.clone()));
+        }
+        if cached_stats.is_none_or(|cached| cached.stale) {
+            let store = self.store.clone();
+            self.stats_request.start(|id| async move {
+                let link = link.callback(move |msg| Msg::Response(id, Box::new(msg)));
+                match fetch_vocab_stats(&store, vocab_study_id).await {
+                    Ok(stats) => link.emit(Msg::StatsLoaded(stats)),
+                    Err(err) => link.emit(Msg::FetchError(err)),
+                }
+            });
+        }
+    }
+
+    fn stats_view(&self) -> Html {
+        match &self.stats {
+            Some(stats) => html! {
+                <table class="summary">
+                    <tr><th>{ self.tr("Attempts") }</th><td>{ stats.attempts }</td></tr>
+                    <tr><th>{ self.tr("Correct") }</th><td>{ stats.correct_attempts }</td></tr>
+                    <tr><th>{ self.tr("Accuracy") }</th><td>{ format!("{:.0}%", stats.percentage_correct) }</td></tr>
+                    <tr><th>{ self.tr("Last change") }</th><td>{ format!("{:+.0}%", stats.last_change) }</td></tr>
+                    <tr><th>{ self.tr("Last tested") }</th><td>{ stats.last_tested.clone() }</td></tr>
+                </table>
+            },
+            None => html! { <p>{ self.tr("Loading statistics...") }</p> },
+        }
+    }
+}
+
+impl Component for VocabDetail {
+    type Message = Msg;
+    type Properties = VocabDetailProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = scope_store(ctx.link());
+        Self {
+            challenge: None,
+            stats: None,
+            notes_saves: NotesSaves::default(),
+            notes_error: None,
+            error: None,
+            challenge_request: LatestRequest::default(),
+            stats_request: LatestRequest::default(),
+            lang: store.state().settings.language.clone(),
+            store,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ChallengeLoaded(challenge) => {
+                self.challenge = Some(challenge);
+                true
+            }
+            Msg::StatsLoaded(stats) => {
+                self.stats = Some(stats);
+                true
+            }
+            Msg::SaveNotes(notes) => {
+                let Some(challenge) = self.challenge.as_mut() else {
+                    return false;
+                };
+
+                let previous = std::mem::replace(&mut challenge.user_notes, notes.clone());
+                self.notes_error = None;
+
+                let link = ctx.link().clone();
+                let vocab_study_id = ctx.props().vocab_study_id;
+                let seq = self.notes_saves.begin(vocab_study_id, &previous);
+                let store = self.store.clone();
+                spawn_local(async move {
+                    match update_user_notes(&store, vocab_study_id, notes).await {
+                        Ok(saved) => link.send_message(Msg::NotesSaved(vocab_study_id, seq, saved)),
+                        Err(err) => link.send_message(Msg::NotesSaveFailed(vocab_study_id, seq, err)),
+                    }
+                });
+                true
+            }
+            Msg::NotesSaved(vocab_study_id, seq, notes) => {
+                if !self.notes_saves.succeed(vocab_study_id, seq, &notes) {
+                    return false;
+                }
+                if let Some(challenge) = self.challenge.as_mut() {
+                    challenge.user_notes = notes;
+                }
+                true
+            }
+            Msg::NotesSaveFailed(vocab_study_id, seq, err) => {
+                let Some(saved) = self.notes_saves.fail(vocab_study_id, seq) else {
+                    return false;
+                };
+                if let Some(challenge) = self.challenge.as_mut() {
+                    challenge.user_notes = saved;
+                }
+                self.notes_error = Some(self.tr(err.kind.title()));
+                true
+            }
+            Msg::Practise => {
+                let Some(challenge) = self.challenge.clone() else {
+                    return false;
+                };
+
+                match save_json(StorageArea::Session, DECK_STORAGE_KEY, &vec![challenge]) {
+                    Ok(_) => {
+                        if let Some(navigator) = ctx.link().navigator() {
+                            navigator.push_with_query(&Route::Study, &SessionQuery::items(1))
+                                .unwrap_or_default();
+                        }
+                        false
+                    }
+                    Err(err) => {
+                        self.error = Some(err.into());
+                        true
+                    }
+                }
+            }
+            Msg::FetchError(err) => {
+                self.error = Some(err);
+                true
+            }
+            Msg::Response(id, msg) => {
+                if self.challenge_request.finish(id) || self.stats_request.finish(id) {
+                    <Self as Component>::update(self, ctx, *msg)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        if old_props.vocab_study_id != ctx.props().vocab_study_id {
+            *self = <Self as Component>::create(ctx);
+            self.load(ctx.link().clone(), ctx.props().vocab_study_id);
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let Some(challenge) = &self.challenge else {
+            return html! {
+                <section>
+                    if let Some(error) = self.error.clone() {
+                        <ErrorPanel {error}/>
+                    } else {
+                        <p>{ self.tr("Loading...") }</p>
+                    }
+                    <Link<Route> to={Route::Vocab}>{ self.tr("Back to my words") }</Link<Route>>
+                </section>
+            };
+        };
+
+        html! {
+            <section>
+                <h2>{ format!("{} → {}", challenge.first_lang, challenge.learning) }</h2>
+                <table class="summary">
+                    <tr><th>{ self.tr("Languages") }</th><td>{ format!("{} → {}", challenge.known_lang_code, challenge.learning_lang_code) }</td></tr>
+                    if !challenge.pos.is_empty() {
+                        <tr><th>{ self.tr("Part of Speech") }</th><td>{ challenge.pos.clone() }</td></tr>
+                    }
+                    if !challenge.infinitive.is_empty() {
+                        <tr><th>{ self.tr("Infinitive") }</th><td>{ challenge.infinitive.clone() }</td></tr>
+                    }
+                    if !challenge.hint.is_empty() {
+                        <tr><th>{ self.tr("Other Hints") }</th><td>{ challenge.hint.clone() }</td></tr>
+                    }
+                    <tr><th>{ self.tr("Words in phrase") }</th><td>{ challenge.num_learning_words }</td></tr>
+                </table>
+
+                <h3>{ self.tr("Statistics") }</h3>
+                { self.stats_view() }
+
+                <h3>{ self.tr("Notes") }</h3>
+                <NotesEditor
+                    notes={challenge.user_notes.clone()}
+                    saving={self.notes_saves.is_saving(ctx.props().vocab_study_id)}
+                    error={self.notes_error.clone()}
+                    on_save={ctx.link().callback(Msg::SaveNotes)}/>
+
+                if let Some(error) = self.error.clone() {
+                    <ErrorPanel {error}/>
+                }
+
+                <button onclick={ctx.link().callback(|_| Msg::Practise)}>{ self.tr("Practise just this word") }</button>
+                <Link<Route> to={Route::Vocab}>{ self.tr("Back to my words") }</Link<Route>>
+            </section>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.load(ctx.link().clone(), ctx.props().vocab_study_id);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use yew::{Html, html};
 use yew_router::prelude::*;
 
-use crate::pages::{home::Home, study::Study, page_not_found::PageNotFound};
+use crate::pages::{add_vocab::AddVocab, home::Home, import::Import, schedule::ScheduleDebug, study::Study, summary::Summary, vocab::Vocab, vocab_detail::VocabDetail, page_not_found::PageNotFound, profile::Profile, settings::SettingsPage};
 
 /// Enum representing the routes in the application, used with `yew_router`.
 ///
@@ -12,6 +12,14 @@ use crate::pages::{home::Home, study::Study, page_not_found::PageNotFound};
 /// ## Variants:
 /// - `Home`: The root path (`"/"`), corresponding to the application's home page.
 /// - `Study`: The study page (`"/study"`), dedicated to study-related content.
+/// - `Summary`: The session summary page (`"/summary"`), shown when a study session completes.
+/// - `Vocab`: The vocabulary browser (`"/vocab"`), listing everything the learner is studying.
+/// - `VocabDetail`: The detail page of a single word (`"/vocab/:vocab_study_id"`).
+/// - `AddVocab`: The form to add a new word (`"/add-word"`).
+/// - `Import`: The page importing words from a file (`"/import"`).
+/// - `Profile`: The learner's goals, streaks and study calendar (`"/profile"`).
+/// - `Settings`: The learner's study and display preferences (`"/settings"`).
+/// - `Schedule`: A debug view of the client-side review schedule (`"/debug/schedule"`).
 /// - `NotFound`: A catch-all route (`"/404"`) used when a requested route is not found.
 ///
 /// ## Derived Attributes:
@@ -30,6 +38,22 @@ pub enum Route {
     Home,
     #[at("/study")]
     Study,
+    #[at("/summary")]
+    Summary,
+    #[at("/vocab")]
+    Vocab,
+    #[at("/vocab/:vocab_study_id")]
+    VocabDetail { vocab_study_id: i32 },
+    #[at("/add-word")]
+    AddVocab,
+    #[at("/import")]
+    Import,
+    #[at("/profile")]
+    Profile,
+    #[at("/settings")]
+    Settings,
+    #[at("/debug/schedule")]
+    Schedule,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -49,6 +73,14 @@ pub enum Route {
 ///
 /// ## Supported Routes:
 /// - `Route::Study`: Renders the `Study` component.
+/// - `Route::Summary`: Renders the `Summary` component for the last completed session.
+/// - `Route::Vocab`: Renders the `Vocab` browser.
+/// - `Route::VocabDetail`: Renders the `VocabDetail` page for the vocab study in the path.
+/// - `Route::AddVocab`: Renders the `AddVocab` form.
+/// - `Route::Import`: Renders the `Import` page.
+/// - `Route::Profile`: Renders the `Profile` page.
+/// - `Route::Settings`: Renders the `SettingsPage`.
+/// - `Route::Schedule`: Renders the `ScheduleDebug` view.
 /// - `Route::Home`: Renders the `Home` component as the landing page.
 /// - `Route::NotFound`: Renders the `PageNotFound` component for unmatched routes.
 ///
@@ -56,9 +88,33 @@ pub enum Route {
 /// Given a URL path that matches `/study`, the `switch` function will render the `Study` component.
 pub fn switch(routes: Route) -> Html {
     match routes {
-        Route::Study { } => {
+        Route::Study => {
             html! { <Study  /> }
         }
+        Route::Summary => {
+            html! { <Summary /> }
+        }
+        Route::Vocab => {
+            html! { <Vocab /> }
+        }
+        Route::VocabDetail { vocab_study_id } => {
+            html! { <VocabDetail {vocab_study_id} /> }
+        }
+        Route::AddVocab => {
+            html! { <AddVocab /> }
+        }
+        Route::Import => {
+            html! { <Import /> }
+        }
+        Route::Profile => {
+            html! { <Profile /> }
+        }
+        Route::Settings => {
+            html! { <SettingsPage /> }
+        }
+        Route::Schedule => {
+            html! { <ScheduleDebug /> }
+        }
         Route::Home => {
             html! { <Home /> }
         }
@@ -0,0 +1,116 @@
+use graphql_client::{GraphQLQuery, QueryBody};
+use serde::{Deserialize, Serialize};
+use crate::sl::gql::{cached_query, peek_query, post_gql_query, CacheTag, Cached, FetchError, QueryKey};
+use crate::sl::store::Store;
+use crate::sl::storage::{load_json, remove, save_json, StorageArea};
+
+/// The awesome person studied for when no learner has been selected in this browser.
+pub const DEFAULT_AWESOME_ID: i32 = 1;
+
+/// Local storage key holding the id of the awesome person selected in this browser.
+pub const LEARNER_STORAGE_KEY: &str = "awesome_id";
+
+/// How long a fetched `AwesomeProfile` is used before it is fetched again, in milliseconds.
+pub const PROFILE_TTL_MS: f64 = 5.0 * 60_000.0;
+
+/// Response JSON wrapper
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AwesomeProfileResponseWrapper {
+    pub data: AwesomeProfileData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AwesomeProfileData {
+    #[serde(rename = "getAwesomePerson")]
+    pub get_awesome_person: AwesomeProfile,
+}
+
+/// The overall statistics of an awesome person, i.e. a learner.
+///
+/// # Fields
+///
+/// - `id`: The awesome id of the learner.
+/// - `num_known`: The number of vocabulary items the learner knows.
+/// - `num_correct`, `num_incorrect`: The number of correct and incorrect answers overall.
+/// - `total_percentage`: The share of correct answers, from 0 to 100.
+/// - `name`: The learner's name.
+/// - `smallest_vocab`: The length of the shortest vocabulary the learner is studying.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct AwesomeProfile {
+    pub id: i32,
+
+    #[serde(rename = "numKnown")]
+    pub num_known: i32,
+
+    #[serde(rename = "numCorrect")]
+    pub num_correct: i32,
+
+    #[serde(rename = "numIncorrect")]
+    pub num_incorrect: i32,
+
+    #[serde(rename = "totalPercentage")]
+    pub total_percentage: f64,
+
+    pub name: String,
+
+    #[serde(rename = "smallestVocab")]
+    pub smallest_vocab: i32,
+}
+
+/// The learner selected in this browser, `None` for anonymous visitors.
+pub fn selected_learner() -> Option<i32> {
+    load_json(StorageArea::Local, LEARNER_STORAGE_KEY)
+}
+
+/// Selects the learner whose vocabulary is studied in this browser.
+pub fn select_learner(awesome_id: i32) -> Result<(), wasm_bindgen::JsValue> {
+    save_json(StorageArea::Local, LEARNER_STORAGE_KEY, &awesome_id)
+}
+
+/// Forgets the selected learner, making the visitor anonymous again.
+pub fn forget_learner() {
+    remove(StorageArea::Local, LEARNER_STORAGE_KEY);
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/awesome_person.graphql",
+response_derives = "Debug"
+)]
+struct AwesomePersonQuery;
+
+fn awesome_profile_query(awesome_id: i32) -> QueryBody<awesome_person_query::Variables> {
+    AwesomePersonQuery::build_query(awesome_person_query::Variables {
+        awesome_id: awesome_id.into(),
+    })
+}
+
+/// Fetches the overall statistics of an awesome person.
+///
+/// The statistics are cached for `PROFILE_TTL_MS`, or until an answer of the learner is checked.
+///
+/// # Arguments
+///
+/// * `store` - The store caching the statistics.
+/// * `awesome_id` - The unique identifier of the awesome person.
+///
+/// # Returns
+///
+/// A `Result` wrapping the `AwesomeProfile` on success, or a `FetchError` on failure.
+pub async fn fetch_awesome_profile(store: &Store, awesome_id: i32) -> Result<AwesomeProfile, FetchError> {
+    let build_query = awesome_profile_query(awesome_id);
+
+    cached_query(store, QueryKey::of(&build_query), PROFILE_TTL_MS, vec![CacheTag::Learner(awesome_id)], || async move {
+        // Serialize the query to a string
+        let query_string = serde_json::to_string(&build_query)?;
+        let gql_json_res = post_gql_query(query_string).await?;
+        let wrapper: AwesomeProfileResponseWrapper = serde_json::from_str(&gql_json_res)?;
+        Ok(wrapper.data.get_awesome_person)
+    }).await
+}
+
+/// The cached statistics of an awesome person, fresh or stale, without fetching them.
+pub fn peek_awesome_profile(store: &Store, awesome_id: i32) -> Option<Cached<AwesomeProfile>> {
+    peek_query(store, &QueryKey::of(&awesome_profile_query(awesome_id)))
+}
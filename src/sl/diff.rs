@@ -0,0 +1,143 @@
+/// The kind of change a segment of a character-level answer diff represents.
+///
+/// The diff is always read from the learner's point of view: it describes what would need to
+/// happen to the entered answer for it to become the expected answer.
+///
+/// ## Variants:
+/// - `Equal`: Characters the learner typed correctly (letter case is ignored).
+/// - `Insert`: Characters missing from the learner's answer that the expected answer contains.
+/// - `Delete`: Extra characters the learner typed that are not part of the expected answer.
+/// - `Accent`: Characters with the right base letter but the wrong or missing accent, e.g. `e` for `é`.
+///   The segment text holds the expected, accented characters.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+    Accent,
+}
+
+impl DiffKind {
+    /// CSS class used when rendering a segment of this kind.
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            DiffKind::Equal => "diff-equal",
+            DiffKind::Insert => "diff-insert",
+            DiffKind::Delete => "diff-delete",
+            DiffKind::Accent => "diff-accent",
+        }
+    }
+}
+
+/// A run of consecutive characters sharing the same `DiffKind`.
+///
+/// # Fields
+///
+/// - `kind`: What happened to the characters in this run.
+/// - `text`: The characters themselves. For `Delete` these come from the entered answer, for
+///   every other kind they come from the expected answer.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DiffSegment {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+impl DiffSegment {
+    pub fn new(kind: DiffKind, text: &str) -> Self {
+        DiffSegment { kind, text: text.to_string() }
+    }
+}
+
+/// Strips the accent from a lower cased Latin character, leaving any other character as is.
+///
+/// Covers the accented letters used by the languages the app ships translations for
+/// (Spanish, French, German, Italian and Portuguese).
+fn base_letter(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'ö' | 'õ' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ç' => 'c',
+        'ñ' => 'n',
+        _ => c,
+    }
+}
+
+/// Lower cases a single character, keeping it a single character.
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Two characters are considered the same letter when they match after lower casing and accent removal.
+fn same_letter(a: char, b: char) -> bool {
+    base_letter(lower(a)) == base_letter(lower(b))
+}
+
+/// Appends a character to the segment list, extending the last segment when the kind matches.
+fn push_char(segments: &mut Vec<DiffSegment>, kind: DiffKind, c: char) {
+    match segments.last_mut() {
+        Some(last) if last.kind == kind => last.text.push(c),
+        _ => segments.push(DiffSegment { kind, text: c.to_string() }),
+    }
+}
+
+/// Computes a character-level diff between what the learner entered and the expected answer.
+///
+/// The diff is based on the longest common subsequence of the two answers, where characters are
+/// matched on their base letter. Matched characters that differ only by their accent are reported
+/// as `DiffKind::Accent`, so that a forgotten `´` can be styled differently from a wrong letter.
+/// Letter case and leading/trailing whitespace are ignored.
+///
+/// # Arguments
+///
+/// * `entered` - The answer typed by the learner.
+/// * `expected` - The correct answer for the challenge.
+///
+/// # Returns
+///
+/// The list of `DiffSegment` runs, in display order. Where a letter was substituted, the extra
+/// entered characters (`Delete`) come before the missing expected characters (`Insert`).
+pub fn diff_answer(entered: &str, expected: &str) -> Vec<DiffSegment> {
+    let entered: Vec<char> = entered.trim().chars().collect();
+    let expected: Vec<char> = expected.trim().chars().collect();
+    let (n, m) = (entered.len(), expected.len());
+
+    // lcs[i][j] holds the length of the longest common subsequence of entered[i..] and expected[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if same_letter(entered[i], expected[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut segments = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && same_letter(entered[i], expected[j]) {
+            let kind = if lower(entered[i]) == lower(expected[j]) { DiffKind::Equal } else { DiffKind::Accent };
+            push_char(&mut segments, kind, expected[j]);
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            push_char(&mut segments, DiffKind::Delete, entered[i]);
+            i += 1;
+        } else {
+            push_char(&mut segments, DiffKind::Insert, expected[j]);
+            j += 1;
+        }
+    }
+
+    segments
+}
+
+/// Returns `true` when the diff contains nothing but correctly typed characters.
+pub fn is_exact(segments: &[DiffSegment]) -> bool {
+    segments.iter().all(|segment| segment.kind == DiffKind::Equal)
+}
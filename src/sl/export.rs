@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+use crate::sl::gql::FetchError;
+use crate::sl::store::Store;
+use crate::sl::vocab::{fetch_vocab_page, VocabEntry, VocabFilter, VocabQuery};
+
+/// Number of rows fetched per request when exporting the whole vocabulary.
+pub const EXPORT_PAGE_SIZE: usize = 200;
+
+/// How long the object URL of a download is kept before it is revoked, in milliseconds. Revoking it right
+/// after the click can cancel the download in some browsers.
+pub const REVOKE_DELAY_MS: i32 = 60_000;
+
+/// The column names of a CSV export, in order.
+pub const CSV_COLUMNS: [&str; 11] = [
+    "vocab_id",
+    "vocab_study_id",
+    "first_lang",
+    "learning",
+    "pos",
+    "known_lang_code",
+    "learning_lang_code",
+    "attempts",
+    "correct_attempts",
+    "percentage_correct",
+    "last_tested",
+];
+
+/// The file formats the vocabulary can be exported to.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv;charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    /// The name of the downloaded file, e.g. `vocabulary-2024-05-01.csv`.
+    pub fn file_name(&self, date: &str) -> String {
+        format!("vocabulary-{}.{}", date, self.extension())
+    }
+
+    /// Formats the entries as the contents of an export file.
+    pub fn export(&self, entries: &[VocabEntry]) -> Result<String, serde_json::Error> {
+        match self {
+            ExportFormat::Csv => Ok(export_csv(entries)),
+            ExportFormat::Json => export_json(entries),
+        }
+    }
+}
+
+/// An exported vocabulary item with its statistics, using the same field names as the CSV columns.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ExportRecord {
+    pub vocab_id: i32,
+    pub vocab_study_id: i32,
+    pub first_lang: String,
+    pub learning: String,
+    pub pos: String,
+    pub known_lang_code: String,
+    pub learning_lang_code: String,
+    pub attempts: i32,
+    pub correct_attempts: i32,
+    pub percentage_correct: f64,
+    pub last_tested: String,
+}
+
+impl From<&VocabEntry> for ExportRecord {
+    fn from(entry: &VocabEntry) -> Self {
+        ExportRecord {
+            vocab_id: entry.vocab_id,
+            vocab_study_id: entry.vocab_study_id,
+            first_lang: entry.first_lang.clone(),
+            learning: entry.learning.clone(),
+            pos: entry.pos.clone(),
+            known_lang_code: entry.known_lang_code.clone(),
+            learning_lang_code: entry.learning_lang_code.clone(),
+            attempts: entry.attempts,
+            correct_attempts: entry.correct_attempts,
+            percentage_correct: entry.percentage_correct,
+            last_tested: entry.last_tested.clone(),
+        }
+    }
+}
+
+/// Quotes a CSV field when it holds a comma, quote or line break.
+///
+/// A field starting with `=`, `+`, `-`, `@`, a tab or a carriage return is prefixed with `'`, so spreadsheets
+/// show it as text instead of running it as a formula.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Formats the entries as CSV with a header row of `CSV_COLUMNS`.
+pub fn export_csv(entries: &[VocabEntry]) -> String {
+    let mut csv = CSV_COLUMNS.join(",") + "\r\n";
+    for entry in entries {
+        let fields = [
+            entry.vocab_id.to_string(),
+            entry.vocab_study_id.to_string(),
+            entry.first_lang.clone(),
+            entry.learning.clone(),
+            entry.pos.clone(),
+            entry.known_lang_code.clone(),
+            entry.learning_lang_code.clone(),
+            entry.attempts.to_string(),
+            entry.correct_attempts.to_string(),
+            format!("{:.1}", entry.percentage_correct),
+            entry.last_tested.clone(),
+        ];
+        csv += &fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",");
+        csv += "\r\n";
+    }
+    csv
+}
+
+/// Formats the entries as a pretty printed JSON array of `ExportRecord`.
+pub fn export_json(entries: &[VocabEntry]) -> Result<String, serde_json::Error> {
+    let records: Vec<ExportRecord> = entries.iter().map(ExportRecord::from).collect();
+    serde_json::to_string_pretty(&records)
+}
+
+/// Fetches every vocabulary item matching the filter, page by page.
+///
+/// # Arguments
+///
+/// * `store` - The store caching the fetched pages.
+/// * `awesome_id` - The unique identifier of the user whose vocabulary is exported.
+/// * `filter` - Which items to export.
+///
+/// # Returns
+///
+/// A `Result` wrapping all the matching `VocabEntry` rows on success, or a `FetchError` on failure.
+pub async fn fetch_all_vocab(store: &Store, awesome_id: i32, filter: VocabFilter) -> Result<Vec<VocabEntry>, FetchError> {
+    let mut query = VocabQuery { page_size: EXPORT_PAGE_SIZE, filter, ..Default::default() };
+    let mut entries = Vec::new();
+
+    loop {
+        let page = fetch_vocab_page(store, awesome_id, query.clone()).await?;
+        let fetched = page.entries.len();
+        entries.extend(page.entries);
+
+        if fetched == 0 || entries.len() >= page.total.max(0) as usize {
+            return Ok(entries);
+        }
+        query.page += 1;
+    }
+}
+
+/// Makes the browser download `contents` as a file.
+///
+/// The contents are wrapped in a `Blob`, linked from a temporary anchor element which is clicked, and the
+/// object URL is revoked `REVOKE_DELAY_MS` later, once the browser has started the download.
+///
+/// # Returns
+///
+/// * `Result<(), JsValue>`: An error when the page has no document or the `Blob` cannot be created.
+pub fn download(file_name: &str, mime_type: &str, contents: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("The page has no window"))?;
+    let document = window.document().ok_or_else(|| JsValue::from_str("The page has no document"))?;
+
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime_type);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let anchor: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    let revoke = Closure::once_into_js(move || Url::revoke_object_url(&url).unwrap_or_default());
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), REVOKE_DELAY_MS)?;
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::sl::i18n::translate_format;
+
+/// Local storage key, per learner with `learner_key`, holding the learner's `DailyGoal`.
+pub const GOAL_STORAGE_KEY: &str = "study_daily_goal";
+
+/// Local storage key, per learner with `learner_key`, holding the learner's `ActivityLog`.
+pub const ACTIVITY_STORAGE_KEY: &str = "study_activity";
+
+/// Milliseconds in a day.
+const DAY_MS: f64 = 86_400_000.0;
+
+/// Longest time counted for a single answer, so a learner walking away does not count as studying.
+pub const MAX_ANSWER_MS: f64 = 2.0 * 60_000.0;
+
+/// Number of weeks shown in the activity heatmap.
+pub const HEATMAP_WEEKS: usize = 53;
+
+/// How much a learner wants to study every day.
+///
+/// ## Variants:
+/// - `Items(u32)`: Answer this many challenges a day.
+/// - `Minutes(u32)`: Study this many minutes a day.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum DailyGoal {
+    Items(u32),
+    Minutes(u32),
+}
+
+impl Default for DailyGoal {
+    fn default() -> Self {
+        DailyGoal::Items(10)
+    }
+}
+
+impl DailyGoal {
+    /// The share of the goal reached by a day's activity, from 0 and unbounded above.
+    pub fn progress(&self, activity: &DayActivity) -> f64 {
+        match *self {
+            DailyGoal::Items(items) => activity.items as f64 / items.max(1) as f64,
+            DailyGoal::Minutes(minutes) => activity.minutes() / minutes.max(1) as f64,
+        }
+    }
+
+    pub fn is_met(&self, activity: &DayActivity) -> bool {
+        self.progress(activity) >= 1.0
+    }
+
+    /// Describes the goal in the interface language `lang`, e.g. `10 items a day`.
+    pub fn display(&self, lang: &str) -> String {
+        match self {
+            DailyGoal::Items(items) => translate_format(lang, "{} items a day", &[items]),
+            DailyGoal::Minutes(minutes) => translate_format(lang, "{} minutes a day", &[minutes]),
+        }
+    }
+}
+
+/// The study done on one day.
+///
+/// # Fields
+///
+/// - `items`: The number of answers checked.
+/// - `elapsed_ms`: The time spent answering, in milliseconds.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub struct DayActivity {
+    pub items: u32,
+    pub elapsed_ms: f64,
+}
+
+impl DayActivity {
+    pub fn minutes(&self) -> f64 {
+        self.elapsed_ms / 60_000.0
+    }
+}
+
+/// The study done per day, by day number as returned by `day_number`.
+pub type ActivityLog = BTreeMap<i64, DayActivity>;
+
+/// The local day a time falls on, counted in days since 1970-01-01.
+///
+/// # Arguments
+///
+/// * `ms` - The time in milliseconds since the epoch.
+/// * `timezone_offset_minutes` - The offset of the local time zone as returned by JavaScript's
+///   `Date.getTimezoneOffset`, i.e. UTC minus local time.
+pub fn day_number(ms: f64, timezone_offset_minutes: f64) -> i64 {
+    ((ms - timezone_offset_minutes * 60_000.0) / DAY_MS).floor() as i64
+}
+
+/// The time a local day ends, in milliseconds since the epoch.
+///
+/// # Arguments
+///
+/// * `day` - The day number.
+/// * `timezone_offset_minutes` - The offset of the local time zone, as for `day_number`.
+pub fn day_end_ms(day: i64, timezone_offset_minutes: f64) -> f64 {
+    (day + 1) as f64 * DAY_MS + timezone_offset_minutes * 60_000.0
+}
+
+/// The local day a time falls on in the browser's time zone.
+pub fn local_day(ms: f64) -> i64 {
+    day_number(ms, js_sys::Date::new(&ms.into()).get_timezone_offset())
+}
+
+/// Formats a day number as `YYYY-MM-DD`.
+pub fn day_display(day: i64) -> String {
+    // Civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// The day of the week of a day number, from 0 for Sunday to 6 for Saturday.
+pub fn weekday(day: i64) -> i64 {
+    // 1970-01-01 was a Thursday
+    (day + 4).rem_euclid(7)
+}
+
+/// Adds a checked answer to the activity of its day.
+///
+/// # Arguments
+///
+/// * `log` - The activity log to update.
+/// * `day` - The day the answer was checked on.
+/// * `elapsed_ms` - The time spent on the answer, counted up to `MAX_ANSWER_MS`.
+pub fn record_answer(log: &mut ActivityLog, day: i64, elapsed_ms: f64) {
+    let activity = log.entry(day).or_default();
+    activity.items += 1;
+    activity.elapsed_ms += elapsed_ms.clamp(0.0, MAX_ANSWER_MS);
+}
+
+/// The number of days in a row the goal was met, up to `today`.
+///
+/// A streak is not broken by today until the day is over: when today's goal is not met yet, the streak
+/// ending yesterday is returned.
+pub fn current_streak(log: &ActivityLog, goal: &DailyGoal, today: i64) -> u32 {
+    let met = |day: i64| log.get(&day).is_some_and(|activity| goal.is_met(activity));
+    let last = if met(today) { today } else { today - 1 };
+
+    (0..).take_while(|offset| met(last - offset)).count() as u32
+}
+
+/// The most days in a row the goal was ever met.
+pub fn longest_streak(log: &ActivityLog, goal: &DailyGoal) -> u32 {
+    let mut longest = 0;
+    let mut streak = 0;
+    let mut previous: Option<i64> = None;
+
+    for (&day, activity) in log.iter() {
+        if !goal.is_met(activity) {
+            streak = 0;
+        } else if previous == Some(day - 1) && streak > 0 {
+            streak += 1;
+        } else {
+            streak = 1;
+        }
+        previous = Some(day);
+        longest = longest.max(streak);
+    }
+
+    longest
+}
+
+/// A day of the activity heatmap.
+///
+/// # Fields
+///
+/// - `day`: The day number.
+/// - `activity`: The study done that day.
+/// - `level`: The shade of the cell: 0 without study, 1 below half the goal, 2 below the goal, 3 when the
+///   goal is met and 4 when it is doubled.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct HeatCell {
+    pub day: i64,
+    pub activity: DayActivity,
+    pub level: u8,
+}
+
+/// The shade of a heatmap cell for a day's activity.
+pub fn heat_level(goal: &DailyGoal, activity: &DayActivity) -> u8 {
+    if activity.items == 0 {
+        return 0;
+    }
+
+    match goal.progress(activity) {
+        p if p < 0.5 => 1,
+        p if p < 1.0 => 2,
+        p if p < 2.0 => 3,
+        _ => 4,
+    }
+}
+
+/// Lays out the activity of the last `weeks` weeks as a GitHub style heatmap.
+///
+/// # Returns
+///
+/// * `Vec<Vec<HeatCell>>`: One column per week, oldest first, each running from Sunday to Saturday. The last
+///   column ends with `today`, so it may be shorter.
+pub fn heatmap(log: &ActivityLog, goal: &DailyGoal, today: i64, weeks: usize) -> Vec<Vec<HeatCell>> {
+    let first = today - weekday(today) - 7 * (weeks.max(1) as i64 - 1);
+
+    (0..weeks.max(1) as i64).map(|week| {
+        (0..7).map(|offset| first + 7 * week + offset)
+            .take_while(|&day| day <= today)
+            .map(|day| {
+                let activity = log.get(&day).copied().unwrap_or_default();
+                HeatCell { day, activity, level: heat_level(goal, &activity) }
+            })
+            .collect()
+    }).collect()
+}
@@ -1,7 +1,81 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use graphql_client::QueryBody;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use wasm_bindgen::JsValue;
+use crate::sl::store::Store;
+
+/// What kind of failure a `FetchError` is, to tell the learner what went wrong and what they can do about it.
+///
+/// ## Variants:
+/// - `Offline`: The browser has no network connection.
+/// - `BackendDown`: The backend could not be reached or failed with a server error.
+/// - `Unauthorized`: The backend refused the request for the learner.
+/// - `Validation`: The backend rejected the GraphQL operation or its variables.
+/// - `Decode`: The response did not match the expected schema, e.g. after a backend upgrade.
+/// - `Other`: Any other failure.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ErrorKind {
+    Offline,
+    BackendDown,
+    Unauthorized,
+    Validation,
+    Decode,
+    #[default]
+    Other,
+}
+
+impl ErrorKind {
+    /// The kind of a request that did not get a response, depending on whether the browser is online.
+    pub fn unreachable(online: bool) -> Self {
+        if online { ErrorKind::BackendDown } else { ErrorKind::Offline }
+    }
+
+    /// The kind of a failed HTTP response, `None` for statuses whose body may still hold a GraphQL response.
+    pub fn of_status(status: u16) -> Option<Self> {
+        match status {
+            401 | 403 => Some(ErrorKind::Unauthorized),
+            500..=599 => Some(ErrorKind::BackendDown),
+            _ => None,
+        }
+    }
+
+    /// A short description of the failure, in English, to be translated with `translate`.
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorKind::Offline => "You are offline",
+            ErrorKind::BackendDown => "The server is not responding",
+            ErrorKind::Unauthorized => "You are not allowed to do this",
+            ErrorKind::Validation => "The server rejected the request",
+            ErrorKind::Decode => "The server sent an unexpected answer",
+            ErrorKind::Other => "Something went wrong",
+        }
+    }
+
+    /// Whether sending the request again may succeed. Refused learners have to select their learner again,
+    /// rejected requests are rejected again and unexpected answers need an updated app, so retrying those
+    /// cannot help.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, ErrorKind::Unauthorized | ErrorKind::Validation | ErrorKind::Decode)
+    }
+
+    /// What the learner can do about the failure, in English, to be translated with `translate`.
+    pub fn advice(&self) -> &'static str {
+        match self {
+            ErrorKind::Offline => "Check your internet connection and try again.",
+            ErrorKind::BackendDown => "Please try again in a few minutes.",
+            ErrorKind::Unauthorized => "Select your learner again on the home page.",
+            ErrorKind::Validation => "Sending it again will not help. If you think it should work, let us know.",
+            ErrorKind::Decode => "The app may be out of date. Reload the page to update it.",
+            ErrorKind::Other => "Please try again.",
+        }
+    }
+}
 
 /// Represents an error encountered during a fetch operation in a WebAssembly environment.
 ///
@@ -13,9 +87,22 @@ use wasm_bindgen::JsValue;
 /// # Fields
 ///
 /// - `err`: The underlying JavaScript error (`JsValue`) that caused the fetch operation to fail.
+/// - `kind`: What kind of failure it is.
 #[derive(Debug, Clone, PartialEq)]
 pub struct FetchError {
     pub err: JsValue,
+    pub kind: ErrorKind,
+}
+
+impl FetchError {
+    pub fn new(kind: ErrorKind, message: &str) -> Self {
+        FetchError { err: JsValue::from_str(message), kind }
+    }
+
+    /// The error message without the `JsValue` wrapping it, for the technical details shown to developers.
+    pub fn message(&self) -> String {
+        self.err.as_string().unwrap_or_else(|| format!("{:?}", self.err))
+    }
 }
 
 impl Display for FetchError {
@@ -26,19 +113,24 @@ impl Display for FetchError {
 
 impl Error for FetchError {}
 
+impl From<JsValue> for FetchError {
+    fn from(value: JsValue) -> Self {
+        // A browser API failed, e.g. storage or a download
+        FetchError { err: value, kind: ErrorKind::Other }
+    }
+}
+
 impl From<String> for FetchError {
     fn from(value: String) -> Self {
-        let js_value_error = JsValue::from_str(&value);
-        FetchError { err: js_value_error }
+        FetchError::new(ErrorKind::Other, &value)
     }
 }
 impl From<serde_json::Error> for FetchError {
     fn from(value: serde_json::Error) -> Self {
         // Convert the serde_json::Error to a string and then to a JsValue
         let error_message = value.to_string();
-        let js_value_error = JsValue::from_str(&error_message);
 
-        FetchError { err: js_value_error }
+        FetchError::new(ErrorKind::Decode, &error_message)
     }
 }
 
@@ -46,9 +138,57 @@ impl From<reqwest::Error> for FetchError {
     fn from(value: reqwest::Error) -> Self {
         // Convert the reqwest::Error to a string and then to a JsValue
         let error_message = value.to_string();
-        let js_value_error = JsValue::from_str(&error_message);
+        let kind = match value.status() {
+            Some(status) => ErrorKind::of_status(status.as_u16()).unwrap_or_default(),
+            None if value.is_decode() => ErrorKind::Decode,
+            None if value.is_request() || value.is_timeout() => ErrorKind::BackendDown,
+            None => ErrorKind::Other,
+        };
 
-        FetchError { err: js_value_error }
+        FetchError::new(kind, &error_message)
+    }
+}
+
+/// A single entry of the `errors` list in a GraphQL response.
+///
+/// # Fields
+///
+/// - `message`: The error message reported by the GraphQL server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GqlError {
+    pub message: String,
+}
+
+impl GqlError {
+    /// Returns `true` when the server rejected the request because it does not know a queried field,
+    /// which is how an older backend responds to an operation it does not support yet.
+    pub fn is_unknown_field(&self) -> bool {
+        self.message.starts_with("Unknown field")
+    }
+
+    /// Returns `true` when the server rejected the request because a field does not take the argument `name`,
+    /// which is how an older backend responds to an argument added since.
+    pub fn is_unknown_argument(&self, name: &str) -> bool {
+        self.message.starts_with(&format!("Unknown argument \"{}\"", name))
+    }
+
+    /// Returns `true` when the server refused the operation for the learner rather than for the operation itself.
+    pub fn is_unauthorized(&self) -> bool {
+        let message = self.message.to_lowercase();
+        message.contains("unauthorized") || message.contains("forbidden") || message.contains("not authorized")
+    }
+}
+
+impl From<Vec<GqlError>> for FetchError {
+    fn from(value: Vec<GqlError>) -> Self {
+        // Join the messages of all errors reported by the server
+        let error_message = value.iter()
+            .map(|error| error.message.clone())
+            .collect::<Vec<String>>()
+            .join("; ");
+        let kind = if value.iter().any(GqlError::is_unauthorized) { ErrorKind::Unauthorized } else { ErrorKind::Validation };
+
+        FetchError::new(kind, &error_message)
     }
 }
 
@@ -64,14 +204,174 @@ impl From<reqwest::Error> for FetchError {
 /// # Returns
 ///
 /// * `Result<String, FetchError>`: On success, returns the response body as a `String`. On failure, returns
-/// a `FetchError` indicating what went wrong during the request process.
+///   a `FetchError` indicating what went wrong during the request process. Unauthorized and server error
+///   responses are failures; the body of other responses is returned for its GraphQL `errors`.
 pub async fn post_gql_query(gql_query_body: String) -> Result<String, FetchError> {
 
     /// GQL endpoint expected on the BE server.
     pub static GQL_URL: &str = "http://127.0.0.1:3001/gql";
 
     let client = reqwest::Client::new();
-    let res = client.post(GQL_URL).body(gql_query_body).send().await?;
+    let res = client.post(GQL_URL).body(gql_query_body).send().await.map_err(|err| {
+        // Without a response, the browser tells apart being offline from the backend being down
+        let online = web_sys::window().is_none_or(|window| window.navigator().on_line());
+        FetchError::new(ErrorKind::unreachable(online), &err.to_string())
+    })?;
+
+    if let Some(kind) = ErrorKind::of_status(res.status().as_u16()) {
+        return Err(FetchError::new(kind, &format!("{} {}", res.status(), res.text().await.unwrap_or_default())));
+    }
 
     Ok(res.text().await?)
-}
\ No newline at end of file
+}
+
+/// Largest number of query results kept in the `QueryCache`, the least recently fetched are dropped first.
+pub const MAX_CACHE_ENTRIES: usize = 200;
+
+/// Identifies a cached query result by the GraphQL operation and its variables.
+///
+/// # Fields
+///
+/// - `operation`: The name of the GraphQL operation, e.g. `AwesomePersonQuery`.
+/// - `variables`: The variables of the operation, serialized to JSON.
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
+pub struct QueryKey {
+    pub operation: String,
+    pub variables: String,
+}
+
+impl QueryKey {
+    /// The key of a query built with `graphql_client`.
+    pub fn of<V: Serialize>(body: &QueryBody<V>) -> Self {
+        QueryKey {
+            operation: body.operation_name.to_string(),
+            variables: serde_json::to_string(&body.variables).unwrap_or_default(),
+        }
+    }
+}
+
+/// What a cached query result depends on, so mutations can invalidate it.
+///
+/// ## Variants:
+/// - `Learner(i32)`: Results holding statistics of the awesome person, e.g. their profile or vocabulary list.
+/// - `VocabStudy(i32)`: Results holding data of the vocab study, e.g. its challenge or statistics.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum CacheTag {
+    Learner(i32),
+    VocabStudy(i32),
+}
+
+/// A query result read from the `QueryCache`.
+///
+/// # Fields
+///
+/// - `value`: The result.
+/// - `stale`: Whether the result is older than its time to live or was invalidated. Stale results can
+///   still be shown while they are fetched again.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Cached<T> {
+    pub value: T,
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    value: Value,
+    fetched_ms: f64,
+    ttl_ms: f64,
+    tags: Vec<CacheTag>,
+    invalidated: bool,
+}
+
+/// Query results keyed by `QueryKey`, each with a time to live and the `CacheTag`s it depends on.
+///
+/// The cache is clock agnostic, every method that depends on time takes the current time in milliseconds,
+/// which keeps it usable from native unit tests. The application keeps one cache in its `Store`, used
+/// through `cached_query`, `peek_query` and `invalidate`.
+#[derive(Debug, Clone, Default)]
+pub struct QueryCache {
+    entries: HashMap<QueryKey, CacheEntry>,
+}
+
+impl QueryCache {
+    /// Reads a result, whether fresh or stale. Results that no longer deserialize into `T` are ignored.
+    pub fn get<T: DeserializeOwned>(&self, key: &QueryKey, now_ms: f64) -> Option<Cached<T>> {
+        let entry = self.entries.get(key)?;
+        let value = serde_json::from_value(entry.value.clone()).ok()?;
+        let stale = entry.invalidated || now_ms - entry.fetched_ms >= entry.ttl_ms;
+        Some(Cached { value, stale })
+    }
+
+    /// Keeps a result for `ttl_ms`, dropping the least recently fetched result when the cache is full.
+    pub fn insert<T: Serialize>(&mut self, key: QueryKey, value: &T, ttl_ms: f64, tags: Vec<CacheTag>, now_ms: f64) {
+        let Ok(value) = serde_json::to_value(value) else {
+            return;
+        };
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= MAX_CACHE_ENTRIES {
+            let oldest = self.entries.iter()
+                .min_by(|a, b| a.1.fetched_ms.total_cmp(&b.1.fetched_ms))
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.entries.insert(key, CacheEntry { value, fetched_ms: now_ms, ttl_ms, tags, invalidated: false });
+    }
+
+    /// Marks every result depending on one of the `tags` as stale.
+    pub fn invalidate(&mut self, tags: &[CacheTag]) {
+        self.entries.values_mut()
+            .filter(|entry| entry.tags.iter().any(|tag| tags.contains(tag)))
+            .for_each(|entry| entry.invalidated = true);
+    }
+
+    /// Drops every result.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Reads a result from the store's query cache without fetching it, e.g. to render a stale result right
+/// away while it is fetched again.
+pub fn peek_query<T: DeserializeOwned>(store: &Store, key: &QueryKey) -> Option<Cached<T>> {
+    store.with_queries(|cache| cache.get(key, js_sys::Date::now()))
+}
+
+/// Returns a fresh result from the store's query cache, or runs `fetch` and caches its result.
+///
+/// # Arguments
+///
+/// * `store` - The store keeping the results.
+/// * `key` - The key of the query, see `QueryKey::of`.
+/// * `ttl_ms` - How long the fetched result stays fresh.
+/// * `tags` - What the result depends on, for `invalidate`.
+/// * `fetch` - Fetches the result from the backend. Failures are not cached.
+pub async fn cached_query<T, F, Fut>(store: &Store, key: QueryKey, ttl_ms: f64, tags: Vec<CacheTag>, fetch: F) -> Result<T, FetchError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    if let Some(Cached { value, stale: false }) = peek_query(store, &key) {
+        return Ok(value);
+    }
+
+    let value = fetch().await?;
+    store.with_queries(|cache| cache.insert(key, &value, ttl_ms, tags, js_sys::Date::now()));
+    Ok(value)
+}
+
+/// Marks the cached results depending on one of the `tags` as stale, e.g. after a mutation changed them.
+pub fn invalidate(store: &Store, tags: &[CacheTag]) {
+    store.with_queries(|cache| cache.invalidate(tags));
+}
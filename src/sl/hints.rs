@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use crate::sl::study::Challenge;
+
+/// The largest share of an answer's score that hints can take away.
+pub const MAX_HINT_PENALTY: f64 = 1.0;
+
+/// The kinds of hints a learner can ask for while answering a `Challenge`.
+///
+/// ## Variants:
+/// - `PartOfSpeech`: The part of speech of the word, e.g. noun or verb.
+/// - `WordLength`: The number of letters of each word in the answer.
+/// - `Infinitive`: The infinitive of a conjugated verb.
+/// - `FirstLetter`: The first letter of the answer.
+/// - `OtherHints`: Free form hints stored with the vocabulary.
+/// - `UserNotes`: The learner's own notes for the vocabulary.
+/// - `MaskedLetters`: The answer with its letters masked, revealing more letters with every request.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum HintKind {
+    PartOfSpeech,
+    WordLength,
+    Infinitive,
+    FirstLetter,
+    OtherHints,
+    UserNotes,
+    MaskedLetters,
+}
+
+/// The order hints are revealed in when none is configured, from the least to the most revealing.
+pub const DEFAULT_HINT_ORDER: [HintKind; 7] = [
+    HintKind::PartOfSpeech,
+    HintKind::WordLength,
+    HintKind::Infinitive,
+    HintKind::OtherHints,
+    HintKind::UserNotes,
+    HintKind::FirstLetter,
+    HintKind::MaskedLetters,
+];
+
+impl HintKind {
+    /// The label shown in front of the hint text.
+    pub fn label(&self) -> &'static str {
+        match self {
+            HintKind::PartOfSpeech => "Part of Speech",
+            HintKind::WordLength => "Letters",
+            HintKind::Infinitive => "Infinitive",
+            HintKind::FirstLetter => "Starts with",
+            HintKind::OtherHints => "Other Hints",
+            HintKind::UserNotes => "Your Notes",
+            HintKind::MaskedLetters => "Answer",
+        }
+    }
+
+    /// Whether the hint is built from the answer itself, so the challenge's `learning` text is needed.
+    pub fn needs_learning(&self) -> bool {
+        matches!(self, HintKind::WordLength | HintKind::FirstLetter | HintKind::MaskedLetters)
+    }
+
+    /// The share of the answer's score lost by using this hint once.
+    pub fn cost(&self) -> f64 {
+        match self {
+            HintKind::PartOfSpeech => 0.05,
+            HintKind::WordLength => 0.05,
+            HintKind::Infinitive => 0.1,
+            HintKind::OtherHints => 0.1,
+            HintKind::UserNotes => 0.1,
+            HintKind::FirstLetter => 0.15,
+            HintKind::MaskedLetters => 0.1,
+        }
+    }
+
+    /// The name of the hint kind as sent to the backend, e.g. `PART_OF_SPEECH`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HintKind::PartOfSpeech => "PART_OF_SPEECH",
+            HintKind::WordLength => "WORD_LENGTH",
+            HintKind::Infinitive => "INFINITIVE",
+            HintKind::FirstLetter => "FIRST_LETTER",
+            HintKind::OtherHints => "OTHER_HINTS",
+            HintKind::UserNotes => "USER_NOTES",
+            HintKind::MaskedLetters => "MASKED_LETTERS",
+        }
+    }
+}
+
+/// A single hint ready to be shown to the learner.
+///
+/// # Fields
+///
+/// - `kind`: The kind of hint.
+/// - `text`: The hint itself, without its label.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Hint {
+    pub kind: HintKind,
+    pub text: String,
+}
+
+impl Hint {
+    pub fn new(kind: HintKind, text: &str) -> Self {
+        Hint { kind, text: text.to_string() }
+    }
+
+    /// The hint with its label, e.g. `Part of Speech: noun`.
+    pub fn display(&self) -> String {
+        format!("{}: {}", self.kind.label(), self.text)
+    }
+}
+
+/// Masks the letters of `answer` with `_`, leaving the first `revealed` letters visible.
+/// Spaces and punctuation are never masked.
+fn mask(answer: &str, revealed: usize) -> String {
+    let mut seen = 0;
+    answer.chars().map(|c| {
+        if !c.is_alphanumeric() {
+            return c;
+        }
+        seen += 1;
+        if seen <= revealed { c } else { '_' }
+    }).collect()
+}
+
+/// Builds the progressively revealing `MaskedLetters` hints for `answer`.
+///
+/// The first hint only shows the shape of the answer, each following one reveals roughly another
+/// quarter of the letters. The last letter is never revealed, so the learner still has to answer.
+fn masked_hints(answer: &str) -> Vec<Hint> {
+    let letters = answer.chars().filter(|c| c.is_alphanumeric()).count();
+    if letters < 2 {
+        return Vec::new();
+    }
+
+    let step = letters.div_ceil(4);
+    (0..letters)
+        .step_by(step)
+        .map(|revealed| Hint { kind: HintKind::MaskedLetters, text: mask(answer, revealed) })
+        .collect()
+}
+
+/// Builds the hints available for `challenge`, in the given `order`.
+///
+/// Hint kinds without data for the challenge are skipped. Kinds that depend on the answer itself
+/// (`WordLength`, `FirstLetter` and `MaskedLetters`) are only offered when the challenge carries the
+/// `learning` text. `MaskedLetters` expands into several hints, each revealing more of the answer.
+///
+/// # Arguments
+///
+/// * `challenge` - The challenge to build hints for.
+/// * `order` - The hint kinds to offer, in the order they should be revealed. Kinds missing from
+///   the order are not offered.
+pub fn build_hints(challenge: &Challenge, order: &[HintKind]) -> Vec<Hint> {
+    let learning = challenge.learning.trim();
+    let mut hints = Vec::new();
+
+    for kind in order {
+        let text = match kind {
+            HintKind::PartOfSpeech => challenge.pos.clone(),
+            HintKind::Infinitive => challenge.infinitive.clone(),
+            HintKind::OtherHints => challenge.hint.clone(),
+            HintKind::UserNotes => challenge.user_notes.clone(),
+            HintKind::WordLength => learning.split_whitespace()
+                .map(|word| word.chars().count().to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            HintKind::FirstLetter => learning.chars().next().map(String::from).unwrap_or_default(),
+            HintKind::MaskedLetters => {
+                hints.extend(masked_hints(learning));
+                continue;
+            }
+        };
+
+        if !text.is_empty() {
+            hints.push(Hint { kind: *kind, text });
+        }
+    }
+
+    hints
+}
+
+/// The share of an answer's score lost through the hints used, capped at `MAX_HINT_PENALTY`.
+pub fn hint_penalty(hints_used: &[HintKind]) -> f64 {
+    hints_used.iter().map(HintKind::cost).sum::<f64>().min(MAX_HINT_PENALTY)
+}
+
+/// Applies the hint penalty to an answer's score.
+///
+/// # Arguments
+///
+/// * `score` - The score of the answer before hints are considered, between 0 and 1.
+/// * `hints_used` - Every hint revealed before the answer was checked, one entry per reveal.
+pub fn hinted_score(score: f64, hints_used: &[HintKind]) -> f64 {
+    (score * (1.0 - hint_penalty(hints_used))).max(0.0)
+}
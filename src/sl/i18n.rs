@@ -1,24 +1,65 @@
-/*
-
 use std::collections::HashMap;
-use std::fmt::format;
-use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::JsFuture;
-use web_sys::{Request, RequestInit, RequestMode, Response};
-
+use std::fmt::Display;
+use std::sync::OnceLock;
 
-async fn load_lang(lang: &str) -> Result<HashMap<String, String>, Err> {
+/// The interface languages, each with a translation file in `src/assets/lang`.
+pub const UI_LANGUAGES: [&str; 6] = ["en", "es", "fr", "de", "it", "pt"];
 
-    let map:HashMap<String, String> = HashMap::new();
+/// The interface language when none is chosen, also used for texts missing from a translation file.
+pub const DEFAULT_UI_LANGUAGE: &str = "en";
 
-    let lang_url = format!("/assets/{}.json", lang);
-    let client = reqwest::Client::new();
-    let res = client.get(lang_url).send().await?;
+/// The translation file of an interface language, compiled into the application.
+fn translation_file(lang: &str) -> Option<&'static str> {
+    match lang {
+        "en" => Some(include_str!("../assets/lang/en.json")),
+        "es" => Some(include_str!("../assets/lang/es.json")),
+        "fr" => Some(include_str!("../assets/lang/fr.json")),
+        "de" => Some(include_str!("../assets/lang/de.json")),
+        "it" => Some(include_str!("../assets/lang/it.json")),
+        "pt" => Some(include_str!("../assets/lang/pt.json")),
+        _ => None,
+    }
+}
 
-    serde_json::from_str(&res.json())?;
+/// The translations of every interface language, parsed on first use.
+fn translations() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    static TRANSLATIONS: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+    TRANSLATIONS.get_or_init(|| {
+        UI_LANGUAGES.iter().filter_map(|lang| {
+            let map = serde_json::from_str(translation_file(lang)?).ok()?;
+            Some((*lang, map))
+        }).collect()
+    })
+}
 
+/// Translates an interface text, keyed by its English wording.
+///
+/// Texts missing from the translation file, or languages without one, fall back to the English text.
+pub fn translate(lang: &str, text: &str) -> String {
+    translations().get(lang)
+        .and_then(|map| map.get(text))
+        .cloned()
+        .unwrap_or_else(|| text.to_string())
+}
 
-    Err("implme")
+/// Translates an interface text with `{}` placeholders, e.g. `Score: {} of {}`, filling them in order with
+/// `args`.
+pub fn translate_format(lang: &str, text: &str, args: &[&dyn Display]) -> String {
+    let translated = translate(lang, text);
+    let mut parts = translated.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        match args.next() {
+            Some(arg) => filled.push_str(&arg.to_string()),
+            None => filled.push_str("{}"),
+        }
+        filled.push_str(part);
+    }
+    filled
 }
 
- */
+/// The name of an interface language in that language, e.g. `español` for `es`.
+pub fn language_name(lang: &str) -> String {
+    translate(lang, lang)
+}
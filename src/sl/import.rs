@@ -0,0 +1,377 @@
+use std::fmt;
+use crate::sl::i18n::{translate, translate_format, DEFAULT_UI_LANGUAGE};
+use crate::sl::new_vocab::{FieldError, NewVocab};
+
+/// Number of words submitted to the backend per request when importing.
+pub const IMPORT_BATCH_SIZE: usize = 25;
+
+/// The kinds of files the import page reads.
+///
+/// ## Variants:
+/// - `Csv`: Comma separated values, e.g. saved from a spreadsheet.
+/// - `Tsv`: Tab separated values.
+/// - `Anki`: An Anki "Notes in Plain Text" export, tab separated by default with optional `#key:value`
+///   header lines describing the separator, whether fields hold HTML and the column names.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ImportFormat {
+    Csv,
+    Tsv,
+    Anki,
+}
+
+impl ImportFormat {
+    /// Picks the format from the extension of the file name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ImportFormat, ImportError>`: The format, or an error for Anki packages and unknown extensions.
+    pub fn from_file_name(file_name: &str) -> Result<Self, ImportError> {
+        let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+        match extension.as_str() {
+            "csv" => Ok(ImportFormat::Csv),
+            "tsv" | "tab" => Ok(ImportFormat::Tsv),
+            "txt" => Ok(ImportFormat::Anki),
+            "apkg" | "colpkg" => Err(ImportError::AnkiPackage),
+            _ => Err(ImportError::UnsupportedFile(file_name.to_string())),
+        }
+    }
+
+    /// The separator between fields, before any Anki `#separator` header is applied.
+    fn separator(&self) -> char {
+        match self {
+            ImportFormat::Csv => ',',
+            ImportFormat::Tsv | ImportFormat::Anki => '\t',
+        }
+    }
+}
+
+/// Reasons a file cannot be imported at all.
+///
+/// ## Variants:
+/// - `UnsupportedFile(String)`: The file name has an extension that is not imported.
+/// - `AnkiPackage`: The file is a binary Anki package, which cannot be read in the browser.
+/// - `Empty`: The file has no rows.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ImportError {
+    UnsupportedFile(String),
+    AnkiPackage,
+    Empty,
+}
+
+impl ImportError {
+    /// Explains the error in the interface language `lang`.
+    pub fn message(&self, lang: &str) -> String {
+        match self {
+            ImportError::UnsupportedFile(name) => translate_format(lang, "{} is not a CSV, TSV or Anki text file.", &[name]),
+            ImportError::AnkiPackage => translate(lang, "Anki packages cannot be read here. In Anki, export the deck as \
+                \"Notes in Plain Text\" and import the .txt file instead."),
+            ImportError::Empty => translate(lang, "The file has no rows to import."),
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message(DEFAULT_UI_LANGUAGE))
+    }
+}
+
+/// The vocabulary field a column of an imported file is read into.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ImportColumn {
+    FirstLang,
+    Learning,
+    Pos,
+    Infinitive,
+    Hint,
+    Ignore,
+}
+
+impl ImportColumn {
+    /// Every column choice, in the order they are offered.
+    pub const ALL: [ImportColumn; 6] = [
+        ImportColumn::FirstLang,
+        ImportColumn::Learning,
+        ImportColumn::Pos,
+        ImportColumn::Infinitive,
+        ImportColumn::Hint,
+        ImportColumn::Ignore,
+    ];
+
+    /// The mapping used for files without a header: the fields in their usual order.
+    const DEFAULT_ORDER: [ImportColumn; 5] = [
+        ImportColumn::FirstLang,
+        ImportColumn::Learning,
+        ImportColumn::Pos,
+        ImportColumn::Infinitive,
+        ImportColumn::Hint,
+    ];
+
+    /// Human readable name of the column choice.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportColumn::FirstLang => "Word",
+            ImportColumn::Learning => "Translation",
+            ImportColumn::Pos => "Part of Speech",
+            ImportColumn::Infinitive => "Infinitive",
+            ImportColumn::Hint => "Hint",
+            ImportColumn::Ignore => "Ignore",
+        }
+    }
+
+    /// Guesses the field from a column name of a header row, `Ignore` when the name is not recognised.
+    pub fn guess(name: &str) -> Self {
+        let name = name.trim().to_lowercase().replace(['_', '-'], " ");
+        match name.as_str() {
+            "first lang" | "firstlang" | "word" | "front" | "known" | "english" => ImportColumn::FirstLang,
+            "learning" | "translation" | "back" => ImportColumn::Learning,
+            "pos" | "part of speech" => ImportColumn::Pos,
+            "infinitive" => ImportColumn::Infinitive,
+            "hint" | "hints" | "notes" => ImportColumn::Hint,
+            _ => ImportColumn::Ignore,
+        }
+    }
+}
+
+/// A row of an imported file, with the line it starts on for error messages.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ImportLine {
+    pub line: usize,
+    pub cells: Vec<String>,
+}
+
+/// The rows of an imported file.
+///
+/// # Fields
+///
+/// - `header`: The column names, when the file has a header row or Anki `#columns` header.
+/// - `rows`: The data rows.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ImportTable {
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<ImportLine>,
+}
+
+impl ImportTable {
+    /// The number of columns of the widest row or header.
+    pub fn num_columns(&self) -> usize {
+        let header = self.header.as_ref().map_or(0, Vec::len);
+        self.rows.iter().map(|row| row.cells.len()).max().unwrap_or(0).max(header)
+    }
+
+    /// Guesses which field each column holds: from the header when there is one, otherwise the fields
+    /// in their usual order.
+    pub fn guess_mapping(&self) -> Vec<ImportColumn> {
+        (0..self.num_columns()).map(|index| match &self.header {
+            Some(header) => header.get(index).map_or(ImportColumn::Ignore, |name| ImportColumn::guess(name)),
+            None => ImportColumn::DEFAULT_ORDER.get(index).copied().unwrap_or(ImportColumn::Ignore),
+        }).collect()
+    }
+}
+
+/// A data row mapped to a new vocabulary item, with its validation errors.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ImportRow {
+    pub line: usize,
+    pub vocab: NewVocab,
+    pub errors: Vec<FieldError>,
+}
+
+impl ImportRow {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Splits delimited text into rows of cells.
+///
+/// Fields may be quoted with `"`, in which case they can hold separators, line breaks and doubled `""`
+/// quotes. Blank lines are skipped and both `\n` and `\r\n` line endings are accepted.
+pub fn parse_delimited(text: &str, separator: char) -> Vec<ImportLine> {
+    let mut rows = Vec::new();
+    let mut cells: Vec<String> = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut chars = text.chars().peekable();
+
+    let mut end_row = |cells: &mut Vec<String>, cell: &mut String, row_line: usize| {
+        cells.push(std::mem::take(cell));
+        let cells = std::mem::take(cells);
+        if cells.iter().any(|cell| !cell.trim().is_empty()) {
+            rows.push(ImportLine { line: row_line, cells });
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if cell.trim().is_empty() => {
+                cell.clear();
+                in_quotes = true;
+            }
+            '\n' if in_quotes => {
+                line += 1;
+                cell.push('\n');
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                end_row(&mut cells, &mut cell, row_line);
+                line += 1;
+                row_line = line;
+            }
+            c if c == separator && !in_quotes => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    end_row(&mut cells, &mut cell, row_line);
+
+    rows
+}
+
+/// Removes HTML tags and decodes the common entities of an Anki field.
+fn strip_html(text: &str) -> String {
+    let mut plain = String::new();
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+
+    plain.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Whether every cell of a row is a recognised column name, making it a header row.
+fn is_header(cells: &[String]) -> bool {
+    cells.iter().any(|cell| ImportColumn::guess(cell) != ImportColumn::Ignore)
+        && cells.iter().all(|cell| cell.trim().is_empty() || ImportColumn::guess(cell) != ImportColumn::Ignore)
+}
+
+/// Parses the text of an Anki export, applying its `#separator`, `#html` and `#columns` headers.
+fn parse_anki(text: &str) -> ImportTable {
+    let mut separator = ImportFormat::Anki.separator();
+    let mut html = false;
+    let mut columns: Option<String> = None;
+    let mut skipped = 0;
+
+    for header in text.lines().take_while(|line| line.starts_with('#')) {
+        skipped += 1;
+        let Some((key, value)) = header[1..].split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "separator" => separator = match value.trim() {
+                "tab" => '\t',
+                "comma" => ',',
+                "semicolon" => ';',
+                "pipe" => '|',
+                "space" => ' ',
+                other => other.chars().next().unwrap_or(separator),
+            },
+            "html" => html = value.trim() == "true",
+            "columns" => columns = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    let body: String = text.lines().skip(skipped).collect::<Vec<_>>().join("\n");
+    let mut rows = parse_delimited(&body, separator);
+    for row in rows.iter_mut() {
+        row.line += skipped;
+        if html {
+            row.cells = row.cells.iter().map(|cell| strip_html(cell)).collect();
+        }
+    }
+
+    let header = columns.map(|columns| columns.split(separator).map(|name| name.trim().to_string()).collect());
+    ImportTable { header, rows }
+}
+
+/// Parses an imported file into an `ImportTable`.
+///
+/// # Arguments
+///
+/// * `file_name` - The name of the file, used to pick the `ImportFormat`.
+/// * `text` - The contents of the file.
+///
+/// # Returns
+///
+/// * `Result<ImportTable, ImportError>`: The rows of the file, with the first row taken as the header when
+///   all its cells are recognised column names.
+pub fn parse_import(file_name: &str, text: &str) -> Result<ImportTable, ImportError> {
+    let format = ImportFormat::from_file_name(file_name)?;
+    let text = text.trim_start_matches('\u{feff}');
+
+    let mut table = match format {
+        ImportFormat::Anki => parse_anki(text),
+        _ => ImportTable { header: None, rows: parse_delimited(text, format.separator()) },
+    };
+
+    if table.header.is_none() && table.rows.first().is_some_and(|row| is_header(&row.cells)) {
+        table.header = Some(table.rows.remove(0).cells);
+    }
+
+    if table.rows.is_empty() {
+        return Err(ImportError::Empty);
+    }
+    Ok(table)
+}
+
+/// Maps the rows of a table to new vocabulary items of a language pair and validates them.
+///
+/// # Arguments
+///
+/// * `table` - The parsed file.
+/// * `mapping` - The field each column is read into. Columns beyond the mapping are ignored.
+/// * `known_lang_code`, `learning_lang_code` - The language pair of every item.
+pub fn map_rows(table: &ImportTable, mapping: &[ImportColumn], known_lang_code: &str, learning_lang_code: &str) -> Vec<ImportRow> {
+    table.rows.iter().map(|row| {
+        let mut vocab = NewVocab {
+            known_lang_code: known_lang_code.to_string(),
+            learning_lang_code: learning_lang_code.to_string(),
+            ..NewVocab::default()
+        };
+
+        for (column, cell) in mapping.iter().zip(row.cells.iter()) {
+            let field = match column {
+                ImportColumn::FirstLang => &mut vocab.first_lang,
+                ImportColumn::Learning => &mut vocab.learning,
+                ImportColumn::Pos => &mut vocab.pos,
+                ImportColumn::Infinitive => &mut vocab.infinitive,
+                ImportColumn::Hint => &mut vocab.hint,
+                ImportColumn::Ignore => continue,
+            };
+            if !field.is_empty() {
+                field.push(' ');
+            }
+            field.push_str(cell);
+        }
+
+        let vocab = vocab.tidied();
+        let errors = vocab.validate();
+        ImportRow { line: row.line, vocab, errors }
+    }).collect()
+}
+
+/// Splits the valid rows into batches of at most `IMPORT_BATCH_SIZE` items to submit.
+///
+/// The first `imported` valid rows are skipped: they were created by an earlier import that stopped on a
+/// failed batch, so submitting them again would add the same words twice.
+pub fn valid_batches(rows: &[ImportRow], imported: usize) -> Vec<Vec<NewVocab>> {
+    let valid: Vec<NewVocab> = rows.iter().filter(|row| row.is_valid()).skip(imported).map(|row| row.vocab.clone()).collect();
+    valid.chunks(IMPORT_BATCH_SIZE).map(|batch| batch.to_vec()).collect()
+}
@@ -1,3 +1,20 @@
+pub mod awesome;
+pub mod diff;
+pub mod export;
+pub mod goals;
 pub mod gql;
+pub mod hints;
+pub mod import;
+pub mod new_vocab;
+pub mod request;
+pub mod scheduler;
+pub mod session;
+pub mod settings;
+pub mod shortcuts;
+pub mod speech;
+pub mod storage;
+pub mod store;
 pub mod study;
-pub mod i18n;
\ No newline at end of file
+pub mod theme;
+pub mod vocab;
+pub mod i18n;
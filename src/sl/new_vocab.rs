@@ -0,0 +1,297 @@
+use graphql_client::GraphQLQuery;
+use serde::{Deserialize, Serialize};
+use crate::sl::gql::{invalidate, post_gql_query, CacheTag, FetchError};
+use crate::sl::store::Store;
+use crate::sl::study::Challenge;
+use crate::sl::vocab::LANGUAGES;
+
+/// Maximum number of characters accepted in a single field of a new vocabulary item.
+pub const MAX_FIELD_LEN: usize = 200;
+
+/// Maximum number of challenges remembered for duplicate detection.
+pub const KNOWN_CHALLENGES_LIMIT: usize = 500;
+
+/// Response JSON wrapper
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateVocabResponseWrapper {
+    pub data: CreateVocabData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateVocabData {
+    #[serde(rename = "createVocab")]
+    pub create_vocab: Challenge,
+}
+
+/// Response JSON wrapper
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateVocabBatchResponseWrapper {
+    pub data: CreateVocabBatchData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateVocabBatchData {
+    #[serde(rename = "createVocabBatch")]
+    pub create_vocab_batch: Vec<Challenge>,
+}
+
+/// The fields of the "add word" form.
+///
+/// ## Variants:
+/// - `FirstLang`: The vocabulary in the learner's known language.
+/// - `Learning`: The vocabulary in the learning language.
+/// - `Pos`: The part of speech.
+/// - `Infinitive`: The infinitive, for verbs.
+/// - `Hint`: A hint shown on request while studying.
+/// - `KnownLang`, `LearningLang`: The language codes of the pair.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum VocabField {
+    FirstLang,
+    Learning,
+    Pos,
+    Infinitive,
+    Hint,
+    KnownLang,
+    LearningLang,
+}
+
+/// A validation error of one field of the "add word" form.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct FieldError {
+    pub field: VocabField,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: VocabField, message: &str) -> Self {
+        FieldError { field, message: message.to_string() }
+    }
+}
+
+/// A vocabulary item the learner wants to add and start studying.
+///
+/// # Fields
+///
+/// - `first_lang`: The vocabulary in the learner's known language.
+/// - `learning`: The vocabulary in the learning language.
+/// - `pos`, `infinitive`, `hint`: Optional details used for hints while studying.
+/// - `known_lang_code`, `learning_lang_code`: The language pair, e.g. `en` and `es`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct NewVocab {
+    pub first_lang: String,
+    pub learning: String,
+    pub pos: String,
+    pub infinitive: String,
+    pub hint: String,
+    pub known_lang_code: String,
+    pub learning_lang_code: String,
+}
+
+impl Default for NewVocab {
+    fn default() -> Self {
+        NewVocab {
+            first_lang: "".to_string(),
+            learning: "".to_string(),
+            pos: "".to_string(),
+            infinitive: "".to_string(),
+            hint: "".to_string(),
+            known_lang_code: "en".to_string(),
+            learning_lang_code: "es".to_string(),
+        }
+    }
+}
+
+/// Trims `text` and collapses inner runs of whitespace into single spaces.
+fn tidy(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether two texts are the same vocabulary, ignoring case and spacing.
+fn same_text(a: &str, b: &str) -> bool {
+    let a = tidy(a).to_lowercase();
+    !a.is_empty() && a == tidy(b).to_lowercase()
+}
+
+impl NewVocab {
+    /// Replaces the value of a field.
+    pub fn set(&mut self, field: VocabField, value: String) {
+        match field {
+            VocabField::FirstLang => self.first_lang = value,
+            VocabField::Learning => self.learning = value,
+            VocabField::Pos => self.pos = value,
+            VocabField::Infinitive => self.infinitive = value,
+            VocabField::Hint => self.hint = value,
+            VocabField::KnownLang => self.known_lang_code = value,
+            VocabField::LearningLang => self.learning_lang_code = value,
+        }
+    }
+
+    /// A copy with every text field trimmed and its inner whitespace collapsed, as it is sent to the backend.
+    pub fn tidied(&self) -> NewVocab {
+        NewVocab {
+            first_lang: tidy(&self.first_lang),
+            learning: tidy(&self.learning),
+            pos: tidy(&self.pos).to_lowercase(),
+            infinitive: tidy(&self.infinitive),
+            hint: tidy(&self.hint),
+            known_lang_code: self.known_lang_code.clone(),
+            learning_lang_code: self.learning_lang_code.clone(),
+        }
+    }
+
+    /// Checks the fields before the item is submitted.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<FieldError>`: One error per invalid field, empty when the item can be submitted.
+    pub fn validate(&self) -> Vec<FieldError> {
+        let vocab = self.tidied();
+        let mut errors = Vec::new();
+
+        if vocab.first_lang.is_empty() {
+            errors.push(FieldError::new(VocabField::FirstLang, "Enter the word in the language you know."));
+        }
+        if vocab.learning.is_empty() {
+            errors.push(FieldError::new(VocabField::Learning, "Enter the word in the language you are learning."));
+        }
+
+        let texts = [
+            (VocabField::FirstLang, &vocab.first_lang),
+            (VocabField::Learning, &vocab.learning),
+            (VocabField::Pos, &vocab.pos),
+            (VocabField::Infinitive, &vocab.infinitive),
+            (VocabField::Hint, &vocab.hint),
+        ];
+        for (field, text) in texts {
+            if text.chars().count() > MAX_FIELD_LEN {
+                errors.push(FieldError::new(field, &format!("Use at most {} characters.", MAX_FIELD_LEN)));
+            }
+        }
+
+        let is_language = |code: &str| LANGUAGES.iter().any(|(known, _)| *known == code);
+        if !is_language(&vocab.known_lang_code) {
+            errors.push(FieldError::new(VocabField::KnownLang, "Choose a language."));
+        }
+        if !is_language(&vocab.learning_lang_code) {
+            errors.push(FieldError::new(VocabField::LearningLang, "Choose a language."));
+        } else if vocab.learning_lang_code == vocab.known_lang_code {
+            errors.push(FieldError::new(VocabField::LearningLang, "Choose a different language from the one you know."));
+        }
+
+        errors
+    }
+
+    /// Finds a challenge the learner already studies for the same vocabulary.
+    ///
+    /// A challenge is a duplicate when it has the same language pair and the same text in either
+    /// language, ignoring case and spacing.
+    pub fn find_duplicate<'a>(&self, known: &'a [Challenge]) -> Option<&'a Challenge> {
+        known.iter().find(|challenge| {
+            challenge.known_lang_code == self.known_lang_code
+                && challenge.learning_lang_code == self.learning_lang_code
+                && (same_text(&challenge.first_lang, &self.first_lang) || same_text(&challenge.learning, &self.learning))
+        })
+    }
+}
+
+/// Adds `challenges` to the remembered challenges, replacing older copies of the same vocab study.
+///
+/// The most recent challenges are kept last and at most `KNOWN_CHALLENGES_LIMIT` are remembered.
+pub fn remember_challenges(known: &mut Vec<Challenge>, challenges: &[Challenge]) {
+    known.retain(|old| !challenges.iter().any(|challenge| challenge.vocab_study_id == old.vocab_study_id));
+    known.extend(challenges.iter().cloned());
+
+    let excess = known.len().saturating_sub(KNOWN_CHALLENGES_LIMIT);
+    known.drain(..excess);
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/create_vocab.graphql",
+response_derives = "Debug"
+)]
+struct CreateVocab;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/create_vocab_batch.graphql",
+response_derives = "Debug"
+)]
+struct CreateVocabBatch;
+
+/// Converts a `NewVocab`, tidied, into the `NewVocab` input generated for a mutation.
+///
+/// Each operation gets its own copy of the input type, so both mutations share this one conversion.
+macro_rules! new_vocab_input {
+    ($input:path) => {
+        impl From<NewVocab> for $input {
+            fn from(value: NewVocab) -> Self {
+                let value = value.tidied();
+                Self {
+                    first_lang: value.first_lang,
+                    learning: value.learning,
+                    pos: value.pos,
+                    infinitive: value.infinitive,
+                    hint: value.hint,
+                    known_lang_code: value.known_lang_code,
+                    learning_lang_code: value.learning_lang_code,
+                }
+            }
+        }
+    };
+}
+
+new_vocab_input!(create_vocab::NewVocab);
+new_vocab_input!(create_vocab_batch::NewVocab);
+
+/// Adds a new vocabulary item and starts a vocab study of it.
+///
+/// # Arguments
+///
+/// * `store` - The store whose cached vocabulary of the user is invalidated.
+/// * `awesome_id` - The unique identifier of the user who will study the vocabulary.
+/// * `vocab` - The vocabulary to add. It is tidied before it is sent.
+///
+/// # Returns
+///
+/// A `Result` wrapping the `Challenge` of the new vocab study on success, or a `FetchError` on failure.
+pub async fn create_vocab(store: &Store, awesome_id: i32, vocab: NewVocab) -> Result<Challenge, FetchError> {
+    let build_query = CreateVocab::build_query(create_vocab::Variables {
+        awesome_id: awesome_id.into(),
+        vocab: vocab.into(),
+    });
+
+    // Serialize the query to a string
+    let query_string = serde_json::to_string(&build_query)?;
+    let gql_json_res = post_gql_query(query_string).await?;
+    let wrapper: CreateVocabResponseWrapper = serde_json::from_str(&gql_json_res)?;
+    invalidate(store, &[CacheTag::Learner(awesome_id)]);
+    Ok(wrapper.data.create_vocab)
+}
+
+/// Adds several new vocabulary items in one request and starts a vocab study of each.
+///
+/// # Arguments
+///
+/// * `store` - The store whose cached vocabulary of the user is invalidated.
+/// * `awesome_id` - The unique identifier of the user who will study the vocabulary.
+/// * `vocab` - The vocabulary to add. Each item is tidied before it is sent.
+///
+/// # Returns
+///
+/// A `Result` wrapping the `Challenge` of each new vocab study on success, or a `FetchError` on failure.
+pub async fn create_vocab_batch(store: &Store, awesome_id: i32, vocab: Vec<NewVocab>) -> Result<Vec<Challenge>, FetchError> {
+    let build_query = CreateVocabBatch::build_query(create_vocab_batch::Variables {
+        awesome_id: awesome_id.into(),
+        vocab: vocab.into_iter().map(Into::into).collect(),
+    });
+
+    // Serialize the query to a string
+    let query_string = serde_json::to_string(&build_query)?;
+    let gql_json_res = post_gql_query(query_string).await?;
+    let wrapper: CreateVocabBatchResponseWrapper = serde_json::from_str(&gql_json_res)?;
+    invalidate(store, &[CacheTag::Learner(awesome_id)]);
+    Ok(wrapper.data.create_vocab_batch)
+}
@@ -0,0 +1,148 @@
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll, Waker};
+use wasm_bindgen_futures::spawn_local;
+
+/// Identifies a request started by a page, so its response can be told apart from those of newer requests.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct RequestId(u64);
+
+impl RequestId {
+    /// A new id, different from every id handed out before.
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        RequestId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+/// Cancels a request started with `abortable` or `spawn_cancellable`, also when it is dropped, so a request
+/// held by a component is cancelled when the component goes away.
+pub struct RequestHandle {
+    state: Rc<CancelState>,
+}
+
+impl RequestHandle {
+    /// Cancels the request. Its future is dropped without being polled again, which aborts a pending
+    /// `fetch` through the `AbortController` reqwest attaches to it.
+    pub fn cancel(&self) {
+        self.state.cancelled.set(true);
+        if let Some(waker) = self.state.waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.get()
+    }
+}
+
+impl Drop for RequestHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// A future that stops, resolving to `None`, once its `RequestHandle` is cancelled.
+pub struct Abortable<F> {
+    future: Option<Pin<Box<F>>>,
+    state: Rc<CancelState>,
+}
+
+impl<F: Future> Future for Abortable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.state.cancelled.get() {
+            // Drop the inner future right away rather than with the task, to abort its request
+            self.future = None;
+            return Poll::Ready(None);
+        }
+
+        *self.state.waker.borrow_mut() = Some(cx.waker().clone());
+        match self.future.as_mut() {
+            Some(future) => future.as_mut().poll(cx).map(Some),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+/// Wraps a future so it can be cancelled with the returned `RequestHandle`.
+pub fn abortable<F: Future>(future: F) -> (Abortable<F>, RequestHandle) {
+    let state = Rc::new(CancelState::default());
+    (Abortable { future: Some(Box::pin(future)), state: state.clone() }, RequestHandle { state })
+}
+
+/// Runs a future like `spawn_local`, returning a `RequestHandle` that cancels it.
+pub fn spawn_cancellable<F: Future<Output = ()> + 'static>(future: F) -> RequestHandle {
+    let (future, handle) = abortable(future);
+    spawn_local(async move {
+        future.await;
+    });
+    handle
+}
+
+/// The latest request of one kind made by a page, e.g. the answer being checked.
+///
+/// Starting a request cancels the previous one, and only the response of the latest request is accepted
+/// by `finish`, so a slow response cannot overwrite the outcome of a newer request. Dropping it cancels
+/// the pending request.
+#[derive(Default)]
+pub struct LatestRequest {
+    current: Option<(RequestId, RequestHandle)>,
+}
+
+impl LatestRequest {
+    /// Spawns a request, cancelling the pending one.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Builds the request's future from its `RequestId`, which the future sends back with its
+    ///   response so it can be checked with `finish`.
+    pub fn start<F, R>(&mut self, request: R) -> RequestId
+    where
+        F: Future<Output = ()> + 'static,
+        R: FnOnce(RequestId) -> F,
+    {
+        let id = RequestId::next();
+        self.track(id, spawn_cancellable(request(id)));
+        id
+    }
+
+    /// Makes `handle` the pending request, cancelling the previous one.
+    pub fn track(&mut self, id: RequestId, handle: RequestHandle) {
+        self.cancel();
+        self.current = Some((id, handle));
+    }
+
+    /// Accepts the response of a request, returning `false` when it is stale: cancelled or superseded by a
+    /// newer request.
+    pub fn finish(&mut self, id: RequestId) -> bool {
+        match &self.current {
+            Some((current, _)) if *current == id => {
+                self.current = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Cancels the pending request, if any.
+    pub fn cancel(&mut self) {
+        if let Some((_, handle)) = self.current.take() {
+            handle.cancel();
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.current.is_some()
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde::{Deserialize, Serialize};
+use crate::sl::study::{Challenge, VocabStats};
+use crate::sl::i18n::{translate, translate_format};
+use crate::sl::store::Store;
+use crate::sl::vocab::peek_vocab_stats;
+
+/// Local storage key, per learner with `learner_key`, holding the `Schedule` of every vocab study they
+/// reviewed in this browser.
+pub const SCHEDULE_STORAGE_KEY: &str = "study_schedule";
+
+/// Milliseconds in a day.
+pub const DAY_MS: f64 = 86_400_000.0;
+
+/// Delay before a missed item is due again, in milliseconds.
+pub const RELEARN_MS: f64 = 10.0 * 60_000.0;
+
+/// Ease factor of an item that has not been reviewed yet.
+pub const DEFAULT_EASE: f64 = 2.5;
+
+/// Lowest ease factor, so that hard items still get longer intervals when answered correctly.
+pub const MIN_EASE: f64 = 1.3;
+
+/// Highest number of repetitions assumed when seeding a state from the aggregate history of an item.
+pub const MAX_SEED_REPETITIONS: u32 = 4;
+
+/// Lowest answer quality that counts as remembered.
+const PASSING_QUALITY: u8 = 3;
+
+/// The review states of vocab studies, by vocab study id.
+pub type Schedule = BTreeMap<i32, ReviewState>;
+
+/// The aggregate history of a vocabulary item, as reported by the backend.
+///
+/// # Fields
+///
+/// - `attempts`: The number of answers checked.
+/// - `correct_attempts`: The number of correct answers.
+/// - `last_tested_ms`: When the item was last answered, in milliseconds since the epoch, `None` when unknown.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub struct ItemHistory {
+    pub attempts: u32,
+    pub correct_attempts: u32,
+    pub last_tested_ms: Option<f64>,
+}
+
+impl From<&VocabStats> for ItemHistory {
+    fn from(stats: &VocabStats) -> Self {
+        ItemHistory {
+            attempts: stats.attempts.max(0) as u32,
+            correct_attempts: stats.correct_attempts.max(0) as u32,
+            last_tested_ms: parse_timestamp_ms(&stats.last_tested),
+        }
+    }
+}
+
+/// The history of a vocab study from its cached `VocabStats`, fresh or stale, `None` when they were not fetched.
+pub fn cached_history(store: &Store, vocab_study_id: i32) -> Option<ItemHistory> {
+    peek_vocab_stats(store, vocab_study_id).map(|cached| ItemHistory::from(&cached.value))
+}
+
+/// Days since the epoch of a civil date, see http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Reads a UTC timestamp like `2024-05-01`, `2024-05-01 13:45` or `2024-05-01T13:45:10Z`, as the backend
+/// formats `last_tested`, into milliseconds since the epoch. Returns `None` for anything else, e.g. `never`.
+pub fn parse_timestamp_ms(text: &str) -> Option<f64> {
+    let text = text.trim().trim_end_matches('Z');
+    let (date, time) = match text.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+
+    let mut date_parts = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let seconds = match time {
+        Some(time) => {
+            let mut time_parts = time.splitn(3, ':').map(|part| part.parse::<f64>().ok());
+            let (hours, minutes) = (time_parts.next()??, time_parts.next()??);
+            let seconds = time_parts.next().unwrap_or(Some(0.0))?;
+            hours * 3600.0 + minutes * 60.0 + seconds
+        }
+        None => 0.0,
+    };
+
+    Some(days_from_civil(year, month, day) as f64 * DAY_MS + seconds * 1000.0)
+}
+
+/// Rates an answer on the SM-2 scale of 0 to 5.
+///
+/// A missed answer rates 1. A correct answer rates 5 without hints, 4 when it still earns at least half of
+/// its points, and 3 otherwise.
+///
+/// # Arguments
+///
+/// * `correct` - Whether the answer was accepted.
+/// * `score` - The points the answer earned, from 0 to 1, after hint penalties.
+pub fn answer_quality(correct: bool, score: f64) -> u8 {
+    match (correct, score) {
+        (false, _) => 1,
+        (true, score) if score >= 1.0 => 5,
+        (true, score) if score >= 0.5 => 4,
+        (true, _) => 3,
+    }
+}
+
+/// The SM-2 interval in days after `repetitions` successful reviews in a row.
+fn interval_days(repetitions: u32, ease: f64) -> f64 {
+    match repetitions {
+        0 => 0.0,
+        1 => 1.0,
+        n => 6.0 * ease.powi(n as i32 - 2),
+    }
+}
+
+/// The spaced repetition state of a vocabulary item, following the SM-2 algorithm.
+///
+/// # Fields
+///
+/// - `repetitions`: The number of successful reviews in a row.
+/// - `ease`: How quickly the interval grows, at least `MIN_EASE`.
+/// - `interval_days`: The interval until the next review, in days.
+/// - `last_reviewed_ms`: When the item was last reviewed, in milliseconds since the epoch.
+/// - `due_ms`: When the item is due for review, in milliseconds since the epoch. Zero for new items.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub struct ReviewState {
+    pub repetitions: u32,
+    pub ease: f64,
+    pub interval_days: f64,
+    pub last_reviewed_ms: Option<f64>,
+    pub due_ms: f64,
+}
+
+impl Default for ReviewState {
+    fn default() -> Self {
+        ReviewState {
+            repetitions: 0,
+            ease: DEFAULT_EASE,
+            interval_days: 0.0,
+            last_reviewed_ms: None,
+            due_ms: 0.0,
+        }
+    }
+}
+
+impl ReviewState {
+    /// Estimates a state from the aggregate history of an item that has no review state yet.
+    ///
+    /// The ease follows the accuracy, from `MIN_EASE` for items never answered correctly to `DEFAULT_EASE`
+    /// for items always answered correctly. Items answered correctly at least 60% of the time are assumed
+    /// to have been remembered as many times in a row as they were answered correctly, up to
+    /// `MAX_SEED_REPETITIONS`. The item is due one interval after it was last tested, or right away
+    /// when that is unknown.
+    pub fn from_history(history: &ItemHistory) -> Self {
+        let attempts = history.attempts.max(history.correct_attempts);
+        if attempts == 0 {
+            return ReviewState::default();
+        }
+
+        let accuracy = history.correct_attempts as f64 / attempts as f64;
+        let ease = MIN_EASE + (DEFAULT_EASE - MIN_EASE) * accuracy;
+        let repetitions = if accuracy >= 0.6 { history.correct_attempts.min(MAX_SEED_REPETITIONS) } else { 0 };
+        let interval_days = interval_days(repetitions, ease);
+        let due_ms = history.last_tested_ms.map_or(0.0, |last| last + interval_days * DAY_MS);
+
+        ReviewState { repetitions, ease, interval_days, last_reviewed_ms: history.last_tested_ms, due_ms }
+    }
+
+    /// Applies a review of the given SM-2 `quality` at `now_ms`.
+    ///
+    /// A remembered item moves on to the next interval. A missed item starts over and is due again after
+    /// `RELEARN_MS`. Either way the ease is adjusted by the SM-2 formula.
+    pub fn review(&mut self, quality: u8, now_ms: f64) {
+        let quality = quality.min(5);
+        let shortfall = (5 - quality) as f64;
+        self.ease = (self.ease + 0.1 - shortfall * (0.08 + shortfall * 0.02)).max(MIN_EASE);
+
+        if quality >= PASSING_QUALITY {
+            self.interval_days = match self.repetitions {
+                0 | 1 => interval_days(self.repetitions + 1, self.ease),
+                _ => (self.interval_days * self.ease).round(),
+            };
+            self.repetitions += 1;
+            self.due_ms = now_ms + self.interval_days * DAY_MS;
+        } else {
+            self.repetitions = 0;
+            self.interval_days = 0.0;
+            self.due_ms = now_ms + RELEARN_MS;
+        }
+        self.last_reviewed_ms = Some(now_ms);
+    }
+
+    pub fn is_due(&self, now_ms: f64) -> bool {
+        self.due_ms <= now_ms
+    }
+}
+
+/// A cached challenge with its review state.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ScheduledItem {
+    pub challenge: Challenge,
+    pub state: ReviewState,
+}
+
+/// Orders cached challenges by when they are due for review.
+///
+/// Items are ordered by due time, earliest first, and by vocab study id when due at the same time, so the
+/// order only depends on the challenges and their states.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Scheduler {
+    items: Vec<ScheduledItem>,
+}
+
+impl Scheduler {
+    /// Schedules the challenges, using their stored state from `schedule` or, when they have none, a state
+    /// estimated from their history. Only the first challenge of each vocab study is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenges` - The challenges to schedule.
+    /// * `schedule` - The stored review states.
+    /// * `history` - The history of a vocab study, e.g. from its cached `VocabStats`, `None` when unknown.
+    pub fn new(challenges: Vec<Challenge>, schedule: &Schedule, history: impl Fn(i32) -> Option<ItemHistory>) -> Self {
+        let mut seen = BTreeSet::new();
+        let items: Vec<ScheduledItem> = challenges.into_iter().filter(|challenge| seen.insert(challenge.vocab_study_id)).map(|challenge| {
+            let state = schedule.get(&challenge.vocab_study_id).copied()
+                .unwrap_or_else(|| ReviewState::from_history(&history(challenge.vocab_study_id).unwrap_or_default()));
+            ScheduledItem { challenge, state }
+        }).collect();
+
+        let mut scheduler = Scheduler { items };
+        scheduler.sort();
+        scheduler
+    }
+
+    fn sort(&mut self) {
+        self.items.sort_by(|a, b| {
+            a.state.due_ms.total_cmp(&b.state.due_ms)
+                .then(a.challenge.vocab_study_id.cmp(&b.challenge.vocab_study_id))
+        });
+    }
+
+    /// The scheduled items, earliest due first.
+    pub fn items(&self) -> &[ScheduledItem] {
+        &self.items
+    }
+
+    /// The challenge to study next: the item due the earliest, even when it is not due yet.
+    pub fn next_challenge(&self) -> Option<&Challenge> {
+        self.items.first().map(|item| &item.challenge)
+    }
+
+    /// The number of items due at `now_ms`.
+    pub fn num_due(&self, now_ms: f64) -> usize {
+        self.items.iter().filter(|item| item.state.is_due(now_ms)).count()
+    }
+
+    /// Up to `limit` challenges to study next, earliest due first.
+    pub fn batch(&self, limit: usize) -> Vec<Challenge> {
+        self.items.iter().take(limit).map(|item| item.challenge.clone()).collect()
+    }
+
+    /// Applies a review of a challenge and reorders the items.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ReviewState>`: The new state of the item, `None` when the challenge is not scheduled.
+    pub fn record(&mut self, vocab_study_id: i32, quality: u8, now_ms: f64) -> Option<ReviewState> {
+        let item = self.items.iter_mut().find(|item| item.challenge.vocab_study_id == vocab_study_id)?;
+        item.state.review(quality, now_ms);
+        let state = item.state;
+        self.sort();
+        Some(state)
+    }
+}
+
+/// The number of reviewed items in a stored `Schedule` that are due by `ms`.
+pub fn num_due_by(schedule: &Schedule, ms: f64) -> usize {
+    schedule.values().filter(|state| state.is_due(ms)).count()
+}
+
+/// When the next reviewed item in a stored `Schedule` falls due after `now_ms`, `None` when none is upcoming.
+pub fn next_due_ms(schedule: &Schedule, now_ms: f64) -> Option<f64> {
+    schedule.values().map(|state| state.due_ms).filter(|due_ms| *due_ms > now_ms).min_by(f64::total_cmp)
+}
+
+/// Applies a review of a vocab study to a stored `Schedule`, seeding its state from `history` when it has
+/// none yet.
+///
+/// # Arguments
+///
+/// * `schedule` - The stored review states.
+/// * `vocab_study_id` - The vocab study reviewed.
+/// * `history` - The history of the vocab study as reported by the backend, e.g. with the checked answer.
+/// * `quality` - The SM-2 quality of the answer, see `answer_quality`.
+/// * `now_ms` - When the answer was checked.
+///
+/// # Returns
+///
+/// * `ReviewState`: The new state of the vocab study.
+pub fn record_review(schedule: &mut Schedule, vocab_study_id: i32, history: &ItemHistory, quality: u8, now_ms: f64) -> ReviewState {
+    let state = schedule.entry(vocab_study_id)
+        .or_insert_with(|| ReviewState::from_history(history));
+    state.review(quality, now_ms);
+    *state
+}
+
+/// Describes when an item is due relative to `now_ms` in the interface language `lang`, e.g. `due now`,
+/// `in 3h` or `in 6d`.
+pub fn due_display(due_ms: f64, now_ms: f64, lang: &str) -> String {
+    let remaining = due_ms - now_ms;
+    if remaining <= 0.0 {
+        return translate(lang, "due now");
+    }
+
+    let minutes = (remaining / 60_000.0).ceil() as u64;
+    match minutes {
+        0..=59 => translate_format(lang, "in {}m", &[&minutes]),
+        60..=1439 => translate_format(lang, "in {}h", &[&(minutes / 60)]),
+        _ => translate_format(lang, "in {}d", &[&(minutes / 1440)]),
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use crate::sl::hints::{hinted_score, HintKind};
+use crate::sl::study::{Challenge, CheckResult, VocabStats};
+
+/// Number of answered challenges in a session when no length is requested.
+pub const DEFAULT_SESSION_ITEMS: usize = 10;
+
+/// Number of other challenges shown before a missed challenge comes back, when no spacing is requested.
+pub const DEFAULT_REQUEUE_SPACING: usize = 3;
+
+/// Number of words listed as the hardest in a session summary.
+pub const HARDEST_WORDS_LIMIT: usize = 5;
+
+/// Session storage key holding the summary of the last completed session.
+pub const SUMMARY_STORAGE_KEY: &str = "study_session_summary";
+
+/// Session storage key holding a deck of challenges the next study session should use instead of
+/// fetching a new list from the backend.
+pub const DECK_STORAGE_KEY: &str = "study_session_deck";
+
+/// Local storage key, per learner with `learner_key`, holding the challenges the learner missed most
+/// recently, most recent first.
+pub const RECENT_MISTAKES_STORAGE_KEY: &str = "study_recent_mistakes";
+
+/// Number of recently missed challenges remembered.
+pub const RECENT_MISTAKES_LIMIT: usize = 10;
+
+/// Session storage key holding the challenges seen so far, used to detect duplicates when adding words.
+pub const KNOWN_CHALLENGES_STORAGE_KEY: &str = "study_known_challenges";
+
+/// Session storage key holding the challenges of newly added words, which join the next fetched batch.
+pub const NEW_WORDS_STORAGE_KEY: &str = "study_new_words";
+
+/// How long a study session lasts.
+///
+/// ## Variants:
+/// - `Items(usize)`: The session ends once this many answers have been checked.
+/// - `Minutes(u32)`: The session ends once this many minutes have passed since it started.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SessionLength {
+    Items(usize),
+    Minutes(u32),
+}
+
+impl Default for SessionLength {
+    fn default() -> Self {
+        SessionLength::Items(DEFAULT_SESSION_ITEMS)
+    }
+}
+
+/// Query parameters accepted by the study page to configure the session,
+/// e.g. `/study?items=20`, `/study?minutes=5` or `/study?items=20&spacing=5`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct SessionQuery {
+    pub items: Option<usize>,
+    pub minutes: Option<u32>,
+    pub spacing: Option<usize>,
+}
+
+impl SessionQuery {
+    /// Query for a session ending after `items` answers.
+    pub fn items(items: usize) -> Self {
+        SessionQuery { items: Some(items), ..Default::default() }
+    }
+
+    /// Query for a session ending after `minutes` minutes.
+    pub fn minutes(minutes: u32) -> Self {
+        SessionQuery { minutes: Some(minutes), ..Default::default() }
+    }
+
+    /// Resolves the requeue spacing, falling back to `DEFAULT_REQUEUE_SPACING`.
+    pub fn spacing(&self) -> usize {
+        self.spacing.unwrap_or(DEFAULT_REQUEUE_SPACING)
+    }
+
+    /// Resolves the query into a `SessionLength`, preferring `items` when both are given and
+    /// ignoring zero values.
+    pub fn length(&self) -> SessionLength {
+        self.length_or(SessionLength::default())
+    }
+
+    /// Like `length`, falling back to `default`, e.g. the learner's preferred length, when the query has none.
+    pub fn length_or(&self, default: SessionLength) -> SessionLength {
+        match (self.items, self.minutes) {
+            (Some(items), _) if items > 0 => SessionLength::Items(items),
+            (_, Some(minutes)) if minutes > 0 => SessionLength::Minutes(minutes),
+            _ => default,
+        }
+    }
+}
+
+/// Puts a missed challenge at the front of the recent mistakes, dropping its older entry and keeping at most
+/// `RECENT_MISTAKES_LIMIT` challenges.
+pub fn remember_mistake(mistakes: &mut Vec<Challenge>, challenge: &Challenge) {
+    mistakes.retain(|missed| missed.vocab_study_id != challenge.vocab_study_id);
+    mistakes.insert(0, challenge.clone());
+    mistakes.truncate(RECENT_MISTAKES_LIMIT);
+}
+
+/// A single checked answer within a study session.
+///
+/// # Fields
+///
+/// - `challenge`: The challenge that was answered.
+/// - `correct`: Whether the answer was accepted, `None` when the backend did not say.
+/// - `stats`: The user's statistics for the vocabulary item after this answer, when reported.
+/// - `hints_used`: The hints revealed before answering, one entry per reveal.
+/// - `score`: The points earned for the answer, between 0 and 1, after the hint penalty.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Attempt {
+    pub challenge: Challenge,
+    pub correct: Option<bool>,
+    pub stats: Option<VocabStats>,
+    pub hints_used: Vec<HintKind>,
+    pub score: f64,
+}
+
+/// A word the learner struggled with during a session.
+///
+/// # Fields
+///
+/// - `challenge`: The challenge for the word.
+/// - `misses`: How many answers for the word were rejected in the session.
+/// - `stats`: The most recent statistics reported for the word, if any.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct HardWord {
+    pub challenge: Challenge,
+    pub misses: u32,
+    pub stats: Option<VocabStats>,
+}
+
+/// Totals of a finished study session, shown on the summary page.
+///
+/// # Fields
+///
+/// - `attempted`: Number of answers checked.
+/// - `correct`: Number of accepted answers.
+/// - `incorrect`: Number of rejected answers.
+/// - `elapsed_ms`: Time spent in the session, in milliseconds.
+/// - `points`: The sum of the scores of all answers, after hint penalties.
+/// - `hints_used`: Number of hints revealed during the session.
+/// - `hardest`: The words missed most often, hardest first.
+/// - `missed`: Every challenge with at least one rejected answer, in the order first missed.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct SessionSummary {
+    pub attempted: usize,
+    pub correct: usize,
+    pub incorrect: usize,
+    pub elapsed_ms: f64,
+    pub points: f64,
+    pub hints_used: usize,
+    pub hardest: Vec<HardWord>,
+    pub missed: Vec<Challenge>,
+}
+
+impl SessionSummary {
+    /// Formats the time spent as `m:ss`.
+    pub fn elapsed_display(&self) -> String {
+        let total_secs = (self.elapsed_ms.max(0.0) / 1000.0).round() as u64;
+        format!("{}:{:02}", total_secs / 60, total_secs % 60)
+    }
+}
+
+/// Tracks the progress of a single study session.
+///
+/// The session owns the queue of upcoming challenges. A challenge answered incorrectly is put back
+/// into the queue `spacing` positions later, so the learner meets it again while it is still fresh.
+/// Once the configured length is reached no new challenges are served, only the missed ones, and
+/// the session is complete when every challenge answered in it was answered correctly at least once.
+///
+/// The session is clock agnostic: every method that depends on time takes the current time in
+/// milliseconds (e.g. from `js_sys::Date::now()`), which keeps it usable from native unit tests.
+///
+/// # Fields
+///
+/// - `length`: When the session stops serving new challenges.
+/// - `spacing`: How many other challenges are served before a missed challenge comes back.
+/// - `started_ms`: The time the session started, in milliseconds.
+/// - `attempts`: Every answer checked so far, in order.
+/// - `queue`: The challenges still to be served, in order.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct StudySession {
+    pub length: SessionLength,
+    pub spacing: usize,
+    pub started_ms: f64,
+    pub attempts: Vec<Attempt>,
+    pub queue: VecDeque<Challenge>,
+}
+
+impl StudySession {
+    pub fn new(length: SessionLength, started_ms: f64) -> Self {
+        StudySession {
+            length,
+            spacing: DEFAULT_REQUEUE_SPACING,
+            started_ms,
+            attempts: Vec::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Sets how many other challenges are served before a missed challenge comes back.
+    pub fn with_spacing(mut self, spacing: usize) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Adds freshly fetched challenges to the end of the queue.
+    pub fn enqueue(&mut self, challenges: Vec<Challenge>) {
+        self.queue.extend(challenges);
+    }
+
+    /// Takes the next challenge to present from the queue.
+    ///
+    /// Once the session length is reached, challenges that do not need another attempt are skipped.
+    /// Returns `None` when the queue has run out, in which case more challenges need to be fetched
+    /// unless the session `is_complete`.
+    pub fn next_challenge(&mut self, now_ms: f64) -> Option<Challenge> {
+        let length_reached = self.length_reached(now_ms);
+
+        while let Some(challenge) = self.queue.pop_front() {
+            if !length_reached || self.is_outstanding(challenge.vocab_study_id) {
+                return Some(challenge);
+            }
+        }
+
+        None
+    }
+
+    /// Records the outcome of checking an answer for `challenge`, putting the challenge back into
+    /// the queue when the answer was rejected.
+    ///
+    /// The answer's score is taken from the `CheckResult`, or is 1 for an accepted and 0 for any other
+    /// answer when the backend does not report one, and is then reduced by the cost of `hints_used`.
+    pub fn record(&mut self, challenge: &Challenge, check_result: &CheckResult, hints_used: &[HintKind]) {
+        let score = check_result.score
+            .unwrap_or(if check_result.correct == Some(true) { 1.0 } else { 0.0 });
+
+        self.attempts.push(Attempt {
+            challenge: challenge.clone(),
+            correct: check_result.correct,
+            stats: check_result.stats.clone(),
+            hints_used: hints_used.to_vec(),
+            score: hinted_score(score, hints_used),
+        });
+
+        if check_result.correct == Some(false) {
+            let position = self.spacing.min(self.queue.len());
+            self.queue.insert(position, challenge.clone());
+        }
+    }
+
+    /// Replaces the user notes of the vocab study in the queued challenges and the recorded attempts, so the
+    /// summary carries them too.
+    pub fn update_notes(&mut self, vocab_study_id: i32, user_notes: &str) {
+        self.queue.iter_mut()
+            .chain(self.attempts.iter_mut().map(|attempt| &mut attempt.challenge))
+            .filter(|challenge| challenge.vocab_study_id == vocab_study_id)
+            .for_each(|challenge| challenge.user_notes = user_notes.to_string());
+    }
+
+    /// Number of answers checked for the vocab study in this session.
+    pub fn attempts_for(&self, vocab_study_id: i32) -> usize {
+        self.attempts.iter().filter(|a| a.challenge.vocab_study_id == vocab_study_id).count()
+    }
+
+    /// Returns `true` when the vocab study was answered in this session but never correctly.
+    pub fn is_outstanding(&self, vocab_study_id: i32) -> bool {
+        let mut attempts = self.attempts.iter().filter(|a| a.challenge.vocab_study_id == vocab_study_id);
+        attempts.clone().any(|a| a.correct == Some(false)) && !attempts.any(|a| a.correct == Some(true))
+    }
+
+    /// Number of distinct challenges answered in this session that still need a correct answer.
+    pub fn num_outstanding(&self) -> usize {
+        let mut ids: Vec<i32> = self.attempts.iter().map(|a| a.challenge.vocab_study_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.into_iter().filter(|id| self.is_outstanding(*id)).count()
+    }
+
+    /// Returns `true` once the session has reached its configured length and stops serving new challenges.
+    pub fn length_reached(&self, now_ms: f64) -> bool {
+        match self.length {
+            SessionLength::Items(items) => self.attempts.len() >= items,
+            SessionLength::Minutes(minutes) => now_ms - self.started_ms >= minutes as f64 * 60_000.0,
+        }
+    }
+
+    /// Returns `true` once the session has reached its configured length and every challenge answered
+    /// in it was answered correctly at least once.
+    pub fn is_complete(&self, now_ms: f64) -> bool {
+        self.length_reached(now_ms) && self.num_outstanding() == 0
+    }
+
+    /// Builds the summary of the session as of `now_ms`.
+    pub fn summary(&self, now_ms: f64) -> SessionSummary {
+        let correct = self.attempts.iter().filter(|a| a.correct == Some(true)).count();
+        let incorrect = self.attempts.iter().filter(|a| a.correct == Some(false)).count();
+
+        let mut hard_words: Vec<HardWord> = Vec::new();
+        for attempt in &self.attempts {
+            let id = attempt.challenge.vocab_study_id;
+            let missed = attempt.correct == Some(false);
+
+            match hard_words.iter_mut().find(|word| word.challenge.vocab_study_id == id) {
+                Some(word) => {
+                    word.misses += missed as u32;
+                    if attempt.stats.is_some() {
+                        word.stats = attempt.stats.clone();
+                    }
+                }
+                None => hard_words.push(HardWord {
+                    challenge: attempt.challenge.clone(),
+                    misses: missed as u32,
+                    stats: attempt.stats.clone(),
+                }),
+            }
+        }
+        hard_words.retain(|word| word.misses > 0);
+
+        let missed = hard_words.iter().map(|word| word.challenge.clone()).collect();
+
+        // Most misses first, then the lowest overall accuracy; the sort is stable so ties keep session order
+        let mut hardest = hard_words;
+        hardest.sort_by(|a, b| {
+            b.misses.cmp(&a.misses).then_with(|| {
+                let a_pct = a.stats.as_ref().map_or(100.0, |s| s.percentage_correct);
+                let b_pct = b.stats.as_ref().map_or(100.0, |s| s.percentage_correct);
+                a_pct.total_cmp(&b_pct)
+            })
+        });
+        hardest.truncate(HARDEST_WORDS_LIMIT);
+
+        SessionSummary {
+            attempted: self.attempts.len(),
+            correct,
+            incorrect,
+            elapsed_ms: (now_ms - self.started_ms).max(0.0),
+            points: self.attempts.iter().map(|a| a.score).sum(),
+            hints_used: self.attempts.iter().map(|a| a.hints_used.len()).sum(),
+            hardest,
+            missed,
+        }
+    }
+}
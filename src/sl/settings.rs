@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use wasm_bindgen::JsValue;
+use crate::sl::hints::{HintKind, DEFAULT_HINT_ORDER};
+use crate::sl::i18n::{DEFAULT_UI_LANGUAGE, UI_LANGUAGES};
+use crate::sl::session::SessionLength;
+use crate::sl::storage::{load_json, remove, save_json, StorageArea};
+use crate::sl::theme::{ThemeSettings, THEME_STORAGE_KEY};
+
+/// Local storage key holding the learner's `Settings`.
+pub const SETTINGS_STORAGE_KEY: &str = "settings";
+
+/// The version of the `Settings` format written by this build. Bump it and add a step to `upgrade` when a
+/// change cannot be read by `#[serde(default)]` alone.
+pub const SETTINGS_VERSION: u32 = 2;
+
+/// Number of challenges fetched from the backend at a time when not set.
+pub const DEFAULT_BATCH_SIZE: usize = 5;
+
+/// Largest number of challenges fetched at a time.
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// The learner's preferences, kept in local storage under `SETTINGS_STORAGE_KEY`.
+///
+/// Fields added after a version was released must have a `#[serde(default)]`, so settings saved by older
+/// builds still load.
+///
+/// # Fields
+///
+/// - `version`: The format version the settings were saved with.
+/// - `batch_size`: How many challenges are fetched from the backend at a time.
+/// - `session_length`: How long a session lasts when the study page is opened without a length.
+/// - `auto_speak`: Whether the expected answer is read aloud after each check.
+/// - `hint_order`: The hint kinds offered, in the order they are revealed.
+/// - `auto_focus`: Whether the answer box and the next button are focused automatically.
+/// - `theme`: The colours and the text size.
+/// - `language`: The interface language, one of `UI_LANGUAGES`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    pub batch_size: usize,
+    pub session_length: SessionLength,
+    pub auto_speak: bool,
+    pub hint_order: Vec<HintKind>,
+    pub auto_focus: bool,
+    pub theme: ThemeSettings,
+    pub language: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            batch_size: DEFAULT_BATCH_SIZE,
+            session_length: SessionLength::default(),
+            auto_speak: false,
+            hint_order: DEFAULT_HINT_ORDER.to_vec(),
+            auto_focus: true,
+            theme: ThemeSettings::default(),
+            language: DEFAULT_UI_LANGUAGE.to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// Brings every field back into its allowed range, e.g. after a hand edited or corrupted save.
+    ///
+    /// The version is kept, so settings read from a newer build are not saved as this build's format.
+    pub fn validated(mut self) -> Self {
+        self.batch_size = self.batch_size.clamp(1, MAX_BATCH_SIZE);
+        self.session_length = match self.session_length {
+            SessionLength::Items(0) | SessionLength::Minutes(0) => SessionLength::default(),
+            length => length,
+        };
+        if !UI_LANGUAGES.contains(&self.language.as_str()) {
+            self.language = DEFAULT_UI_LANGUAGE.to_string();
+        }
+
+        let mut seen = Vec::new();
+        self.hint_order.retain(|kind| {
+            let first = !seen.contains(kind);
+            seen.push(*kind);
+            first
+        });
+        self
+    }
+}
+
+/// Upgrades stored settings from `version` to the next version.
+fn upgrade(stored: &mut Map<String, Value>, version: u64) {
+    match version {
+        // Settings saved before they had a version have the fields of version 1
+        0 => {}
+        // Version 1 had a study mode and a translation direction, both dropped in version 2
+        1 => {
+            stored.remove("session_mode");
+            stored.remove("direction");
+        }
+        _ => {}
+    }
+}
+
+/// Reads stored settings field by field, so a field that cannot be read, e.g. one a newer build changed,
+/// keeps its default without resetting the others.
+fn read_fields(stored: Map<String, Value>) -> Settings {
+    let Ok(Value::Object(mut fields)) = serde_json::to_value(Settings::default()) else {
+        return Settings::default();
+    };
+
+    for (key, value) in stored {
+        let previous = fields.insert(key.clone(), value);
+        if serde_json::from_value::<Settings>(Value::Object(fields.clone())).is_err() {
+            match previous {
+                Some(previous) => fields.insert(key, previous),
+                None => fields.remove(&key),
+            };
+        }
+    }
+    serde_json::from_value(Value::Object(fields)).unwrap_or_default()
+}
+
+/// Reads stored settings of any version into the current `Settings`.
+///
+/// Older settings are upgraded one version at a time. Settings from a newer build are read as far as this
+/// build understands them and keep their version, so `to_store` does not overwrite what it could not read.
+///
+/// # Arguments
+///
+/// * `stored` - The stored JSON, `None` when nothing is stored yet.
+/// * `legacy_theme` - The theme saved under `THEME_STORAGE_KEY` before the theme became part of the
+///   settings, folded in when no settings are stored yet.
+///
+/// # Returns
+///
+/// * `Settings`: The migrated settings. Fields that cannot be read are replaced by their defaults rather
+///   than failing.
+pub fn migrate(stored: Option<Value>, legacy_theme: Option<ThemeSettings>) -> Settings {
+    let Some(Value::Object(mut stored)) = stored else {
+        return Settings { theme: legacy_theme.unwrap_or_default(), ..Default::default() };
+    };
+
+    let version = stored.get("version").and_then(Value::as_u64).unwrap_or(0);
+    for step in version..SETTINGS_VERSION as u64 {
+        upgrade(&mut stored, step);
+    }
+    stored.insert("version".to_string(), version.max(SETTINGS_VERSION as u64).into());
+
+    read_fields(stored).validated()
+}
+
+/// The JSON to store for `settings`, given the JSON stored so far.
+///
+/// Settings of this build's version or older are written whole. Over settings from a newer build only the
+/// fields changed here are written, so the fields and the version of the newer format survive.
+pub fn to_store(stored: Option<Value>, settings: &Settings) -> Value {
+    let fields = serde_json::to_value(settings).unwrap_or_default();
+    let newer = stored.as_ref()
+        .and_then(|stored| stored.get("version"))
+        .and_then(Value::as_u64)
+        .is_some_and(|version| version > SETTINGS_VERSION as u64);
+    let (true, Some(Value::Object(mut merged)), Value::Object(fields)) = (newer, stored.clone(), &fields) else {
+        return fields;
+    };
+
+    let read = serde_json::to_value(migrate(stored, None)).unwrap_or_default();
+    for (key, value) in fields {
+        if read.get(key) != Some(value) {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    Value::Object(merged)
+}
+
+/// Loads the learner's settings from local storage, migrating them to the current version.
+pub fn load_settings() -> Settings {
+    let stored = load_json::<Value>(StorageArea::Local, SETTINGS_STORAGE_KEY);
+    let legacy_theme = load_json::<ThemeSettings>(StorageArea::Local, THEME_STORAGE_KEY);
+    migrate(stored, legacy_theme)
+}
+
+/// Saves the learner's settings in local storage, removing the settings they replace.
+pub fn save_settings(settings: &Settings) -> Result<(), JsValue> {
+    let stored = load_json::<Value>(StorageArea::Local, SETTINGS_STORAGE_KEY);
+    save_json(StorageArea::Local, SETTINGS_STORAGE_KEY, &to_store(stored, settings))?;
+    remove(StorageArea::Local, THEME_STORAGE_KEY);
+    Ok(())
+}
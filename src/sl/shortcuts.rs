@@ -0,0 +1,265 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Local storage key holding the learner's `ShortcutMap`.
+pub const SHORTCUTS_STORAGE_KEY: &str = "keyboard_shortcuts";
+
+/// Longest pause between the keys of a sequence such as `g p`, in milliseconds.
+pub const SEQUENCE_TIMEOUT_MS: f64 = 1_000.0;
+
+/// Something the learner can do from the keyboard.
+///
+/// ## Variants:
+/// - `Hint`: Reveals the next hint of the current challenge.
+/// - `Next`: Moves on to the next challenge once an answer is checked.
+/// - `Speak`: Reads the current word aloud.
+/// - `Help`: Opens or closes the list of shortcuts.
+/// - `GoHome`, `GoStudy`, `GoVocab`, `GoProfile`: Navigates to a page.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum ShortcutAction {
+    Hint,
+    Next,
+    Speak,
+    Help,
+    GoHome,
+    GoStudy,
+    GoVocab,
+    GoProfile,
+}
+
+impl ShortcutAction {
+    /// All the actions, in the order they are listed in the help.
+    pub const ALL: [ShortcutAction; 8] = [
+        ShortcutAction::Hint,
+        ShortcutAction::Next,
+        ShortcutAction::Speak,
+        ShortcutAction::Help,
+        ShortcutAction::GoHome,
+        ShortcutAction::GoStudy,
+        ShortcutAction::GoVocab,
+        ShortcutAction::GoProfile,
+    ];
+
+    /// Actions available on every page. The others only work on pages that register them.
+    pub const GLOBAL: [ShortcutAction; 5] = [
+        ShortcutAction::Help,
+        ShortcutAction::GoHome,
+        ShortcutAction::GoStudy,
+        ShortcutAction::GoVocab,
+        ShortcutAction::GoProfile,
+    ];
+
+    pub fn is_global(&self) -> bool {
+        ShortcutAction::GLOBAL.contains(self)
+    }
+
+    /// Describes the action for the help.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ShortcutAction::Hint => "Show a hint",
+            ShortcutAction::Next => "Next challenge",
+            ShortcutAction::Speak => "Read the word aloud",
+            ShortcutAction::Help => "Show or hide this help",
+            ShortcutAction::GoHome => "Go to the home page",
+            ShortcutAction::GoStudy => "Go to study",
+            ShortcutAction::GoVocab => "Go to my words",
+            ShortcutAction::GoProfile => "Go to my progress",
+        }
+    }
+}
+
+/// Names a key the way `KeyboardEvent.key` does, with the space bar named `Space` so sequences can be
+/// written with spaces between their keys.
+pub fn normalize_key(key: &str) -> String {
+    match key {
+        " " => "Space".to_string(),
+        "Esc" => "Escape".to_string(),
+        _ => key.to_string(),
+    }
+}
+
+/// The keys pressed one after the other to trigger a shortcut, e.g. `g p`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone)]
+pub struct KeySequence(pub Vec<String>);
+
+impl KeySequence {
+    /// Parses keys separated by spaces, returning `None` when there are none.
+    pub fn parse(text: &str) -> Option<Self> {
+        let keys: Vec<String> = text.split_whitespace().map(normalize_key).collect();
+        (!keys.is_empty()).then_some(KeySequence(keys))
+    }
+
+    pub fn starts_with(&self, prefix: &[String]) -> bool {
+        self.0.starts_with(prefix)
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
+
+/// A key sequence bound to an action.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Shortcut {
+    pub action: ShortcutAction,
+    pub keys: KeySequence,
+}
+
+/// Two shortcuts that cannot be told apart: their sequences are the same, or one starts the other so the
+/// longer one could never be typed.
+///
+/// # Fields
+///
+/// - `first`, `second`: The conflicting shortcuts.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ShortcutConflict {
+    pub first: Shortcut,
+    pub second: Shortcut,
+}
+
+impl fmt::Display for ShortcutConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" ({}) conflicts with \"{}\" ({})",
+               self.first.keys, self.first.action.label(), self.second.keys, self.second.action.label())
+    }
+}
+
+/// The keyboard shortcuts of the application, stored in local storage so learners can change them.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ShortcutMap {
+    pub shortcuts: Vec<Shortcut>,
+}
+
+impl Default for ShortcutMap {
+    fn default() -> Self {
+        let shortcuts = [
+            (ShortcutAction::Hint, "h"),
+            (ShortcutAction::Next, "n"),
+            (ShortcutAction::Next, "Enter"),
+            (ShortcutAction::Speak, "s"),
+            (ShortcutAction::Help, "?"),
+            (ShortcutAction::GoHome, "g h"),
+            (ShortcutAction::GoStudy, "g s"),
+            (ShortcutAction::GoVocab, "g w"),
+            (ShortcutAction::GoProfile, "g p"),
+        ];
+
+        ShortcutMap {
+            shortcuts: shortcuts.iter()
+                .filter_map(|(action, keys)| Some(Shortcut { action: *action, keys: KeySequence::parse(keys)? }))
+                .collect(),
+        }
+    }
+}
+
+impl ShortcutMap {
+    /// The key sequences bound to an action.
+    pub fn keys_for(&self, action: ShortcutAction) -> Vec<&KeySequence> {
+        self.shortcuts.iter().filter(|shortcut| shortcut.action == action).map(|shortcut| &shortcut.keys).collect()
+    }
+
+    /// Every pair of shortcuts that conflict. Binding the same sequence twice to one action is not a conflict.
+    pub fn conflicts(&self) -> Vec<ShortcutConflict> {
+        let mut conflicts = Vec::new();
+
+        for (index, first) in self.shortcuts.iter().enumerate() {
+            for second in self.shortcuts.iter().skip(index + 1) {
+                let overlap = first.keys.starts_with(&second.keys.0) || second.keys.starts_with(&first.keys.0);
+                if overlap && !(first.action == second.action && first.keys == second.keys) {
+                    conflicts.push(ShortcutConflict { first: first.clone(), second: second.clone() });
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Replaces the key sequences of an action, refusing the change when it introduces a conflict.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to rebind.
+    /// * `keys` - Its new key sequences. An empty list leaves the action without a shortcut.
+    pub fn rebind(&self, action: ShortcutAction, keys: Vec<KeySequence>) -> Result<ShortcutMap, ShortcutConflict> {
+        let mut shortcuts: Vec<Shortcut> = self.shortcuts.iter().filter(|shortcut| shortcut.action != action).cloned().collect();
+        shortcuts.extend(keys.into_iter().map(|keys| Shortcut { action, keys }));
+
+        let map = ShortcutMap { shortcuts };
+        match map.conflicts().into_iter().next() {
+            Some(conflict) => Err(conflict),
+            None => Ok(map),
+        }
+    }
+}
+
+/// Whether a key press belongs to the element it happened in rather than to the shortcuts.
+///
+/// Keys typed into text fields are never shortcuts, and neither is `Enter` on buttons and links, which
+/// activates them.
+///
+/// # Arguments
+///
+/// * `tag_name` - The tag name of the event target, e.g. `INPUT`.
+/// * `editable` - Whether the target is content editable.
+/// * `key` - The normalized key.
+pub fn ignores_key(tag_name: &str, editable: bool, key: &str) -> bool {
+    match tag_name.to_ascii_uppercase().as_str() {
+        "INPUT" | "TEXTAREA" | "SELECT" => true,
+        "BUTTON" | "A" => key == "Enter" || key == "Space",
+        _ => editable,
+    }
+}
+
+/// Turns key presses into actions, following the sequences of a `ShortcutMap`.
+///
+/// # Fields
+///
+/// - `pending`: The keys of a sequence typed so far.
+/// - `last_key_ms`: When the last pending key was pressed.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct KeyBuffer {
+    pub pending: Vec<String>,
+    pub last_key_ms: f64,
+}
+
+impl KeyBuffer {
+    /// Adds a key press, returning the action it completes, if any.
+    ///
+    /// A key that starts a longer sequence is held until the next key. When the next key does not continue
+    /// the sequence, or comes after `SEQUENCE_TIMEOUT_MS`, the sequence is dropped and the key is tried on
+    /// its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `map` - The shortcuts.
+    /// * `active` - The actions available on the current page.
+    /// * `key` - The normalized key.
+    /// * `now_ms` - The time of the key press.
+    pub fn press(&mut self, map: &ShortcutMap, active: &[ShortcutAction], key: &str, now_ms: f64) -> Option<ShortcutAction> {
+        if now_ms - self.last_key_ms > SEQUENCE_TIMEOUT_MS {
+            self.pending.clear();
+        }
+        self.last_key_ms = now_ms;
+
+        let retry = !self.pending.is_empty();
+        self.pending.push(key.to_string());
+
+        let shortcuts: Vec<&Shortcut> = map.shortcuts.iter().filter(|shortcut| active.contains(&shortcut.action)).collect();
+        if let Some(shortcut) = shortcuts.iter().find(|shortcut| shortcut.keys.0 == self.pending) {
+            self.pending.clear();
+            return Some(shortcut.action);
+        }
+        if shortcuts.iter().any(|shortcut| shortcut.keys.starts_with(&self.pending)) {
+            return None;
+        }
+
+        self.pending.clear();
+        if retry {
+            self.press(map, active, key, now_ms)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use wasm_bindgen::JsValue;
+use web_sys::SpeechSynthesisUtterance;
+
+/// Reads `text` aloud with the browser's speech synthesis, cancelling anything still being read.
+///
+/// # Arguments
+///
+/// * `text` - The text to read.
+/// * `lang_code` - The language of the text, e.g. `es`, so a matching voice is picked.
+///
+/// # Returns
+///
+/// * `Result<(), JsValue>`: An error when the browser has no speech synthesis.
+pub fn speak(text: &str, lang_code: &str) -> Result<(), JsValue> {
+    let synthesis = web_sys::window()
+        .ok_or_else(|| JsValue::from_str("The page has no window"))?
+        .speech_synthesis()?;
+
+    let utterance = SpeechSynthesisUtterance::new_with_text(text)?;
+    utterance.set_lang(lang_code);
+
+    synthesis.cancel();
+    synthesis.speak(&utterance);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+use web_sys::Storage;
+
+/// The browser storage area a value is kept in.
+///
+/// ## Variants:
+/// - `Session`: `window.sessionStorage`, cleared when the tab is closed. Used to hand data between pages.
+/// - `Local`: `window.localStorage`, kept across visits. Used for user preferences.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum StorageArea {
+    Session,
+    Local,
+}
+
+impl StorageArea {
+    /// Returns the browser `Storage` for this area, or `None` outside a browser or when storage is disabled.
+    fn storage(&self) -> Option<Storage> {
+        let window = web_sys::window()?;
+        match self {
+            StorageArea::Session => window.session_storage().ok()?,
+            StorageArea::Local => window.local_storage().ok()?,
+        }
+    }
+}
+
+/// Loads and deserializes the JSON value stored under `key`.
+///
+/// Returns `None` when the storage is unavailable, the key is not set or the stored JSON no longer
+/// matches `T`.
+pub fn load_json<T: DeserializeOwned>(area: StorageArea, key: &str) -> Option<T> {
+    let json = area.storage()?.get_item(key).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+/// Loads the JSON value stored under `key` and removes it from the storage.
+pub fn take_json<T: DeserializeOwned>(area: StorageArea, key: &str) -> Option<T> {
+    let value = load_json(area, key);
+    remove(area, key);
+    value
+}
+
+/// Serializes `value` as JSON and stores it under `key`.
+///
+/// # Returns
+///
+/// * `Result<(), JsValue>`: An error when the storage is unavailable, full, or `value` cannot be serialized.
+pub fn save_json<T: Serialize>(area: StorageArea, key: &str, value: &T) -> Result<(), JsValue> {
+    let storage = area.storage().ok_or_else(|| JsValue::from_str("Browser storage is not available"))?;
+    let json = serde_json::to_string(value).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    storage.set_item(key, &json)
+}
+
+/// Removes the value stored under `key`, if any.
+pub fn remove(area: StorageArea, key: &str) {
+    if let Some(storage) = area.storage() {
+        storage.remove_item(key).unwrap_or_default();
+    }
+}
+
+/// The key a value of one learner is stored under, e.g. `study_schedule_42`, so learners sharing a
+/// browser each keep their own goal, activity and schedule.
+pub fn learner_key(key: &str, awesome_id: i32) -> String {
+    format!("{}_{}", key, awesome_id)
+}
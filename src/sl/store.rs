@@ -0,0 +1,208 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use yew::{Callback, Reducible};
+use crate::sl::awesome::{selected_learner, AwesomeProfile, DEFAULT_AWESOME_ID};
+use crate::sl::gql::QueryCache;
+use crate::sl::session::StudySession;
+use crate::sl::settings::{load_settings, Settings};
+use crate::sl::theme::ThemeSettings;
+
+/// The state shared by every page, kept while the learner navigates between them.
+///
+/// # Fields
+///
+/// - `learner`: The learner selected in this browser, `None` for anonymous visitors.
+/// - `profile`: The last fetched statistics of the selected learner, `None` until they are fetched.
+/// - `settings`: The learner's preferences.
+/// - `session`: The study session the learner left unfinished, resumed when they return to the study page.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct AppState {
+    pub learner: Option<i32>,
+    pub profile: Option<AwesomeProfile>,
+    pub settings: Settings,
+    pub session: Option<StudySession>,
+}
+
+impl AppState {
+    /// The state the application starts with, restored from local storage.
+    pub fn load() -> Self {
+        AppState {
+            learner: selected_learner(),
+            settings: load_settings(),
+            ..Default::default()
+        }
+    }
+
+    /// The awesome id to study for: the selected learner, or `DEFAULT_AWESOME_ID`.
+    pub fn learner_id(&self) -> i32 {
+        self.learner.unwrap_or(DEFAULT_AWESOME_ID)
+    }
+}
+
+/// Changes to the `AppState`, applied by `Store::dispatch`.
+///
+/// ## Variants:
+/// - `SelectLearner(i32)`: Selects a learner, dropping the profile and session of the previous one.
+/// - `ForgetLearner`: Forgets the selected learner, making the visitor anonymous again.
+/// - `ProfileLoaded(AwesomeProfile)`: Keeps the fetched statistics of the selected learner. Statistics of
+///   another learner, e.g. from a fetch that finished after switching, are ignored.
+/// - `ChangeSettings(Settings)`: Replaces the settings, after validating them.
+/// - `ChangeTheme(ThemeSettings)`: Replaces only the theme settings.
+/// - `SaveSession(StudySession)`: Keeps an unfinished study session to resume later.
+/// - `EndSession`: Drops the kept study session.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Action {
+    SelectLearner(i32),
+    ForgetLearner,
+    ProfileLoaded(AwesomeProfile),
+    ChangeSettings(Settings),
+    ChangeTheme(ThemeSettings),
+    SaveSession(StudySession),
+    EndSession,
+}
+
+impl Reducible for AppState {
+    type Action = Action;
+
+    /// Applies an `Action`, returning the same `Rc` when nothing changed so subscribers are not notified.
+    fn reduce(self: Rc<Self>, action: Action) -> Rc<Self> {
+        let mut state = (*self).clone();
+
+        match action {
+            Action::SelectLearner(awesome_id) => {
+                if state.learner != Some(awesome_id) {
+                    state = AppState { learner: Some(awesome_id), settings: state.settings, ..Default::default() };
+                }
+            }
+            Action::ForgetLearner => state = AppState { settings: state.settings, ..Default::default() },
+            Action::ProfileLoaded(profile) => {
+                if state.learner == Some(profile.id) {
+                    state.profile = Some(profile);
+                }
+            }
+            Action::ChangeSettings(settings) => state.settings = settings.validated(),
+            Action::ChangeTheme(theme) => state.settings.theme = theme,
+            Action::SaveSession(session) => state.session = Some(session),
+            Action::EndSession => state.session = None,
+        }
+
+        if state == *self {
+            self
+        } else {
+            Rc::new(state)
+        }
+    }
+}
+
+type Listener = Rc<dyn Fn(&AppState)>;
+
+struct StoreInner {
+    state: RefCell<Rc<AppState>>,
+    listeners: RefCell<Vec<(usize, Listener)>>,
+    next_id: Cell<usize>,
+    queries: RefCell<QueryCache>,
+}
+
+/// The app-wide store holding the `AppState`.
+///
+/// Pages read the current state with `state`, change it with `dispatch` and subscribe to the part they
+/// display with `select`, so they only re-render when that part changes. Clones share the same state;
+/// two stores are equal when they are clones of each other, so providing the store as a context never
+/// re-renders the whole tree.
+///
+/// The store also keeps the results of GraphQL queries in a `QueryCache`, used through `sl::gql`. They are
+/// kept beside the `AppState` rather than in it, so caching a result never notifies the subscribers.
+#[derive(Clone)]
+pub struct Store {
+    inner: Rc<StoreInner>,
+}
+
+impl PartialEq for Store {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Store {
+    pub fn new(state: AppState) -> Self {
+        Store {
+            inner: Rc::new(StoreInner {
+                state: RefCell::new(Rc::new(state)),
+                listeners: RefCell::new(Vec::new()),
+                next_id: Cell::new(0),
+                queries: RefCell::new(QueryCache::default()),
+            }),
+        }
+    }
+
+    /// The current state.
+    pub fn state(&self) -> Rc<AppState> {
+        self.inner.state.borrow().clone()
+    }
+
+    /// Applies an `Action` and notifies the subscribers when the state changed.
+    pub fn dispatch(&self, action: Action) {
+        let current = self.state();
+        let next = current.clone().reduce(action);
+        if Rc::ptr_eq(&current, &next) {
+            return;
+        }
+
+        *self.inner.state.borrow_mut() = next.clone();
+        // Listeners may dispatch or unsubscribe, so they are called without holding the borrow
+        let listeners: Vec<Listener> = self.inner.listeners.borrow().iter().map(|(_, listener)| listener.clone()).collect();
+        for listener in listeners {
+            listener(&next);
+        }
+    }
+
+    /// Runs `f` with the query results kept in the store.
+    pub fn with_queries<R>(&self, f: impl FnOnce(&mut QueryCache) -> R) -> R {
+        f(&mut self.inner.queries.borrow_mut())
+    }
+
+    /// Subscribes to a part of the state.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - Picks the part of the state the subscriber is interested in.
+    /// * `on_change` - Called with the selected part whenever it changes.
+    ///
+    /// # Returns
+    ///
+    /// * `Subscription`: Keeps the subscription alive, unsubscribing when dropped.
+    pub fn select<T, F>(&self, selector: F, on_change: Callback<T>) -> Subscription
+    where
+        T: PartialEq + Clone + 'static,
+        F: Fn(&AppState) -> T + 'static,
+    {
+        let last = RefCell::new(selector(&self.state()));
+        let listener: Listener = Rc::new(move |state: &AppState| {
+            let selected = selector(state);
+            if *last.borrow() != selected {
+                *last.borrow_mut() = selected.clone();
+                on_change.emit(selected);
+            }
+        });
+
+        let id = self.inner.next_id.get();
+        self.inner.next_id.set(id + 1);
+        self.inner.listeners.borrow_mut().push((id, listener));
+
+        Subscription { store: Rc::downgrade(&self.inner), id }
+    }
+}
+
+/// A subscription to a `Store`, returned by `Store::select`. Dropping it unsubscribes.
+pub struct Subscription {
+    store: Weak<StoreInner>,
+    id: usize,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(store) = self.store.upgrade() {
+            store.listeners.borrow_mut().retain(|(id, _)| *id != self.id);
+        }
+    }
+}
@@ -1,6 +1,12 @@
-use graphql_client::GraphQLQuery;
+use std::collections::HashMap;
+use graphql_client::{GraphQLQuery, QueryBody};
 use serde::{Deserialize, Serialize};
-use crate::sl::gql::{post_gql_query, FetchError};
+use crate::sl::gql::{cached_query, invalidate, peek_query, post_gql_query, CacheTag, Cached, FetchError, GqlError, QueryKey};
+use crate::sl::hints::HintKind;
+use crate::sl::store::Store;
+
+/// How long a fetched `Challenge` of a single vocab study is used before it is fetched again, in milliseconds.
+pub const CHALLENGE_TTL_MS: f64 = 5.0 * 60_000.0;
 
 /// Response JSON wrapper
 #[derive(Serialize, Deserialize, Debug)]
@@ -24,11 +30,13 @@ pub struct Data {
 /// # Fields
 ///
 /// - `vocab_id`: The unique identifier of the vocabulary item being challenged. This relates to a specific
-/// word or phrase in the study material.
+///   word or phrase in the study material.
 /// - `vocab_study_id`: The unique identifier for the user's study history with this vocabulary item,
-/// allowing for tracking of progress and retrieval of user-specific study data.
+///   allowing for tracking of progress and retrieval of user-specific study data.
 /// - `prompt`: The question or challenge presented to the user, designed to elicit the correct response or
-/// translation based on the vocabulary being studied.
+///   translation based on the vocabulary being studied.
+/// - `learning`: The vocabulary in the learning language, used to build letter based hints. It is not part
+///   of the study list, so it is empty until fetched with `fetch_learning_texts`.
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct Challenge {
     #[serde(rename = "vocabId")]
@@ -58,6 +66,9 @@ pub struct Challenge {
 
     #[serde(rename = "learningLangCode")]
     pub learning_lang_code: String,
+
+    #[serde(default)]
+    pub learning: String,
 }
 
 /// Represents a GraphQL query for fetching a list of vocabulary study items.
@@ -117,6 +128,62 @@ pub async fn fetch_vocab_study_list(awesome_id: i32, limit: i32) -> Result<Vec<C
 }
 
 
+/// Response JSON wrapper
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChallengeResponseWrapper {
+    pub data: ChallengeData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChallengeData {
+    #[serde(rename = "getChallenge")]
+    pub get_challenge: Challenge,
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/challenge.graphql",
+response_derives = "Debug"
+)]
+struct ChallengeQuery;
+
+fn challenge_query(vocab_study_id: i32) -> QueryBody<challenge_query::Variables> {
+    ChallengeQuery::build_query(challenge_query::Variables {
+        vocab_study_id: vocab_study_id.into(),
+    })
+}
+
+/// Fetches the `Challenge` for a single vocab study.
+///
+/// The challenge is cached for `CHALLENGE_TTL_MS`, or until an answer for it is checked or its notes change.
+///
+/// # Arguments
+///
+/// * `store` - The store caching the challenge.
+/// * `vocab_study_id` - The unique identifier of the vocab study.
+///
+/// # Returns
+///
+/// A `Result` wrapping the `Challenge` on success, or a `FetchError` on failure.
+pub async fn fetch_challenge(store: &Store, vocab_study_id: i32) -> Result<Challenge, FetchError> {
+    let build_query = challenge_query(vocab_study_id);
+
+    cached_query(store, QueryKey::of(&build_query), CHALLENGE_TTL_MS, vec![CacheTag::VocabStudy(vocab_study_id)], || async move {
+        // Serialize the query to a string
+        let query_string = serde_json::to_string(&build_query)?;
+        let gql_json_res = post_gql_query(query_string).await?;
+        let wrapper: ChallengeResponseWrapper = serde_json::from_str(&gql_json_res)?;
+        Ok(wrapper.data.get_challenge)
+    }).await
+}
+
+/// The cached `Challenge` of a vocab study, fresh or stale, without fetching it.
+pub fn peek_challenge(store: &Store, vocab_study_id: i32) -> Option<Cached<Challenge>> {
+    peek_query(store, &QueryKey::of(&challenge_query(vocab_study_id)))
+}
+
+
 /// Response JSON wrapper
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CheckAnswerResponseWrapper {
@@ -129,18 +196,84 @@ pub struct Check {
     pub response_prompt: String,
 }
 
+/// Response JSON wrapper for the structured `checkAnswer` mutation.
+///
+/// Unlike the other wrappers, `data` is optional and `errors` is kept, so that a backend which does not
+/// know the mutation yet can be detected and the legacy `checkResponse` mutation used instead.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CheckResultResponseWrapper {
+    pub data: Option<CheckResultData>,
+    #[serde(default)]
+    pub errors: Vec<GqlError>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CheckResultData {
+    #[serde(rename = "checkAnswer")]
+    pub check_answer: CheckResult,
+}
 
-/// Performs a GraphQL query to check a user's answer against the correct vocabulary answer.
+/// Statistics for the user's study history with a single vocabulary item.
 ///
-/// This function sends a query to a GraphQL server with the user's answer and the associated vocabulary study information.
-/// It utilizes the `CheckResponse` GraphQL query which is defined in a separate `.graphql` file.
+/// # Fields
 ///
-/// ## Parameters:
-/// - `answer`: The answer provided by the user.
-/// - `challenge`: A `Challenge` struct containing the `vocab_id`, `vocab_study_id`, and the prompt for the vocabulary challenge.
+/// - `learning`: The word or phrase in the learning language.
+/// - `attempts`: How many times the user has answered this item.
+/// - `correct_attempts`: How many of those answers were accepted.
+/// - `percentage_correct`: The share of accepted answers, from 0 to 100.
+/// - `last_change`: The change in `percentage_correct` caused by the most recent answer.
+/// - `last_tested`: When the item was last answered, formatted by the backend for display.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct VocabStats {
+    pub learning: String,
+    pub attempts: i32,
+
+    #[serde(rename = "correctAttempts")]
+    pub correct_attempts: i32,
+
+    #[serde(rename = "percentageCorrect")]
+    pub percentage_correct: f64,
+
+    #[serde(rename = "lastChange")]
+    pub last_change: f64,
+
+    #[serde(rename = "lastTested")]
+    pub last_tested: String,
+}
+
+/// The outcome of checking a user's answer to a `Challenge`.
 ///
-/// ## Returns:
-/// - A `Result` which is `Ok` containing the server's response prompt if the query was successful, or a `FetchError` if there was an issue with the query.
+/// Backends that support the `checkAnswer` mutation fill in every field. Older backends only return a
+/// feedback string, in which case `correct`, `expected`, `distance`, `score` and `stats` are `None`.
+///
+/// # Fields
+///
+/// - `correct`: Whether the answer was accepted.
+/// - `expected`: The correct answer for the challenge.
+/// - `distance`: The edit distance between the entered and the expected answer.
+/// - `score`: A score between 0 and 1 for the answer, 1 being a perfect answer.
+/// - `feedback`: The message to show to the user about their answer.
+/// - `stats`: The user's statistics for the vocabulary item, including this answer.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct CheckResult {
+    pub correct: Option<bool>,
+    pub expected: Option<String>,
+    pub distance: Option<i32>,
+    pub score: Option<f64>,
+
+    #[serde(rename = "prompt")]
+    pub feedback: String,
+
+    pub stats: Option<VocabStats>,
+}
+
+impl From<String> for CheckResult {
+    /// Wraps the plain feedback string returned by the legacy `checkResponse` mutation.
+    fn from(feedback: String) -> Self {
+        CheckResult { feedback, ..Default::default() }
+    }
+}
+
 #[derive(GraphQLQuery)]
 #[graphql(
 schema_path = "./graphql/schema.graphql",
@@ -148,18 +281,314 @@ query_path = "./graphql/check.graphql",
 response_derives = "Debug"
 )]
 struct CheckResponse;
-pub async fn check_vocab_answer(answer: String, challenge: Challenge) -> Result<String, FetchError>{
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/check_answer.graphql",
+response_derives = "Debug"
+)]
+struct CheckAnswer;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/check_answer_without_key.graphql",
+response_derives = "Debug"
+)]
+struct CheckAnswerWithoutKey;
+
+/// The `CheckAnswer` mutation for an answer, see `check_vocab_answer`.
+pub fn check_answer_query(answer: &str, challenge: &Challenge, hints_used: &[HintKind], idempotency_key: &str) -> QueryBody<check_answer::Variables> {
+    CheckAnswer::build_query(check_answer::Variables {
+        vocab_id: challenge.vocab_id.into(),
+        vocab_study_id: challenge.vocab_study_id.into(),
+        entered: answer.to_string(),
+        hints_used: Some(hints_used.iter().map(|kind| kind.code().to_string()).collect()),
+        idempotency_key: Some(idempotency_key.to_string()),
+    })
+}
+
+/// The `CheckAnswer` mutation for an answer without the idempotency key, for backends that do not take it.
+pub fn check_answer_without_key_query(answer: &str, challenge: &Challenge, hints_used: &[HintKind]) -> QueryBody<check_answer_without_key::Variables> {
+    CheckAnswerWithoutKey::build_query(check_answer_without_key::Variables {
+        vocab_id: challenge.vocab_id.into(),
+        vocab_study_id: challenge.vocab_study_id.into(),
+        entered: answer.to_string(),
+        hints_used: Some(hints_used.iter().map(|kind| kind.code().to_string()).collect()),
+    })
+}
+
+/// A key identifying one submission of an answer, e.g. `42-18c2f3a1b40-9f3e01c2`, built from the vocab study,
+/// the time of the submission in milliseconds and a random number between 0 and 1.
+pub fn idempotency_key(vocab_study_id: i32, now_ms: f64, random: f64) -> String {
+    format!("{}-{:x}-{:08x}", vocab_study_id, now_ms.max(0.0) as u64, (random.clamp(0.0, 1.0) * u32::MAX as f64) as u32)
+}
+
+/// An answer submitted for checking.
+///
+/// # Fields
+///
+/// - `vocab_study_id`: The vocab study the answer is for.
+/// - `answer`: The submitted answer.
+/// - `idempotency_key`: The key sent with the check, so the backend records the submission only once.
+#[derive(PartialEq, Debug, Clone)]
+pub struct AnswerSubmission {
+    pub vocab_study_id: i32,
+    pub answer: String,
+    pub idempotency_key: String,
+}
+
+/// Guards the answer checks of a study session against double submissions.
+///
+/// While a check is in flight, further submissions are refused. When a check fails, submitting the same
+/// answer again reuses its idempotency key, as the failed check may have reached the backend.
+#[derive(Default, Debug)]
+pub struct CheckGuard {
+    in_flight: Option<AnswerSubmission>,
+    failed: Option<AnswerSubmission>,
+}
+
+impl CheckGuard {
+    /// Starts checking the answer to a challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge` - The challenge answered.
+    /// * `answer` - The submitted answer.
+    /// * `now_ms` - The time of the submission, which the new idempotency key is built from.
+    /// * `random` - A random number between 0 and 1, which the new idempotency key is built from.
+    ///
+    /// # Returns
+    ///
+    /// The submission to send, or `None` while another check is in flight. It reuses the idempotency key of a
+    /// failed check of the same answer.
+    pub fn submit(&mut self, challenge: &Challenge, answer: &str, now_ms: f64, random: f64) -> Option<AnswerSubmission> {
+        if self.in_flight.is_some() {
+            return None;
+        }
+
+        let vocab_study_id = challenge.vocab_study_id;
+        let submission = match self.failed.take() {
+            Some(failed) if failed.vocab_study_id == vocab_study_id && failed.answer == answer => failed,
+            _ => AnswerSubmission {
+                vocab_study_id,
+                answer: answer.to_string(),
+                idempotency_key: idempotency_key(vocab_study_id, now_ms, random),
+            },
+        };
+        self.in_flight = Some(submission.clone());
+        Some(submission)
+    }
+
+    /// Records that the check in flight returned a result.
+    pub fn finish(&mut self) {
+        self.in_flight = None;
+        self.failed = None;
+    }
+
+    /// Records that the check in flight failed, keeping its key for a retry.
+    pub fn fail(&mut self) {
+        self.failed = self.in_flight.take();
+    }
+
+    /// Forgets the check in flight and any failed one, e.g. when moving on to the next challenge.
+    pub fn reset(&mut self) {
+        *self = CheckGuard::default();
+    }
+
+    pub fn is_checking(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Whether the last check failed, so submitting the answer again retries it.
+    pub fn has_failed(&self) -> bool {
+        self.failed.is_some()
+    }
+}
+
+/// Performs a GraphQL query to check a user's answer against the correct vocabulary answer.
+///
+/// This function sends the `CheckAnswer` mutation to the GraphQL server with the user's answer and the
+/// associated vocabulary study information, returning the structured result. If the backend predates
+/// idempotency keys and rejects the `idempotencyKey` argument, the answer is checked again without it. If it
+/// predates `checkAnswer` and rejects it as an unknown field, the legacy `CheckResponse` mutation is sent
+/// instead and its feedback string is wrapped into a `CheckResult`.
+///
+/// ## Parameters:
+/// - `store`: The store whose cached statistics of the word and the learner are invalidated.
+/// - `awesome_id`: The learner who answered.
+/// - `answer`: The answer provided by the user.
+/// - `challenge`: A `Challenge` struct containing the `vocab_id`, `vocab_study_id`, and the prompt for the vocabulary challenge.
+/// - `hints_used`: The hints revealed before answering, one entry per reveal. Older backends do not receive them.
+/// - `idempotency_key`: Identifies the submission, so a repeated check is recorded only once. Older backends do
+///   not receive it, nor do backends rejecting the argument.
+///
+/// ## Returns:
+/// - A `Result` which is `Ok` containing the `CheckResult` if the query was successful, or a `FetchError` if there was an issue with the query.
+pub async fn check_vocab_answer(store: &Store, awesome_id: i32, answer: String, challenge: Challenge, hints_used: Vec<HintKind>, idempotency_key: String) -> Result<CheckResult, FetchError> {
+
+    let mut wrapper = post_check_answer(&check_answer_query(&answer, &challenge, &hints_used, &idempotency_key)).await?;
+
+    if wrapper.data.is_none() && wrapper.errors.iter().any(|error| error.is_unknown_argument("idempotencyKey")) {
+        wrapper = post_check_answer(&check_answer_without_key_query(&answer, &challenge, &hints_used)).await?;
+    }
+
+    if let Some(data) = wrapper.data {
+        invalidate_checked(store, awesome_id, &challenge);
+        return Ok(data.check_answer);
+    }
+
+    if wrapper.errors.iter().any(GqlError::is_unknown_field) {
+        return check_vocab_answer_legacy(store, awesome_id, answer, challenge).await;
+    }
+
+    Err(FetchError::from(wrapper.errors))
+}
+
+/// Sends a `checkAnswer` mutation, with or without the idempotency key.
+async fn post_check_answer<V: Serialize>(build_query: &QueryBody<V>) -> Result<CheckResultResponseWrapper, FetchError> {
+    // Serialize the query to a string
+    let query_string = serde_json::to_string(build_query)?;
+    let gql_json_res = post_gql_query(query_string).await?;
+    Ok(serde_json::from_str(&gql_json_res)?)
+}
+
+/// Marks the cached statistics of the challenge's word and of the learner who answered as stale once the
+/// check was recorded.
+fn invalidate_checked(store: &Store, awesome_id: i32, challenge: &Challenge) {
+    invalidate(store, &[CacheTag::VocabStudy(challenge.vocab_study_id), CacheTag::Learner(awesome_id)]);
+}
+
+/// Checks a user's answer with the `CheckResponse` mutation supported by older backends.
+///
+/// ## Returns:
+/// - A `Result` which is `Ok` containing a `CheckResult` holding only the server's response prompt, or a `FetchError`.
+async fn check_vocab_answer_legacy(store: &Store, awesome_id: i32, answer: String, challenge: Challenge) -> Result<CheckResult, FetchError> {
 
     let build_query = CheckResponse::build_query(check_response::Variables {
         vocab_id: challenge.vocab_id.into(),
         vocab_study_id: challenge.vocab_study_id.into(),
-        entered: answer.clone().into()
+        entered: answer
     });
 
     // Serialize the query to a string
     let query_string = serde_json::to_string(&build_query)?;
     let gql_json_res = post_gql_query(query_string).await?;
     let wrapper: CheckAnswerResponseWrapper = serde_json::from_str(&gql_json_res)?;
+    invalidate_checked(store, awesome_id, &challenge);
+
+    Ok(CheckResult::from(wrapper.data.response_prompt))
+}
+
+/// Response JSON wrapper
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateUserNotesResponseWrapper {
+    pub data: UpdatedNotes,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdatedNotes {
+    #[serde(rename = "updateUserNotes")]
+    pub user_notes: String,
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/update_notes.graphql",
+response_derives = "Debug"
+)]
+struct UpdateUserNotes;
+
+/// Saves the user's notes for a vocab study.
+///
+/// ## Parameters:
+/// - `store`: The store whose cached data of the vocab study is invalidated.
+/// - `vocab_study_id`: The identifier of the vocab study the notes belong to.
+/// - `user_notes`: The new notes, replacing any existing ones.
+///
+/// ## Returns:
+/// - A `Result` which is `Ok` containing the notes as saved by the server, or a `FetchError` if there was an issue with the query.
+pub async fn update_user_notes(store: &Store, vocab_study_id: i32, user_notes: String) -> Result<String, FetchError> {
+
+    let build_query = UpdateUserNotes::build_query(update_user_notes::Variables {
+        vocab_study_id: vocab_study_id.into(),
+        user_notes,
+    });
+
+    // Serialize the query to a string
+    let query_string = serde_json::to_string(&build_query)?;
+    let gql_json_res = post_gql_query(query_string).await?;
+    let wrapper: UpdateUserNotesResponseWrapper = serde_json::from_str(&gql_json_res)?;
+    invalidate(store, &[CacheTag::VocabStudy(vocab_study_id)]);
+
+    Ok(wrapper.data.user_notes)
+}
+
+/// The notes saves of a vocab study that are in flight.
+///
+/// # Fields
+///
+/// - `latest`: The sequence number of the latest save.
+/// - `saved`: The notes last known to be stored, restored when the latest save fails.
+#[derive(Debug)]
+struct PendingNotes {
+    latest: u64,
+    saved: String,
+}
+
+/// Keeps track of the notes saves in flight, so a late response cannot undo a newer edit.
+///
+/// Every save gets a sequence number. Only the outcome of the latest save of a vocab study is applied: an
+/// older save that succeeds only updates the notes a failed newer save rolls back to, and an older save that
+/// fails is ignored, since newer notes are already on their way.
+#[derive(Default, Debug)]
+pub struct NotesSaves {
+    next_seq: u64,
+    pending: HashMap<i32, PendingNotes>,
+}
+
+impl NotesSaves {
+    /// Starts saving notes, returning the sequence number to report the outcome with.
+    ///
+    /// # Arguments
+    ///
+    /// * `vocab_study_id` - The vocab study the notes belong to.
+    /// * `previous` - The notes before the edit, restored if the save fails and no other save is in flight.
+    pub fn begin(&mut self, vocab_study_id: i32, previous: &str) -> u64 {
+        self.next_seq += 1;
+        let seq = self.next_seq;
+        self.pending.entry(vocab_study_id)
+            .or_insert_with(|| PendingNotes { latest: seq, saved: previous.to_string() })
+            .latest = seq;
+        seq
+    }
+
+    /// Records a successful save, returning `true` when it is the latest one, so its notes should be shown.
+    pub fn succeed(&mut self, vocab_study_id: i32, seq: u64, saved: &str) -> bool {
+        match self.pending.get_mut(&vocab_study_id) {
+            Some(pending) if pending.latest == seq => {
+                self.pending.remove(&vocab_study_id);
+                true
+            }
+            Some(pending) => {
+                pending.saved = saved.to_string();
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Records a failed save, returning the notes to restore when it is the latest one.
+    pub fn fail(&mut self, vocab_study_id: i32, seq: u64) -> Option<String> {
+        match self.pending.get(&vocab_study_id) {
+            Some(pending) if pending.latest == seq => self.pending.remove(&vocab_study_id).map(|pending| pending.saved),
+            _ => None,
+        }
+    }
 
-    Ok(wrapper.data.response_prompt)
+    pub fn is_saving(&self, vocab_study_id: i32) -> bool {
+        self.pending.contains_key(&vocab_study_id)
+    }
 }
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// Local storage key the learner's `ThemeSettings` were kept under before they became part of the `Settings`.
+pub const THEME_STORAGE_KEY: &str = "theme_settings";
+
+/// Media query matching browsers set to prefer dark colours.
+pub const DARK_SCHEME_QUERY: &str = "(prefers-color-scheme: dark)";
+
+/// The colours of the application.
+///
+/// ## Variants:
+/// - `System`: Follows the browser's `prefers-color-scheme`, light or dark.
+/// - `Light`: Dark text on a light background.
+/// - `Dark`: Light text on a dark background.
+/// - `HighContrast`: White and yellow on black, for learners with low vision.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl Theme {
+    /// All the themes, in the order they are offered.
+    pub const ALL: [Theme; 4] = [Theme::System, Theme::Light, Theme::Dark, Theme::HighContrast];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "Same as my device",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::HighContrast => "High contrast",
+        }
+    }
+
+    /// The theme actually shown, resolving `System` with the browser's preference.
+    pub fn resolve(&self, prefers_dark: bool) -> Theme {
+        match self {
+            Theme::System if prefers_dark => Theme::Dark,
+            Theme::System => Theme::Light,
+            theme => *theme,
+        }
+    }
+
+    /// The value of the `data-theme` attribute selecting the theme's colours in `app.css`.
+    pub fn attribute(&self, prefers_dark: bool) -> &'static str {
+        match self.resolve(prefers_dark) {
+            Theme::Dark => "dark",
+            Theme::HighContrast => "high-contrast",
+            _ => "light",
+        }
+    }
+}
+
+/// The size of the text, relative to the browser's default.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum FontSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+    ExtraLarge,
+}
+
+impl FontSize {
+    /// All the sizes, smallest first.
+    pub const ALL: [FontSize; 4] = [FontSize::Small, FontSize::Medium, FontSize::Large, FontSize::ExtraLarge];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FontSize::Small => "Small",
+            FontSize::Medium => "Medium",
+            FontSize::Large => "Large",
+            FontSize::ExtraLarge => "Extra large",
+        }
+    }
+
+    /// The value of the `data-font-size` attribute selecting the size in `app.css`.
+    pub fn attribute(&self) -> &'static str {
+        match self {
+            FontSize::Small => "small",
+            FontSize::Medium => "medium",
+            FontSize::Large => "large",
+            FontSize::ExtraLarge => "extra-large",
+        }
+    }
+}
+
+/// How the learner wants the application to look, kept in local storage.
+///
+/// # Fields
+///
+/// - `theme`: The colours.
+/// - `font_size`: The size of the text.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct ThemeSettings {
+    pub theme: Theme,
+    pub font_size: FontSize,
+}
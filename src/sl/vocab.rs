@@ -0,0 +1,324 @@
+use futures::future::join_all;
+use graphql_client::{GraphQLQuery, QueryBody};
+use serde::{Deserialize, Serialize};
+use crate::sl::store::Store;
+use crate::sl::gql::{cached_query, peek_query, post_gql_query, CacheTag, Cached, FetchError, QueryKey};
+use crate::sl::study::{Challenge, VocabStats};
+
+/// Number of rows shown per page of the vocabulary browser.
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Number of words with the lowest accuracy listed on the profile page.
+pub const WEAKEST_WORDS: usize = 5;
+
+/// How long a fetched `VocabPage` is used before it is fetched again, in milliseconds.
+pub const VOCAB_PAGE_TTL_MS: f64 = 60_000.0;
+
+/// How long fetched `VocabStats` are used before they are fetched again, in milliseconds.
+pub const VOCAB_STATS_TTL_MS: f64 = 5.0 * 60_000.0;
+
+/// The language codes the app has translations for, with their English names.
+pub const LANGUAGES: [(&str, &str); 6] = [
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("de", "German"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+];
+
+/// Response JSON wrapper
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VocabPageResponseWrapper {
+    pub data: VocabPageData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VocabPageData {
+    #[serde(rename = "getVocabList")]
+    pub get_vocab_list: VocabPage,
+}
+
+/// Response JSON wrapper
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VocabStatsResponseWrapper {
+    pub data: VocabStatsData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VocabStatsData {
+    #[serde(rename = "getVocabStats")]
+    pub get_vocab_stats: VocabStats,
+}
+
+/// A row of the vocabulary browser: one vocabulary item the learner is studying, with their accuracy.
+///
+/// # Fields
+///
+/// - `vocab_id`, `vocab_study_id`: Identify the vocabulary and the learner's study of it.
+/// - `first_lang`: The vocabulary in the learner's known language.
+/// - `learning`: The vocabulary in the learning language.
+/// - `pos`: The part of speech.
+/// - `known_lang_code`, `learning_lang_code`: The language pair, e.g. `en` and `es`.
+/// - `attempts`, `correct_attempts`, `percentage_correct`: The learner's accuracy for the item.
+/// - `last_tested`: When the item was last answered, formatted by the backend for display.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct VocabEntry {
+    #[serde(rename = "vocabId")]
+    pub vocab_id: i32,
+
+    #[serde(rename = "vocabStudyId")]
+    pub vocab_study_id: i32,
+
+    #[serde(rename = "firstLang")]
+    pub first_lang: String,
+
+    pub learning: String,
+    pub pos: String,
+
+    #[serde(rename = "knownLangCode")]
+    pub known_lang_code: String,
+
+    #[serde(rename = "learningLangCode")]
+    pub learning_lang_code: String,
+
+    pub attempts: i32,
+
+    #[serde(rename = "correctAttempts")]
+    pub correct_attempts: i32,
+
+    #[serde(rename = "percentageCorrect")]
+    pub percentage_correct: f64,
+
+    #[serde(rename = "lastTested")]
+    pub last_tested: String,
+}
+
+/// A page of `VocabEntry` rows and the total number of rows matching the filter.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct VocabPage {
+    pub entries: Vec<VocabEntry>,
+    pub total: i32,
+}
+
+/// The columns the vocabulary browser can be sorted by.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum VocabSort {
+    #[default]
+    FirstLang,
+    Learning,
+    Pos,
+    PercentageCorrect,
+    LastTested,
+}
+
+/// Which vocabulary items to list. Every `None` field matches all items.
+///
+/// # Fields
+///
+/// - `pos`: Only items with this part of speech.
+/// - `known_lang_code`, `learning_lang_code`: Only items of this language pair.
+/// - `min_percentage`, `max_percentage`: Only items whose accuracy is within this range, from 0 to 100.
+/// - `not_tested_for_days`: Only items that have not been answered for at least this many days.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct VocabFilter {
+    pub pos: Option<String>,
+    pub known_lang_code: Option<String>,
+    pub learning_lang_code: Option<String>,
+    pub min_percentage: Option<f64>,
+    pub max_percentage: Option<f64>,
+    pub not_tested_for_days: Option<i32>,
+}
+
+/// The page, sort order and filter the vocabulary browser is showing.
+///
+/// # Fields
+///
+/// - `page`: The zero based page number.
+/// - `page_size`: The number of rows per page.
+/// - `sort`: The column rows are sorted by.
+/// - `descending`: Whether rows are sorted in descending order.
+/// - `filter`: Which items to list.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct VocabQuery {
+    pub page: usize,
+    pub page_size: usize,
+    pub sort: VocabSort,
+    pub descending: bool,
+    pub filter: VocabFilter,
+}
+
+impl Default for VocabQuery {
+    fn default() -> Self {
+        VocabQuery {
+            page: 0,
+            page_size: DEFAULT_PAGE_SIZE,
+            sort: VocabSort::default(),
+            descending: false,
+            filter: VocabFilter::default(),
+        }
+    }
+}
+
+impl VocabQuery {
+    /// The first `count` items with the lowest accuracy.
+    pub fn weakest(count: usize) -> Self {
+        VocabQuery { page_size: count, sort: VocabSort::PercentageCorrect, ..Default::default() }
+    }
+
+    /// The number of rows to skip for the current page.
+    pub fn offset(&self) -> usize {
+        self.page * self.page_size
+    }
+
+    /// The number of pages needed for `total` rows, at least one.
+    pub fn num_pages(&self, total: usize) -> usize {
+        total.div_ceil(self.page_size.max(1)).max(1)
+    }
+
+    /// Sorts by `sort`, flipping the direction when already sorted by it, and goes back to the first page.
+    pub fn sort_by(&mut self, sort: VocabSort) {
+        self.descending = self.sort == sort && !self.descending;
+        self.sort = sort;
+        self.page = 0;
+    }
+
+    /// Replaces the filter and goes back to the first page.
+    pub fn filter_by(&mut self, filter: VocabFilter) {
+        self.filter = filter;
+        self.page = 0;
+    }
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/vocab_list.graphql",
+response_derives = "Debug"
+)]
+struct VocabListPage;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+schema_path = "./graphql/schema.graphql",
+query_path = "./graphql/vocab_stats.graphql",
+response_derives = "Debug"
+)]
+struct VocabStatsQuery;
+
+impl From<VocabSort> for vocab_list_page::VocabSortField {
+    fn from(value: VocabSort) -> Self {
+        match value {
+            VocabSort::FirstLang => vocab_list_page::VocabSortField::FIRST_LANG,
+            VocabSort::Learning => vocab_list_page::VocabSortField::LEARNING,
+            VocabSort::Pos => vocab_list_page::VocabSortField::POS,
+            VocabSort::PercentageCorrect => vocab_list_page::VocabSortField::PERCENTAGE_CORRECT,
+            VocabSort::LastTested => vocab_list_page::VocabSortField::LAST_TESTED,
+        }
+    }
+}
+
+impl From<VocabFilter> for vocab_list_page::VocabFilter {
+    fn from(value: VocabFilter) -> Self {
+        vocab_list_page::VocabFilter {
+            pos: value.pos,
+            known_lang_code: value.known_lang_code,
+            learning_lang_code: value.learning_lang_code,
+            min_percentage: value.min_percentage,
+            max_percentage: value.max_percentage,
+            not_tested_for_days: value.not_tested_for_days.map(Into::into),
+        }
+    }
+}
+
+fn vocab_page_query(awesome_id: i32, query: VocabQuery) -> QueryBody<vocab_list_page::Variables> {
+    VocabListPage::build_query(vocab_list_page::Variables {
+        awesome_id: awesome_id.into(),
+        offset: (query.offset() as i32).into(),
+        limit: (query.page_size as i32).into(),
+        sort_by: query.sort.into(),
+        descending: query.descending,
+        filter: Some(query.filter.into()),
+    })
+}
+
+/// Fetches one page of the vocabulary an awesome person is studying.
+///
+/// Pages are cached for `VOCAB_PAGE_TTL_MS`, or until the learner answers or adds a word.
+///
+/// # Arguments
+///
+/// * `store` - The store caching the page.
+/// * `awesome_id` - The unique identifier of the user whose vocabulary is listed.
+/// * `query` - The page, sort order and filter to fetch.
+///
+/// # Returns
+///
+/// A `Result` wrapping the `VocabPage` on success, or a `FetchError` on failure.
+pub async fn fetch_vocab_page(store: &Store, awesome_id: i32, query: VocabQuery) -> Result<VocabPage, FetchError> {
+    let build_query = vocab_page_query(awesome_id, query);
+
+    cached_query(store, QueryKey::of(&build_query), VOCAB_PAGE_TTL_MS, vec![CacheTag::Learner(awesome_id)], || async move {
+        // Serialize the query to a string
+        let query_string = serde_json::to_string(&build_query)?;
+        let gql_json_res = post_gql_query(query_string).await?;
+        let wrapper: VocabPageResponseWrapper = serde_json::from_str(&gql_json_res)?;
+        Ok(wrapper.data.get_vocab_list)
+    }).await
+}
+
+/// The cached page of the vocabulary, fresh or stale, without fetching it.
+pub fn peek_vocab_page(store: &Store, awesome_id: i32, query: VocabQuery) -> Option<Cached<VocabPage>> {
+    peek_query(store, &QueryKey::of(&vocab_page_query(awesome_id, query)))
+}
+
+fn vocab_stats_query(vocab_study_id: i32) -> QueryBody<vocab_stats_query::Variables> {
+    VocabStatsQuery::build_query(vocab_stats_query::Variables {
+        vocab_study_id: vocab_study_id.into(),
+    })
+}
+
+/// Fetches the learner's statistics for a single vocab study.
+///
+/// The statistics are cached for `VOCAB_STATS_TTL_MS`, or until an answer for the vocab study is checked.
+///
+/// # Arguments
+///
+/// * `store` - The store caching the statistics.
+/// * `vocab_study_id` - The unique identifier of the vocab study.
+///
+/// # Returns
+///
+/// A `Result` wrapping the `VocabStats` on success, or a `FetchError` on failure.
+pub async fn fetch_vocab_stats(store: &Store, vocab_study_id: i32) -> Result<VocabStats, FetchError> {
+    let build_query = vocab_stats_query(vocab_study_id);
+
+    cached_query(store, QueryKey::of(&build_query), VOCAB_STATS_TTL_MS, vec![CacheTag::VocabStudy(vocab_study_id)], || async move {
+        // Serialize the query to a string
+        let query_string = serde_json::to_string(&build_query)?;
+        let gql_json_res = post_gql_query(query_string).await?;
+        let wrapper: VocabStatsResponseWrapper = serde_json::from_str(&gql_json_res)?;
+        Ok(wrapper.data.get_vocab_stats)
+    }).await
+}
+
+/// The cached statistics of a vocab study, fresh or stale, without fetching them.
+pub fn peek_vocab_stats(store: &Store, vocab_study_id: i32) -> Option<Cached<VocabStats>> {
+    peek_query(store, &QueryKey::of(&vocab_stats_query(vocab_study_id)))
+}
+
+/// Fills in the `learning` text of the challenges that lack it, which answer based hints are built from.
+///
+/// `getStudyList` does not return the answer, so it is read from each vocab study's statistics with a
+/// separate `getVocabStats` query. The queries are sent together, so a batch waits for the slowest of them
+/// rather than for all of them in turn. Challenges whose statistics cannot be fetched keep an empty
+/// `learning`, so their answer based hints are not offered.
+pub async fn fetch_learning_texts(store: &Store, challenges: &mut [Challenge]) {
+    let missing: Vec<&mut Challenge> = challenges.iter_mut().filter(|challenge| challenge.learning.is_empty()).collect();
+    let stats = join_all(missing.iter().map(|challenge| fetch_vocab_stats(store, challenge.vocab_study_id))).await;
+    for (challenge, stats) in missing.into_iter().zip(stats) {
+        if let Ok(stats) = stats {
+            challenge.learning = stats.learning;
+        }
+    }
+}
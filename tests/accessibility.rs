@@ -0,0 +1,75 @@
+#[cfg(test)]
+use wasm_bindgen_test::*;
+use web_sys::Element;
+use yew::platform::time::sleep;
+use yew::{Callback, Properties};
+use yew_study::components::answer_diff::{AnswerDiff, AnswerDiffProps};
+use yew_study::components::notes_editor::{NotesEditor, NotesEditorProps};
+use yew_study::pages::study::Study;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Creates a fresh element in the test page to mount a component into.
+fn test_root() -> Element {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+    root
+}
+
+/// Mounts a component with its props and waits for it to render.
+async fn render<C>(props: C::Properties) -> Element
+where
+    C: yew::BaseComponent,
+    C::Properties: Properties,
+{
+    let root = test_root();
+    yew::Renderer::<C>::with_root_and_props(root.clone(), props).render();
+    // Yields to the scheduler so the component renders before it is inspected
+    sleep(Default::default()).await;
+    root
+}
+
+#[wasm_bindgen_test]
+async fn study_announces_outcomes_in_a_live_region() {
+    let root = render::<Study>(()).await;
+
+    let status = root.query_selector("[role=status]").unwrap().unwrap();
+    assert_eq!(status.get_attribute("aria-live").as_deref(), Some("polite"));
+    assert_eq!(status.get_attribute("aria-atomic").as_deref(), Some("true"));
+}
+
+#[wasm_bindgen_test]
+async fn study_answer_box_is_labelled_and_described() {
+    let root = render::<Study>(()).await;
+
+    assert!(root.query_selector("label[for=challenge_taken]").unwrap().is_some());
+    let input = root.query_selector("#challenge_taken").unwrap().unwrap();
+    let described_by = input.get_attribute("aria-describedby").unwrap_or_default();
+    assert!(described_by.split(' ').any(|id| id == "challenge_prompt"));
+    assert!(root.query_selector("#challenge_prompt").unwrap().is_some());
+}
+
+#[wasm_bindgen_test]
+async fn answer_diff_reads_out_the_expected_answer() {
+    let root = render::<AnswerDiff>(AnswerDiffProps { entered: "cama".to_string(), expected: "casa".to_string() }).await;
+
+    let diff = root.query_selector(".answer-diff").unwrap().unwrap();
+    assert_eq!(diff.get_attribute("aria-hidden").as_deref(), Some("true"));
+    let spoken = root.query_selector(".visually-hidden").unwrap().unwrap();
+    assert_eq!(spoken.text_content().as_deref(), Some("The expected answer is casa"));
+}
+
+#[wasm_bindgen_test]
+async fn notes_errors_are_alerts() {
+    let props = NotesEditorProps {
+        notes: "".to_string(),
+        saving: false,
+        error: Some("offline".to_string()),
+        on_save: Callback::noop(),
+    };
+    let root = render::<NotesEditor>(props).await;
+
+    let error = root.query_selector(".error").unwrap().unwrap();
+    assert_eq!(error.get_attribute("role").as_deref(), Some("alert"));
+}
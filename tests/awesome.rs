@@ -0,0 +1,18 @@
+use yew_study::sl::awesome::{AwesomeProfile, AwesomeProfileResponseWrapper};
+
+#[test]
+fn profile_is_read_from_the_response() {
+    let json = r#"{"data":{"getAwesomePerson":{"id":3,"numKnown":120,"numCorrect":340,"numIncorrect":60,
+        "totalPercentage":85.0,"name":"Ana","smallestVocab":2}}}"#;
+
+    let response: AwesomeProfileResponseWrapper = serde_json::from_str(json).unwrap();
+    assert_eq!(response.data.get_awesome_person, AwesomeProfile {
+        id: 3,
+        num_known: 120,
+        num_correct: 340,
+        num_incorrect: 60,
+        total_percentage: 85.0,
+        name: "Ana".to_string(),
+        smallest_vocab: 2,
+    });
+}
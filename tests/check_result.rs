@@ -0,0 +1,137 @@
+use yew_study::sl::gql::GqlError;
+use yew_study::sl::hints::HintKind;
+use yew_study::sl::study::{check_answer_query, check_answer_without_key_query, idempotency_key, Challenge, CheckGuard, CheckResult,
+    CheckResultResponseWrapper};
+
+fn challenge(vocab_study_id: i32) -> Challenge {
+    Challenge { vocab_id: 3, vocab_study_id, ..Default::default() }
+}
+
+#[test]
+fn structured_check_result_deserializes() {
+    let json = r#"{"data":{"checkAnswer":{"correct":false,"expected":"café","distance":1,"score":0.75,
+        "prompt":"Almost!","stats":{"learning":"café","attempts":4,"correctAttempts":3,
+        "percentageCorrect":75.0,"lastChange":-25.0,"lastTested":"2024-03-20 10:00"}}}}"#;
+
+    let wrapper: CheckResultResponseWrapper = serde_json::from_str(json).unwrap();
+    let result = wrapper.data.unwrap().check_answer;
+
+    assert_eq!(result.correct, Some(false));
+    assert_eq!(result.expected, Some("café".to_string()));
+    assert_eq!(result.distance, Some(1));
+    assert_eq!(result.feedback, "Almost!");
+    assert_eq!(result.stats.unwrap().correct_attempts, 3);
+    assert!(wrapper.errors.is_empty());
+}
+
+#[test]
+fn legacy_feedback_string_becomes_check_result() {
+    let result = CheckResult::from("Correct!".to_string());
+
+    assert_eq!(result.feedback, "Correct!");
+    assert_eq!(result.correct, None);
+    assert_eq!(result.expected, None);
+    assert_eq!(result.stats, None);
+}
+
+#[test]
+fn unknown_field_error_is_detected() {
+    let json = r#"{"data":null,"errors":[{"message":"Unknown field \"checkAnswer\" on type \"MutationRoot\".","locations":[{"line":2,"column":5}]}]}"#;
+
+    let wrapper: CheckResultResponseWrapper = serde_json::from_str(json).unwrap();
+
+    assert!(wrapper.data.is_none());
+    assert!(wrapper.errors.iter().any(GqlError::is_unknown_field));
+}
+
+#[test]
+fn other_errors_are_not_unknown_fields() {
+    let error = GqlError { message: "Vocab study not found".to_string() };
+    assert!(!error.is_unknown_field());
+    assert!(!error.is_unknown_argument("idempotencyKey"));
+}
+
+#[test]
+fn unknown_idempotency_key_argument_is_detected() {
+    let json = r#"{"data":null,"errors":[{"message":"Unknown argument \"idempotencyKey\" on field \"checkAnswer\" of type \"MutationRoot\".","locations":[{"line":2,"column":5}]}]}"#;
+
+    let wrapper: CheckResultResponseWrapper = serde_json::from_str(json).unwrap();
+
+    assert!(wrapper.errors.iter().any(|error| error.is_unknown_argument("idempotencyKey")));
+    assert!(!wrapper.errors.iter().any(|error| error.is_unknown_argument("hintsUsed")));
+    assert!(!wrapper.errors.iter().any(GqlError::is_unknown_field));
+}
+
+#[test]
+fn double_submission_sends_one_check() {
+    let mut guard = CheckGuard::default();
+
+    // Enter pressed twice, then the button clicked, before the first check returns
+    let first = guard.submit(&challenge(7), "hola", 1000.0, 0.1).unwrap();
+    assert_eq!(guard.submit(&challenge(7), "hola", 1001.0, 0.2), None);
+    assert_eq!(guard.submit(&challenge(7), "hola", 1002.0, 0.3), None);
+    assert!(guard.is_checking());
+
+    guard.finish();
+    assert!(!guard.is_checking());
+    let second = guard.submit(&challenge(8), "adiós", 2000.0, 0.4).unwrap();
+    assert_eq!(first.idempotency_key, idempotency_key(7, 1000.0, 0.1));
+    assert_eq!(second.idempotency_key, idempotency_key(8, 2000.0, 0.4));
+}
+
+#[test]
+fn retry_after_failure_reuses_key() {
+    let mut guard = CheckGuard::default();
+
+    let first = guard.submit(&challenge(7), "hola", 1000.0, 0.1).unwrap();
+    guard.fail();
+    assert!(!guard.is_checking());
+    assert!(guard.has_failed());
+    let retry = guard.submit(&challenge(7), "hola", 5000.0, 0.9).unwrap();
+
+    assert_eq!(retry, first);
+
+    // A different answer is a new submission
+    guard.fail();
+    let changed = guard.submit(&challenge(7), "ola", 6000.0, 0.9).unwrap();
+    assert_ne!(changed.idempotency_key, first.idempotency_key);
+}
+
+#[test]
+fn reset_forgets_failed_check() {
+    let mut guard = CheckGuard::default();
+
+    let first = guard.submit(&challenge(7), "hola", 1000.0, 0.1).unwrap();
+    guard.fail();
+    guard.reset();
+    let second = guard.submit(&challenge(7), "hola", 2000.0, 0.1).unwrap();
+
+    assert_ne!(first.idempotency_key, second.idempotency_key);
+}
+
+#[test]
+fn idempotency_keys_identify_submissions() {
+    assert_eq!(idempotency_key(42, 255.0, 0.0), "42-ff-00000000");
+    assert_eq!(idempotency_key(42, 255.0, 1.0), "42-ff-ffffffff");
+    assert_ne!(idempotency_key(42, 1000.0, 0.25), idempotency_key(42, 1000.0, 0.5));
+}
+
+#[test]
+fn check_answer_mutation_sends_idempotency_key() {
+    let query = check_answer_query("hola", &challenge(7), &[HintKind::FirstLetter], "7-ff-00000001");
+    let json = serde_json::to_value(&query).unwrap();
+
+    assert_eq!(json["variables"]["idempotencyKey"], "7-ff-00000001");
+    assert_eq!(json["variables"]["vocabStudyId"], 7);
+    assert_eq!(json["variables"]["entered"], "hola");
+}
+
+#[test]
+fn check_answer_without_key_leaves_the_argument_out() {
+    let query = check_answer_without_key_query("hola", &challenge(7), &[HintKind::FirstLetter]);
+    let json = serde_json::to_value(&query).unwrap();
+
+    assert!(!query.query.contains("idempotencyKey"));
+    assert!(json["variables"].get("idempotencyKey").is_none());
+    assert_eq!(json["variables"]["entered"], "hola");
+}
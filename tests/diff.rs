@@ -0,0 +1,78 @@
+use yew_study::sl::diff::{diff_answer, is_exact, DiffKind, DiffSegment};
+
+#[test]
+fn exact_answer_is_all_equal() {
+    let segments = diff_answer("hola", "hola");
+    assert_eq!(segments, vec![DiffSegment::new(DiffKind::Equal, "hola")]);
+    assert!(is_exact(&segments));
+}
+
+#[test]
+fn case_and_surrounding_whitespace_are_ignored() {
+    let segments = diff_answer("  Hola ", "hola");
+    assert_eq!(segments, vec![DiffSegment::new(DiffKind::Equal, "hola")]);
+}
+
+#[test]
+fn missing_accent_is_reported_as_accent() {
+    let segments = diff_answer("cafe", "café");
+    assert_eq!(segments, vec![
+        DiffSegment::new(DiffKind::Equal, "caf"),
+        DiffSegment::new(DiffKind::Accent, "é"),
+    ]);
+    assert!(!is_exact(&segments));
+}
+
+#[test]
+fn wrong_accent_is_reported_as_accent() {
+    let segments = diff_answer("està", "está");
+    assert_eq!(segments, vec![
+        DiffSegment::new(DiffKind::Equal, "est"),
+        DiffSegment::new(DiffKind::Accent, "á"),
+    ]);
+}
+
+#[test]
+fn missing_letters_are_inserts() {
+    let segments = diff_answer("gato", "gatos");
+    assert_eq!(segments, vec![
+        DiffSegment::new(DiffKind::Equal, "gato"),
+        DiffSegment::new(DiffKind::Insert, "s"),
+    ]);
+}
+
+#[test]
+fn extra_letters_are_deletes() {
+    let segments = diff_answer("perrro", "perro");
+    let deleted: String = segments.iter()
+        .filter(|s| s.kind == DiffKind::Delete)
+        .map(|s| s.text.clone())
+        .collect();
+    assert_eq!(deleted, "r");
+    assert!(!segments.iter().any(|s| s.kind == DiffKind::Insert));
+}
+
+#[test]
+fn substituted_letter_is_delete_then_insert() {
+    let segments = diff_answer("casa", "cama");
+    assert_eq!(segments, vec![
+        DiffSegment::new(DiffKind::Equal, "ca"),
+        DiffSegment::new(DiffKind::Delete, "s"),
+        DiffSegment::new(DiffKind::Insert, "m"),
+        DiffSegment::new(DiffKind::Equal, "a"),
+    ]);
+}
+
+#[test]
+fn empty_answer_is_all_insert() {
+    let segments = diff_answer("", "niño");
+    assert_eq!(segments, vec![DiffSegment::new(DiffKind::Insert, "niño")]);
+}
+
+#[test]
+fn segments_map_to_css_classes() {
+    assert_eq!(DiffKind::Equal.css_class(), "diff-equal");
+    assert_eq!(DiffKind::Insert.css_class(), "diff-insert");
+    assert_eq!(DiffKind::Delete.css_class(), "diff-delete");
+    assert_eq!(DiffKind::Accent.css_class(), "diff-accent");
+}
@@ -0,0 +1,59 @@
+use yew_study::sl::export::{export_csv, export_json, ExportFormat, ExportRecord, CSV_COLUMNS};
+use yew_study::sl::vocab::VocabEntry;
+
+fn entry(first_lang: &str, learning: &str) -> VocabEntry {
+    VocabEntry {
+        vocab_id: 3,
+        vocab_study_id: 7,
+        first_lang: first_lang.to_string(),
+        learning: learning.to_string(),
+        pos: "noun".to_string(),
+        known_lang_code: "en".to_string(),
+        learning_lang_code: "es".to_string(),
+        attempts: 4,
+        correct_attempts: 3,
+        percentage_correct: 75.0,
+        last_tested: "2024-05-01".to_string(),
+    }
+}
+
+#[test]
+fn csv_has_a_header_and_a_row_per_entry() {
+    let csv = export_csv(&[entry("bed", "la cama")]);
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+
+    assert_eq!(lines[0], CSV_COLUMNS.join(","));
+    assert_eq!(lines[1], "3,7,bed,la cama,noun,en,es,4,3,75.0,2024-05-01");
+    assert_eq!(lines[2], "");
+}
+
+#[test]
+fn csv_quotes_fields_that_need_it() {
+    let csv = export_csv(&[entry("to say \"hi\", loudly", "gritar\nhola")]);
+
+    assert!(csv.contains("\"to say \"\"hi\"\", loudly\",\"gritar\nhola\""));
+}
+
+#[test]
+fn csv_escapes_formulas() {
+    let csv = export_csv(&[entry("=HYPERLINK(\"http://example.com\")", "@SUM(A1)")]);
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+
+    assert!(lines[1].starts_with("3,7,\"'=HYPERLINK(\"\"http://example.com\"\")\",'@SUM(A1),"));
+    assert!(export_csv(&[entry("-ito", "+1")]).contains(",'-ito,'+1,"));
+}
+
+#[test]
+fn json_round_trips_the_records() {
+    let json = export_json(&[entry("bed", "la cama")]).unwrap();
+    let records: Vec<ExportRecord> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(records, vec![ExportRecord::from(&entry("bed", "la cama"))]);
+    assert!(json.contains("\"percentage_correct\": 75.0"));
+}
+
+#[test]
+fn file_names_use_the_date_and_extension() {
+    assert_eq!(ExportFormat::Csv.file_name("2024-05-01"), "vocabulary-2024-05-01.csv");
+    assert_eq!(ExportFormat::Json.file_name("2024-05-01"), "vocabulary-2024-05-01.json");
+}
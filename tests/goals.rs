@@ -0,0 +1,96 @@
+use yew_study::sl::goals::{current_streak, day_display, day_end_ms, day_number, heat_level, heatmap, longest_streak, record_answer, weekday,
+                           ActivityLog, DailyGoal, DayActivity, MAX_ANSWER_MS};
+
+fn items(items: u32) -> DayActivity {
+    DayActivity { items, elapsed_ms: 0.0 }
+}
+
+fn log(days: &[(i64, u32)]) -> ActivityLog {
+    days.iter().map(|&(day, count)| (day, items(count))).collect()
+}
+
+#[test]
+fn days_follow_the_local_time_zone() {
+    // 2024-05-01T23:30:00Z
+    let ms = 1_714_606_200_000.0;
+    assert_eq!(day_display(day_number(ms, 0.0)), "2024-05-01");
+    // UTC+2 reports an offset of -120 minutes
+    assert_eq!(day_display(day_number(ms, -120.0)), "2024-05-02");
+    assert_eq!(day_display(day_number(ms, 300.0)), "2024-05-01");
+}
+
+#[test]
+fn day_numbers_format_as_dates_and_weekdays() {
+    assert_eq!(day_display(0), "1970-01-01");
+    assert_eq!(day_display(19_783), "2024-03-01");
+    assert_eq!(day_display(-1), "1969-12-31");
+    assert_eq!(weekday(0), 4);
+    assert_eq!(weekday(19_783), 5);
+}
+
+#[test]
+fn answers_add_up_per_day_with_capped_time() {
+    let mut log = ActivityLog::new();
+    record_answer(&mut log, 5, 30_000.0);
+    record_answer(&mut log, 5, 10.0 * MAX_ANSWER_MS);
+
+    assert_eq!(log.get(&5), Some(&DayActivity { items: 2, elapsed_ms: 30_000.0 + MAX_ANSWER_MS }));
+}
+
+#[test]
+fn goals_measure_items_or_minutes() {
+    let activity = DayActivity { items: 5, elapsed_ms: 6.0 * 60_000.0 };
+
+    assert!((DailyGoal::Items(10).progress(&activity) - 0.5).abs() < 1e-9);
+    assert!(DailyGoal::Minutes(5).is_met(&activity));
+    assert!(!DailyGoal::Items(10).is_met(&activity));
+}
+
+#[test]
+fn streak_survives_until_the_day_is_over() {
+    let goal = DailyGoal::Items(10);
+    let log = log(&[(7, 10), (8, 12), (9, 10), (10, 3)]);
+
+    assert_eq!(current_streak(&log, &goal, 10), 3);
+    assert_eq!(current_streak(&log, &goal, 9), 3);
+    assert_eq!(current_streak(&log, &goal, 11), 0);
+}
+
+#[test]
+fn longest_streak_needs_consecutive_days() {
+    let goal = DailyGoal::Items(10);
+    let log = log(&[(1, 10), (2, 10), (4, 10), (5, 10), (6, 10), (7, 2), (8, 10)]);
+
+    assert_eq!(longest_streak(&log, &goal), 3);
+    assert_eq!(longest_streak(&ActivityLog::new(), &goal), 0);
+}
+
+#[test]
+fn heat_levels_follow_the_goal() {
+    let goal = DailyGoal::Items(10);
+    assert_eq!([0, 2, 7, 10, 25].map(|count| heat_level(&goal, &items(count))), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn heatmap_has_sunday_based_weeks_ending_today() {
+    // 2024-03-01 was a Friday
+    let today = 19_783;
+    let map = heatmap(&log(&[(today, 10)]), &DailyGoal::Items(10), today, 3);
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[0].len(), 7);
+    assert_eq!(weekday(map[0][0].day), 0);
+    assert_eq!(map[2].len(), 6);
+    assert_eq!(map[2].last().map(|cell| (cell.day, cell.level)), Some((today, 3)));
+}
+
+#[test]
+fn days_end_at_local_midnight() {
+    // UTC-5, as returned by getTimezoneOffset
+    let offset = 300.0;
+    let day = day_number(1_700_000_000_000.0, offset);
+    let end = day_end_ms(day, offset);
+
+    assert_eq!(day_number(end - 1.0, offset), day);
+    assert_eq!(day_number(end, offset), day + 1);
+}
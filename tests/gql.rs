@@ -0,0 +1,113 @@
+use graphql_client::QueryBody;
+use serde_json::json;
+use yew_study::sl::gql::{CacheTag, Cached, ErrorKind, GqlError, QueryCache, QueryKey, MAX_CACHE_ENTRIES};
+
+const NOW: f64 = 1_700_000_000_000.0;
+const TTL: f64 = 60_000.0;
+
+fn key(operation: &'static str, id: i32) -> QueryKey {
+    QueryKey::of(&QueryBody { variables: json!({ "id": id }), query: "", operation_name: operation })
+}
+
+#[test]
+fn keys_differ_by_operation_and_variables() {
+    assert_eq!(key("AwesomePersonQuery", 1), key("AwesomePersonQuery", 1));
+    assert_ne!(key("AwesomePersonQuery", 1), key("AwesomePersonQuery", 2));
+    assert_ne!(key("AwesomePersonQuery", 1), key("VocabStatsQuery", 1));
+}
+
+#[test]
+fn results_go_stale_after_their_ttl() {
+    let mut cache = QueryCache::default();
+    cache.insert(key("Op", 1), &"hola".to_string(), TTL, vec![], NOW);
+
+    assert_eq!(cache.get::<String>(&key("Op", 1), NOW + TTL - 1.0), Some(Cached { value: "hola".to_string(), stale: false }));
+    assert_eq!(cache.get::<String>(&key("Op", 1), NOW + TTL), Some(Cached { value: "hola".to_string(), stale: true }));
+    assert_eq!(cache.get::<String>(&key("Op", 2), NOW), None);
+}
+
+#[test]
+fn results_of_another_type_are_ignored() {
+    let mut cache = QueryCache::default();
+    cache.insert(key("Op", 1), &"hola".to_string(), TTL, vec![], NOW);
+
+    assert_eq!(cache.get::<i32>(&key("Op", 1), NOW), None);
+}
+
+#[test]
+fn invalidation_marks_only_tagged_results_stale() {
+    let mut cache = QueryCache::default();
+    cache.insert(key("Stats", 5), &1, TTL, vec![CacheTag::VocabStudy(5)], NOW);
+    cache.insert(key("Stats", 6), &2, TTL, vec![CacheTag::VocabStudy(6)], NOW);
+    cache.insert(key("Profile", 1), &3, TTL, vec![CacheTag::Learner(1)], NOW);
+
+    cache.invalidate(&[CacheTag::VocabStudy(5), CacheTag::Learner(1)]);
+
+    assert_eq!(cache.get::<i32>(&key("Stats", 5), NOW).map(|cached| cached.stale), Some(true));
+    assert_eq!(cache.get::<i32>(&key("Stats", 6), NOW).map(|cached| cached.stale), Some(false));
+    assert_eq!(cache.get::<i32>(&key("Profile", 1), NOW).map(|cached| cached.stale), Some(true));
+
+    cache.insert(key("Stats", 5), &4, TTL, vec![CacheTag::VocabStudy(5)], NOW);
+    assert_eq!(cache.get::<i32>(&key("Stats", 5), NOW), Some(Cached { value: 4, stale: false }));
+}
+
+#[test]
+fn the_oldest_result_is_dropped_when_full() {
+    let mut cache = QueryCache::default();
+    for id in 0..MAX_CACHE_ENTRIES as i32 {
+        cache.insert(key("Op", id), &id, TTL, vec![], NOW + id as f64);
+    }
+    cache.insert(key("Op", 0), &0, TTL, vec![], NOW + 1_000.0);
+    assert_eq!(cache.len(), MAX_CACHE_ENTRIES);
+
+    cache.insert(key("Op", -1), &-1, TTL, vec![], NOW + 2_000.0);
+    assert_eq!(cache.len(), MAX_CACHE_ENTRIES);
+    assert_eq!(cache.get::<i32>(&key("Op", 1), NOW), None);
+    assert!(cache.get::<i32>(&key("Op", 0), NOW).is_some());
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn unreachable_backend_is_told_apart_from_being_offline() {
+    assert_eq!(ErrorKind::unreachable(true), ErrorKind::BackendDown);
+    assert_eq!(ErrorKind::unreachable(false), ErrorKind::Offline);
+}
+
+#[test]
+fn failed_statuses_are_classified() {
+    assert_eq!(ErrorKind::of_status(401), Some(ErrorKind::Unauthorized));
+    assert_eq!(ErrorKind::of_status(403), Some(ErrorKind::Unauthorized));
+    assert_eq!(ErrorKind::of_status(502), Some(ErrorKind::BackendDown));
+    // GraphQL servers report validation errors in the body of these
+    assert_eq!(ErrorKind::of_status(200), None);
+    assert_eq!(ErrorKind::of_status(400), None);
+}
+
+#[test]
+fn unauthorized_gql_errors_are_detected() {
+    let error = |message: &str| GqlError { message: message.to_string() };
+    assert!(error("Unauthorized").is_unauthorized());
+    assert!(error("Learner 7 is not authorized to read vocab 3").is_unauthorized());
+    assert!(!error("Unknown field \"checkAnswer\" on type \"MutationRoot\"").is_unauthorized());
+}
+
+#[test]
+fn only_failures_a_new_request_can_fix_are_retryable() {
+    assert!(ErrorKind::Offline.is_retryable());
+    assert!(ErrorKind::BackendDown.is_retryable());
+    assert!(ErrorKind::Other.is_retryable());
+    assert!(!ErrorKind::Unauthorized.is_retryable());
+    assert!(!ErrorKind::Validation.is_retryable());
+    assert!(!ErrorKind::Decode.is_retryable());
+}
+
+#[test]
+fn every_error_kind_has_a_message() {
+    let kinds = [ErrorKind::Offline, ErrorKind::BackendDown, ErrorKind::Unauthorized, ErrorKind::Validation, ErrorKind::Decode, ErrorKind::Other];
+    for kind in kinds {
+        assert!(!kind.title().is_empty());
+        assert!(kind.advice().ends_with('.'));
+    }
+}
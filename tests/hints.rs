@@ -0,0 +1,90 @@
+use yew_study::components::prompt::displayed_hints;
+use yew_study::sl::hints::{build_hints, hint_penalty, hinted_score, Hint, HintKind, DEFAULT_HINT_ORDER};
+use yew_study::sl::study::Challenge;
+
+fn challenge() -> Challenge {
+    Challenge {
+        pos: "noun".to_string(),
+        infinitive: "".to_string(),
+        hint: "feminine".to_string(),
+        user_notes: "like casa".to_string(),
+        learning: "cama".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn hints_follow_the_default_order() {
+    let kinds: Vec<HintKind> = build_hints(&challenge(), &DEFAULT_HINT_ORDER).iter().map(|h| h.kind).collect();
+    assert_eq!(kinds, vec![
+        HintKind::PartOfSpeech,
+        HintKind::WordLength,
+        HintKind::OtherHints,
+        HintKind::UserNotes,
+        HintKind::FirstLetter,
+        HintKind::MaskedLetters,
+        HintKind::MaskedLetters,
+        HintKind::MaskedLetters,
+        HintKind::MaskedLetters,
+    ]);
+}
+
+#[test]
+fn hints_follow_a_custom_order() {
+    let order = [HintKind::UserNotes, HintKind::PartOfSpeech];
+    assert_eq!(build_hints(&challenge(), &order), vec![
+        Hint::new(HintKind::UserNotes, "like casa"),
+        Hint::new(HintKind::PartOfSpeech, "noun"),
+    ]);
+}
+
+#[test]
+fn letter_hints_need_the_learning_text() {
+    let challenge = Challenge { learning: "".to_string(), ..challenge() };
+    let order = [HintKind::WordLength, HintKind::FirstLetter, HintKind::MaskedLetters];
+    assert!(build_hints(&challenge, &order).is_empty());
+}
+
+#[test]
+fn word_length_counts_each_word() {
+    let challenge = Challenge { learning: "el niño".to_string(), ..challenge() };
+    assert_eq!(build_hints(&challenge, &[HintKind::WordLength]), vec![Hint::new(HintKind::WordLength, "2, 4")]);
+}
+
+#[test]
+fn masked_letters_reveal_progressively() {
+    let challenge = Challenge { learning: "la casa".to_string(), ..challenge() };
+    let masks: Vec<String> = build_hints(&challenge, &[HintKind::MaskedLetters]).into_iter().map(|h| h.text).collect();
+    assert_eq!(masks, vec!["__ ____", "la ____", "la ca__"]);
+}
+
+#[test]
+fn hint_display_includes_label() {
+    assert_eq!(Hint::new(HintKind::FirstLetter, "c").display(), "Starts with: c");
+}
+
+#[test]
+fn hint_penalty_is_capped() {
+    assert_eq!(hint_penalty(&[]), 0.0);
+    assert_eq!(hint_penalty(&[HintKind::MaskedLetters; 20]), 1.0);
+    assert_eq!(hinted_score(1.0, &[HintKind::MaskedLetters; 20]), 0.0);
+    assert!((hinted_score(0.8, &[HintKind::PartOfSpeech]) - 0.76).abs() < 1e-9);
+}
+
+#[test]
+fn displayed_hints_keep_only_the_latest_mask() {
+    let challenge = Challenge { learning: "la casa".to_string(), ..challenge() };
+    let hints = build_hints(&challenge, &[HintKind::PartOfSpeech, HintKind::MaskedLetters]);
+
+    assert_eq!(displayed_hints(&hints, 0), vec![]);
+    assert_eq!(displayed_hints(&hints, 3), vec![
+        Hint::new(HintKind::PartOfSpeech, "noun"),
+        Hint::new(HintKind::MaskedLetters, "la ____"),
+    ]);
+}
+
+#[test]
+fn only_answer_based_hints_need_the_learning_text() {
+    let needing: Vec<HintKind> = DEFAULT_HINT_ORDER.iter().copied().filter(HintKind::needs_learning).collect();
+    assert_eq!(needing, vec![HintKind::WordLength, HintKind::FirstLetter, HintKind::MaskedLetters]);
+}
@@ -0,0 +1,109 @@
+use yew_study::sl::import::{map_rows, parse_delimited, parse_import, valid_batches, ImportColumn, ImportError, ImportFormat, IMPORT_BATCH_SIZE};
+use yew_study::sl::new_vocab::VocabField;
+
+fn cells(text: &str, separator: char) -> Vec<Vec<String>> {
+    parse_delimited(text, separator).into_iter().map(|row| row.cells).collect()
+}
+
+#[test]
+fn format_follows_the_extension() {
+    assert_eq!(ImportFormat::from_file_name("words.CSV"), Ok(ImportFormat::Csv));
+    assert_eq!(ImportFormat::from_file_name("words.tsv"), Ok(ImportFormat::Tsv));
+    assert_eq!(ImportFormat::from_file_name("deck.txt"), Ok(ImportFormat::Anki));
+    assert_eq!(ImportFormat::from_file_name("deck.apkg"), Err(ImportError::AnkiPackage));
+    assert_eq!(ImportFormat::from_file_name("words.xlsx"), Err(ImportError::UnsupportedFile("words.xlsx".to_string())));
+}
+
+#[test]
+fn quoted_fields_hold_separators_quotes_and_line_breaks() {
+    let rows = cells("bed,\"la cama, grande\"\r\n\"say \"\"hi\"\"\",\"two\nlines\"\n", ',');
+    assert_eq!(rows, vec![
+        vec!["bed".to_string(), "la cama, grande".to_string()],
+        vec!["say \"hi\"".to_string(), "two\nlines".to_string()],
+    ]);
+}
+
+#[test]
+fn blank_lines_are_skipped_and_line_numbers_kept() {
+    let rows = parse_delimited("bed\tla cama\n\n\t\nhouse\tla casa", '\t');
+    assert_eq!(rows.iter().map(|row| row.line).collect::<Vec<_>>(), vec![1, 4]);
+}
+
+#[test]
+fn header_row_is_detected_and_mapped() {
+    let table = parse_import("words.csv", "Word,Notes,Translation\nbed,furniture,la cama\n").unwrap();
+
+    assert_eq!(table.header, Some(vec!["Word".to_string(), "Notes".to_string(), "Translation".to_string()]));
+    assert_eq!(table.rows.len(), 1);
+    assert_eq!(table.guess_mapping(), vec![ImportColumn::FirstLang, ImportColumn::Hint, ImportColumn::Learning]);
+}
+
+#[test]
+fn files_without_header_use_the_default_order() {
+    let table = parse_import("words.tsv", "bed\tla cama\tnoun\t\t\textra\n").unwrap();
+
+    assert_eq!(table.header, None);
+    assert_eq!(table.guess_mapping(), vec![
+        ImportColumn::FirstLang, ImportColumn::Learning, ImportColumn::Pos,
+        ImportColumn::Infinitive, ImportColumn::Hint, ImportColumn::Ignore,
+    ]);
+}
+
+#[test]
+fn anki_headers_are_applied() {
+    let text = "#separator:semicolon\n#html:true\n#columns:Front;Back\nbed;\"<b>la&nbsp;cama</b>\"\n";
+    let table = parse_import("deck.txt", text).unwrap();
+
+    assert_eq!(table.header, Some(vec!["Front".to_string(), "Back".to_string()]));
+    assert_eq!(table.rows[0].line, 4);
+    assert_eq!(table.rows[0].cells, vec!["bed".to_string(), "la cama".to_string()]);
+}
+
+#[test]
+fn empty_files_are_rejected() {
+    assert_eq!(parse_import("words.csv", "\u{feff}\n\n"), Err(ImportError::Empty));
+    assert_eq!(parse_import("words.csv", "word,translation\n"), Err(ImportError::Empty));
+}
+
+#[test]
+fn rows_are_mapped_and_validated() {
+    let table = parse_import("words.csv", "bed,la cama\nhouse,\n").unwrap();
+    let rows = map_rows(&table, &table.guess_mapping(), "en", "es");
+
+    assert!(rows[0].is_valid());
+    assert_eq!((rows[0].vocab.first_lang.as_str(), rows[0].vocab.learning.as_str()), ("bed", "la cama"));
+    assert_eq!(rows[0].vocab.learning_lang_code, "es");
+    assert_eq!(rows[1].errors.iter().map(|error| error.field).collect::<Vec<_>>(), vec![VocabField::Learning]);
+}
+
+#[test]
+fn columns_mapped_to_the_same_field_are_joined() {
+    let table = parse_import("words.csv", "bed,la cama,furniture,bedroom\n").unwrap();
+    let mapping = [ImportColumn::FirstLang, ImportColumn::Learning, ImportColumn::Hint, ImportColumn::Hint];
+    let rows = map_rows(&table, &mapping, "en", "es");
+
+    assert_eq!(rows[0].vocab.hint, "furniture bedroom");
+}
+
+#[test]
+fn only_valid_rows_are_batched() {
+    let text: String = (0..IMPORT_BATCH_SIZE + 2).map(|i| format!("word{},palabra{}\n", i, i)).collect::<String>() + "missing,\n";
+    let table = parse_import("words.csv", &text).unwrap();
+    let batches = valid_batches(&map_rows(&table, &table.guess_mapping(), "en", "es"), 0);
+
+    assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![IMPORT_BATCH_SIZE, 2]);
+}
+
+#[test]
+fn retrying_an_import_skips_the_created_rows() {
+    let text: String = (0..2 * IMPORT_BATCH_SIZE + 3).map(|i| format!("word{},palabra{}\n", i, i)).collect();
+    let table = parse_import("words.csv", &text).unwrap();
+    let rows = map_rows(&table, &table.guess_mapping(), "en", "es");
+
+    // The first batch was created before the second one failed
+    let batches = valid_batches(&rows, IMPORT_BATCH_SIZE);
+
+    assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![IMPORT_BATCH_SIZE, 3]);
+    assert_eq!(batches[0][0].first_lang, format!("word{}", IMPORT_BATCH_SIZE));
+    assert!(valid_batches(&rows, 2 * IMPORT_BATCH_SIZE + 3).is_empty());
+}
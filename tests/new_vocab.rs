@@ -0,0 +1,105 @@
+use yew_study::sl::new_vocab::{remember_challenges, CreateVocabBatchResponseWrapper, CreateVocabResponseWrapper, NewVocab, VocabField, KNOWN_CHALLENGES_LIMIT, MAX_FIELD_LEN};
+use yew_study::sl::study::Challenge;
+
+fn new_vocab(first_lang: &str, learning: &str) -> NewVocab {
+    NewVocab { first_lang: first_lang.to_string(), learning: learning.to_string(), ..Default::default() }
+}
+
+fn challenge(vocab_study_id: i32, first_lang: &str, learning: &str) -> Challenge {
+    Challenge {
+        vocab_study_id,
+        first_lang: first_lang.to_string(),
+        learning: learning.to_string(),
+        known_lang_code: "en".to_string(),
+        learning_lang_code: "es".to_string(),
+        ..Default::default()
+    }
+}
+
+fn error_fields(vocab: &NewVocab) -> Vec<VocabField> {
+    vocab.validate().into_iter().map(|error| error.field).collect()
+}
+
+#[test]
+fn valid_vocab_has_no_errors() {
+    assert!(new_vocab("bed", "la cama").validate().is_empty());
+}
+
+#[test]
+fn both_texts_are_required() {
+    assert_eq!(error_fields(&new_vocab("  ", "")), vec![VocabField::FirstLang, VocabField::Learning]);
+}
+
+#[test]
+fn long_fields_are_rejected() {
+    let vocab = NewVocab { hint: "x".repeat(MAX_FIELD_LEN + 1), ..new_vocab("bed", "la cama") };
+    assert_eq!(error_fields(&vocab), vec![VocabField::Hint]);
+}
+
+#[test]
+fn languages_must_be_known_and_different() {
+    let same = NewVocab { learning_lang_code: "en".to_string(), ..new_vocab("bed", "bed") };
+    assert_eq!(error_fields(&same), vec![VocabField::LearningLang]);
+
+    let unknown = NewVocab { known_lang_code: "xx".to_string(), ..new_vocab("bed", "la cama") };
+    assert_eq!(error_fields(&unknown), vec![VocabField::KnownLang]);
+}
+
+#[test]
+fn tidied_collapses_whitespace() {
+    let tidied = NewVocab { pos: " Noun ".to_string(), ..new_vocab("  the  bed ", "la\tcama") }.tidied();
+    assert_eq!((tidied.first_lang.as_str(), tidied.learning.as_str(), tidied.pos.as_str()), ("the bed", "la cama", "noun"));
+}
+
+#[test]
+fn duplicates_ignore_case_and_spacing() {
+    let known = vec![challenge(1, "bed", "la cama"), challenge(2, "house", "la casa")];
+
+    assert_eq!(new_vocab(" Bed ", "el lecho").find_duplicate(&known).map(|c| c.vocab_study_id), Some(1));
+    assert_eq!(new_vocab("home", "La  Casa").find_duplicate(&known).map(|c| c.vocab_study_id), Some(2));
+    assert_eq!(new_vocab("table", "la mesa").find_duplicate(&known), None);
+}
+
+#[test]
+fn duplicates_need_the_same_language_pair() {
+    let known = vec![challenge(1, "bed", "la cama")];
+    let french = NewVocab { learning_lang_code: "fr".to_string(), ..new_vocab("bed", "le lit") };
+
+    assert_eq!(french.find_duplicate(&known), None);
+}
+
+#[test]
+fn remembered_challenges_are_replaced_and_capped() {
+    let mut known = vec![challenge(1, "bed", ""), challenge(2, "house", "")];
+    remember_challenges(&mut known, &[challenge(1, "bed", "la cama")]);
+
+    assert_eq!(known.iter().map(|c| c.vocab_study_id).collect::<Vec<_>>(), vec![2, 1]);
+    assert_eq!(known[1].learning, "la cama");
+
+    let many: Vec<Challenge> = (0..KNOWN_CHALLENGES_LIMIT as i32 + 10).map(|id| challenge(id + 10, "word", "")).collect();
+    remember_challenges(&mut known, &many);
+    assert_eq!(known.len(), KNOWN_CHALLENGES_LIMIT);
+    assert_eq!(known.last().map(|c| c.vocab_study_id), many.last().map(|c| c.vocab_study_id));
+}
+
+#[test]
+fn create_vocab_response_deserializes() {
+    let json = r#"{"data":{"createVocab":{"vocabId":3,"vocabStudyId":9,"firstLang":"bed","infinitive":"","pos":"noun",
+        "hint":"","numLearningWords":2,"userNotes":"","correctAttempts":0,"knownLangCode":"en","learningLangCode":"es",
+        "learning":"la cama"}}}"#;
+    let wrapper: CreateVocabResponseWrapper = serde_json::from_str(json).unwrap();
+
+    assert_eq!(wrapper.data.create_vocab.vocab_study_id, 9);
+    assert_eq!(wrapper.data.create_vocab.learning, "la cama");
+}
+
+#[test]
+fn create_vocab_batch_response_deserializes() {
+    let json = r#"{"data":{"createVocabBatch":[{"vocabId":3,"vocabStudyId":9,"firstLang":"bed","infinitive":"","pos":"noun",
+        "hint":"","numLearningWords":2,"userNotes":"","correctAttempts":0,"knownLangCode":"en","learningLangCode":"es",
+        "learning":"la cama"}]}}"#;
+    let wrapper: CreateVocabBatchResponseWrapper = serde_json::from_str(json).unwrap();
+
+    assert_eq!(wrapper.data.create_vocab_batch[0].vocab_study_id, 9);
+    assert_eq!(wrapper.data.create_vocab_batch[0].learning, "la cama");
+}
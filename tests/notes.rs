@@ -0,0 +1,44 @@
+use yew_study::sl::study::NotesSaves;
+
+#[test]
+fn failed_save_restores_previous_notes() {
+    let mut saves = NotesSaves::default();
+    let seq = saves.begin(1, "old");
+    assert!(saves.is_saving(1));
+
+    assert_eq!(saves.fail(1, seq), Some("old".to_string()));
+    assert!(!saves.is_saving(1));
+}
+
+#[test]
+fn late_failure_does_not_overwrite_newer_notes() {
+    let mut saves = NotesSaves::default();
+    let first = saves.begin(1, "old");
+    let second = saves.begin(1, "first edit");
+
+    assert_eq!(saves.fail(1, first), None);
+    assert!(saves.is_saving(1));
+    assert!(saves.succeed(1, second, "second edit"));
+    assert!(!saves.is_saving(1));
+}
+
+#[test]
+fn latest_failure_restores_last_saved_notes() {
+    let mut saves = NotesSaves::default();
+    let first = saves.begin(1, "old");
+    let second = saves.begin(1, "first edit");
+
+    assert!(!saves.succeed(1, first, "first edit"));
+    assert_eq!(saves.fail(1, second), Some("first edit".to_string()));
+}
+
+#[test]
+fn saves_are_tracked_per_vocab_study() {
+    let mut saves = NotesSaves::default();
+    let first = saves.begin(1, "one");
+    let second = saves.begin(2, "two");
+
+    assert_eq!(saves.fail(1, first), Some("one".to_string()));
+    assert!(saves.is_saving(2));
+    assert!(saves.succeed(2, second, "two edited"));
+}
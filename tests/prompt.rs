@@ -0,0 +1,103 @@
+#[cfg(test)]
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{Element, HtmlElement};
+use yew::platform::time::sleep;
+use yew_study::components::prompt::{Prompt, PromptProps};
+use yew_study::sl::hints::HintKind;
+use yew_study::sl::study::Challenge;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn challenge(vocab_study_id: i32) -> Challenge {
+    Challenge {
+        vocab_study_id,
+        first_lang: "bed".to_string(),
+        pos: "noun".to_string(),
+        hint: "feminine".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Mounts a `Prompt` into a fresh element of the test page and waits for it to render.
+async fn render_prompt(challenge: Challenge) -> Element {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+
+    let props = PromptProps {
+        challenge,
+        hint_order: vec![HintKind::PartOfSpeech, HintKind::OtherHints],
+        on_hint: Default::default(),
+        hint_requests: 0,
+    };
+    yew::Renderer::<Prompt>::with_root_and_props(root.clone(), props).render();
+    sleep(Duration::ZERO).await;
+
+    root
+}
+
+fn hint_texts(root: &Element) -> Vec<String> {
+    let hints = root.query_selector_all(".hint").unwrap();
+    (0..hints.length())
+        .filter_map(|i| hints.item(i))
+        .filter_map(|node| node.text_content())
+        .collect()
+}
+
+async fn click_hint_link(root: &Element) {
+    let link: HtmlElement = root.query_selector(".hint-link").unwrap().unwrap().dyn_into().unwrap();
+    link.click();
+    sleep(Duration::ZERO).await;
+}
+
+#[wasm_bindgen_test]
+async fn hint_link_is_shown_on_first_render() {
+    let root = render_prompt(challenge(1)).await;
+
+    assert!(root.query_selector(".hint-link").unwrap().is_some());
+    assert!(hint_texts(&root).is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn hint_link_reveals_each_hint_in_order() {
+    let root = render_prompt(challenge(1)).await;
+
+    click_hint_link(&root).await;
+    assert_eq!(hint_texts(&root), vec!["Part of Speech: noun"]);
+
+    click_hint_link(&root).await;
+    assert_eq!(hint_texts(&root), vec!["Part of Speech: noun", "Other Hints: feminine"]);
+
+    // Every hint has been revealed, so the link goes away
+    assert!(root.query_selector(".hint-link").unwrap().is_none());
+}
+
+#[wasm_bindgen_test]
+async fn no_hint_link_without_hints() {
+    let root = render_prompt(Challenge { vocab_study_id: 2, ..Default::default() }).await;
+
+    assert!(root.query_selector(".hint-link").unwrap().is_none());
+}
+
+#[wasm_bindgen_test]
+async fn hint_trigger_is_a_button_controlling_the_hints() {
+    let root = render_prompt(challenge(1)).await;
+
+    let trigger = root.query_selector(".hint-link").unwrap().unwrap();
+    assert_eq!(trigger.tag_name(), "BUTTON");
+    assert_eq!(trigger.get_attribute("type").as_deref(), Some("button"));
+    assert_eq!(trigger.get_attribute("aria-controls").as_deref(), Some("challenge_hints"));
+}
+
+#[wasm_bindgen_test]
+async fn hints_are_revealed_in_a_live_region() {
+    let root = render_prompt(challenge(1)).await;
+
+    let region = root.query_selector("#challenge_hints").unwrap().unwrap();
+    assert_eq!(region.get_attribute("aria-live").as_deref(), Some("polite"));
+
+    click_hint_link(&root).await;
+    assert_eq!(root.query_selector_all("#challenge_hints .hint").unwrap().length(), 1);
+}
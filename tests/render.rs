@@ -1,7 +1,12 @@
 #[cfg(test)]
 use wasm_bindgen_test::*;
 use yew::prelude::*;
-use yew_study::pages::{home::Home, page_not_found::PageNotFound, study::Study};
+use yew_study::components::error_panel::ErrorPanel;
+use yew_study::components::shortcuts::ShortcutsProvider;
+use yew_study::components::store::StoreProvider;
+use yew_study::components::theme::{ThemePicker, ThemeProvider};
+use yew_study::sl::gql::{ErrorKind, FetchError};
+use yew_study::pages::{add_vocab::AddVocab, home::Home, import::Import, page_not_found::PageNotFound, profile::Profile, schedule::ScheduleDebug, settings::SettingsPage, study::Study, summary::Summary, vocab::Vocab, vocab_detail::VocabDetail};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -29,3 +34,110 @@ fn study_component_loads() {
     };
     // The test passes if the component is created without panicking.
 }
+
+#[wasm_bindgen_test]
+fn summary_component_loads() {
+    let _app: Html = html! {
+        <Summary />
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn vocab_component_loads() {
+    let _app: Html = html! {
+        <Vocab />
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn vocab_detail_component_loads() {
+    let _app: Html = html! {
+        <VocabDetail vocab_study_id={1} />
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn add_vocab_component_loads() {
+    let _app: Html = html! {
+        <AddVocab />
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn import_component_loads() {
+    let _app: Html = html! {
+        <Import />
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn schedule_debug_component_loads() {
+    let _app: Html = html! {
+        <ScheduleDebug />
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn profile_component_loads() {
+    let _app: Html = html! {
+        <Profile />
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn shortcuts_provider_component_loads() {
+    let _app: Html = html! {
+        <ShortcutsProvider>
+            <Study />
+        </ShortcutsProvider>
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn theme_provider_component_loads() {
+    let _app: Html = html! {
+        <StoreProvider>
+            <ThemeProvider>
+                <ThemePicker />
+            </ThemeProvider>
+        </StoreProvider>
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn settings_component_loads() {
+    let _app: Html = html! {
+        <StoreProvider>
+            <SettingsPage />
+        </StoreProvider>
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn error_panel_component_loads() {
+    let error = FetchError::new(ErrorKind::Offline, "error sending request");
+    let _app: Html = html! {
+        <StoreProvider>
+            <ErrorPanel {error} on_retry={Callback::noop()} />
+        </StoreProvider>
+    };
+    // The test passes if the component is created without panicking.
+}
+
+#[wasm_bindgen_test]
+fn browser_failures_keep_their_message_for_the_error_panel() {
+    let error = FetchError::from(wasm_bindgen::JsValue::from_str("The quota has been exceeded."));
+
+    assert_eq!(error.kind, ErrorKind::Other);
+    assert_eq!(error.message(), "The quota has been exceeded.");
+}
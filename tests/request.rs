@@ -0,0 +1,118 @@
+use std::cell::Cell;
+use std::future::{pending, ready, Future};
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use yew_study::sl::request::{abortable, LatestRequest, RequestId};
+
+/// Polls a future once.
+fn poll_once<F: Future>(future: F) -> Poll<F::Output> {
+    let mut context = Context::from_waker(Waker::noop());
+    pin!(future).poll(&mut context)
+}
+
+/// Sets a flag when dropped, standing in for reqwest's guard aborting the fetch.
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[test]
+fn request_ids_are_unique() {
+    let first = RequestId::next();
+    let second = RequestId::next();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn abortable_completes_when_not_cancelled() {
+    let (future, handle) = abortable(ready(7));
+    assert_eq!(poll_once(future), Poll::Ready(Some(7)));
+    assert!(!handle.is_cancelled());
+}
+
+#[test]
+fn cancelled_future_is_not_polled() {
+    let polled = Rc::new(Cell::new(false));
+    let (future, handle) = abortable({
+        let polled = polled.clone();
+        async move {
+            polled.set(true);
+        }
+    });
+
+    handle.cancel();
+    assert_eq!(poll_once(future), Poll::Ready(None));
+    assert!(!polled.get());
+}
+
+#[test]
+fn cancelling_drops_pending_request() {
+    let dropped = Rc::new(Cell::new(false));
+    let guard = DropFlag(dropped.clone());
+    let (future, handle) = abortable(async move {
+        let _guard = guard;
+        pending::<()>().await;
+    });
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+
+    assert!(future.as_mut().poll(&mut context).is_pending());
+    assert!(!dropped.get());
+
+    handle.cancel();
+    assert_eq!(future.as_mut().poll(&mut context), Poll::Ready(None));
+    assert!(dropped.get());
+}
+
+#[test]
+fn dropping_handle_cancels() {
+    let (future, handle) = abortable(ready(()));
+    drop(handle);
+    assert_eq!(poll_once(future), Poll::Ready(None));
+}
+
+#[test]
+fn latest_request_accepts_current_response_once() {
+    let mut latest = LatestRequest::default();
+    let id = RequestId::next();
+    let (_future, handle) = abortable(ready(()));
+
+    latest.track(id, handle);
+    assert!(latest.is_pending());
+    assert!(latest.finish(id));
+    assert!(!latest.is_pending());
+    assert!(!latest.finish(id));
+}
+
+#[test]
+fn newer_request_supersedes_older() {
+    let mut latest = LatestRequest::default();
+    let (older, older_handle) = (RequestId::next(), abortable(pending::<()>()));
+    let (newer, newer_handle) = (RequestId::next(), abortable(pending::<()>()));
+
+    latest.track(older, older_handle.1);
+    latest.track(newer, newer_handle.1);
+
+    // The older request was cancelled, and its response would be stale anyway
+    assert_eq!(poll_once(older_handle.0), Poll::Ready(None));
+    assert!(!latest.finish(older));
+    assert!(latest.finish(newer));
+}
+
+#[test]
+fn cancelled_request_response_is_stale() {
+    let mut latest = LatestRequest::default();
+    let id = RequestId::next();
+    let (future, handle) = abortable(pending::<()>());
+
+    latest.track(id, handle);
+    latest.cancel();
+
+    assert!(!latest.is_pending());
+    assert!(!latest.finish(id));
+    assert_eq!(poll_once(future), Poll::Ready(None));
+}
@@ -0,0 +1,190 @@
+use yew_study::sl::scheduler::{answer_quality, due_display, next_due_ms, num_due_by, parse_timestamp_ms, record_review, ItemHistory, ReviewState,
+                               Schedule, Scheduler, DAY_MS, DEFAULT_EASE, MIN_EASE, RELEARN_MS};
+use yew_study::sl::study::{Challenge, VocabStats};
+
+const NOW: f64 = 1_700_000_000_000.0;
+
+fn challenge(vocab_study_id: i32) -> Challenge {
+    Challenge { vocab_study_id, ..Default::default() }
+}
+
+fn unknown(_: i32) -> Option<ItemHistory> {
+    None
+}
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn answers_are_rated_by_correctness_and_score() {
+    assert_eq!(answer_quality(false, 1.0), 1);
+    assert_eq!(answer_quality(true, 1.0), 5);
+    assert_eq!(answer_quality(true, 0.6), 4);
+    assert_eq!(answer_quality(true, 0.2), 3);
+}
+
+#[test]
+fn new_items_are_due_right_away() {
+    let state = ReviewState::from_history(&ItemHistory::default());
+    assert_eq!(state, ReviewState::default());
+    assert!(state.is_due(0.0));
+}
+
+#[test]
+fn history_seeds_ease_repetitions_and_due_time() {
+    let history = ItemHistory { attempts: 4, correct_attempts: 3, last_tested_ms: Some(NOW) };
+    let state = ReviewState::from_history(&history);
+
+    assert!(approx(state.ease, MIN_EASE + (DEFAULT_EASE - MIN_EASE) * 0.75));
+    assert_eq!(state.repetitions, 3);
+    assert!(approx(state.interval_days, 6.0 * state.ease));
+    assert!(approx(state.due_ms, NOW + state.interval_days * DAY_MS));
+
+    let struggling = ReviewState::from_history(&ItemHistory { attempts: 10, correct_attempts: 2, last_tested_ms: Some(NOW) });
+    assert_eq!(struggling.repetitions, 0);
+    assert!(approx(struggling.due_ms, NOW));
+}
+
+#[test]
+fn correct_reviews_follow_the_sm2_intervals() {
+    let mut state = ReviewState::default();
+
+    state.review(5, NOW);
+    assert_eq!((state.repetitions, state.interval_days), (1, 1.0));
+    assert!(approx(state.ease, 2.6));
+
+    state.review(5, NOW);
+    assert_eq!((state.repetitions, state.interval_days), (2, 6.0));
+
+    state.review(4, NOW);
+    assert_eq!((state.repetitions, state.interval_days), (3, (6.0 * 2.7_f64).round()));
+    assert!(approx(state.due_ms, NOW + state.interval_days * DAY_MS));
+    assert_eq!(state.last_reviewed_ms, Some(NOW));
+}
+
+#[test]
+fn missed_reviews_start_over_and_lower_the_ease() {
+    let mut state = ReviewState { repetitions: 3, ease: 1.4, interval_days: 16.0, ..Default::default() };
+    state.review(1, NOW);
+
+    assert_eq!((state.repetitions, state.interval_days), (0, 0.0));
+    assert!(approx(state.ease, MIN_EASE));
+    assert!(approx(state.due_ms, NOW + RELEARN_MS));
+}
+
+#[test]
+fn earliest_due_comes_next_with_ties_broken_by_id() {
+    let mut schedule = Schedule::new();
+    schedule.insert(1, ReviewState { due_ms: NOW + DAY_MS, ..Default::default() });
+    schedule.insert(2, ReviewState { due_ms: NOW - DAY_MS, ..Default::default() });
+
+    let scheduler = Scheduler::new(vec![challenge(1), challenge(4), challenge(2), challenge(3), challenge(4)], &schedule, unknown);
+    let order: Vec<i32> = scheduler.items().iter().map(|item| item.challenge.vocab_study_id).collect();
+
+    assert_eq!(order, vec![3, 4, 2, 1]);
+    assert_eq!(scheduler.next_challenge().map(|c| c.vocab_study_id), Some(3));
+    assert_eq!(scheduler.num_due(NOW), 3);
+    assert_eq!(scheduler.batch(2).iter().map(|c| c.vocab_study_id).collect::<Vec<_>>(), vec![3, 4]);
+}
+
+#[test]
+fn recording_a_review_reorders_the_items() {
+    let mut scheduler = Scheduler::new(vec![challenge(1), challenge(2)], &Schedule::new(), unknown);
+
+    let state = scheduler.record(1, 5, NOW).unwrap();
+    assert!(approx(state.due_ms, NOW + DAY_MS));
+    assert_eq!(scheduler.next_challenge().map(|c| c.vocab_study_id), Some(2));
+    assert_eq!(scheduler.record(9, 5, NOW), None);
+}
+
+#[test]
+fn reviews_are_recorded_in_a_stored_schedule() {
+    let mut schedule = Schedule::new();
+    record_review(&mut schedule, 7, &ItemHistory::default(), 5, NOW);
+    let state = record_review(&mut schedule, 7, &ItemHistory::default(), 5, NOW + DAY_MS);
+
+    assert_eq!(state.repetitions, 2);
+    assert_eq!(schedule.get(&7), Some(&state));
+}
+
+#[test]
+fn due_times_are_displayed_relative_to_now() {
+    assert_eq!(due_display(NOW - 1.0, NOW, "en"), "due now");
+    assert_eq!(due_display(NOW + RELEARN_MS, NOW, "en"), "in 10m");
+    assert_eq!(due_display(NOW + 3.0 * 3_600_000.0, NOW, "en"), "in 3h");
+    assert_eq!(due_display(NOW + 6.0 * DAY_MS, NOW, "en"), "in 6d");
+    assert_eq!(due_display(NOW + 6.0 * DAY_MS, NOW, "fr"), "dans 6 j");
+}
+
+#[test]
+fn items_due_by_a_time_are_counted() {
+    let mut schedule = Schedule::new();
+    record_review(&mut schedule, 1, &ItemHistory::default(), 1, NOW);
+    record_review(&mut schedule, 2, &ItemHistory::default(), 5, NOW);
+    record_review(&mut schedule, 3, &ItemHistory::default(), 5, NOW);
+    record_review(&mut schedule, 3, &ItemHistory::default(), 5, NOW + DAY_MS);
+
+    assert_eq!(num_due_by(&schedule, NOW), 0);
+    assert_eq!(num_due_by(&schedule, NOW + RELEARN_MS), 1);
+    assert_eq!(num_due_by(&schedule, NOW + DAY_MS), 2);
+    assert_eq!(num_due_by(&schedule, NOW + 7.0 * DAY_MS), 3);
+}
+
+#[test]
+fn next_due_time_is_the_earliest_upcoming() {
+    let mut schedule = Schedule::new();
+    assert_eq!(next_due_ms(&schedule, NOW), None);
+
+    record_review(&mut schedule, 1, &ItemHistory::default(), 5, NOW - 7.0 * DAY_MS);
+    record_review(&mut schedule, 2, &ItemHistory::default(), 1, NOW);
+    record_review(&mut schedule, 3, &ItemHistory::default(), 5, NOW);
+
+    assert_eq!(next_due_ms(&schedule, NOW), Some(NOW + RELEARN_MS));
+    assert_eq!(next_due_ms(&schedule, NOW + RELEARN_MS), Some(NOW + DAY_MS));
+    assert_eq!(next_due_ms(&schedule, NOW + DAY_MS), None);
+}
+
+#[test]
+fn stats_seed_the_history() {
+    let stats = VocabStats { attempts: 5, correct_attempts: 4, last_tested: "2023-11-14 22:13:20".to_string(), ..Default::default() };
+    let history = ItemHistory::from(&stats);
+
+    assert_eq!((history.attempts, history.correct_attempts), (5, 4));
+    assert_eq!(history.last_tested_ms, Some(NOW));
+}
+
+#[test]
+fn seeded_history_lengthens_the_first_interval() {
+    let mut fresh = Schedule::new();
+    let unseen = record_review(&mut fresh, 1, &ItemHistory::default(), 5, NOW);
+
+    let mut seeded = Schedule::new();
+    let history = ItemHistory { attempts: 4, correct_attempts: 4, last_tested_ms: Some(NOW - DAY_MS) };
+    let known = record_review(&mut seeded, 1, &history, 5, NOW);
+
+    assert!(approx(unseen.interval_days, 1.0));
+    assert!(known.interval_days > 6.0);
+    assert!(known.due_ms > unseen.due_ms);
+}
+
+#[test]
+fn scheduler_seeds_unreviewed_items_from_their_history() {
+    let history = |vocab_study_id: i32| (vocab_study_id == 1)
+        .then_some(ItemHistory { attempts: 2, correct_attempts: 2, last_tested_ms: Some(NOW) });
+    let scheduler = Scheduler::new(vec![challenge(1), challenge(2)], &Schedule::new(), history);
+
+    assert_eq!(scheduler.num_due(NOW), 1);
+    assert_eq!(scheduler.next_challenge().map(|c| c.vocab_study_id), Some(2));
+}
+
+#[test]
+fn timestamps_are_parsed_as_utc() {
+    assert_eq!(parse_timestamp_ms("1970-01-01"), Some(0.0));
+    assert_eq!(parse_timestamp_ms("2023-11-14T22:13:20Z"), Some(NOW));
+    assert_eq!(parse_timestamp_ms("2023-11-14 22:13"), Some(NOW - 20_000.0));
+    assert_eq!(parse_timestamp_ms("2024-02-29"), Some(19_782.0 * DAY_MS));
+    assert_eq!(parse_timestamp_ms("never"), None);
+    assert_eq!(parse_timestamp_ms(""), None);
+    assert_eq!(parse_timestamp_ms("2024-13-01"), None);
+}
@@ -0,0 +1,205 @@
+use yew_study::sl::session::{remember_mistake, SessionLength, SessionQuery, StudySession, DEFAULT_REQUEUE_SPACING, DEFAULT_SESSION_ITEMS,
+                              RECENT_MISTAKES_LIMIT};
+use yew_study::sl::hints::HintKind;
+use yew_study::sl::study::{Challenge, CheckResult, VocabStats};
+
+fn challenge(id: i32, first_lang: &str) -> Challenge {
+    Challenge { vocab_study_id: id, first_lang: first_lang.to_string(), ..Default::default() }
+}
+
+fn result(correct: bool, percentage_correct: f64) -> CheckResult {
+    CheckResult {
+        correct: Some(correct),
+        stats: Some(VocabStats { percentage_correct, ..Default::default() }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn query_resolves_session_length() {
+    assert_eq!(SessionQuery::default().length(), SessionLength::Items(DEFAULT_SESSION_ITEMS));
+    assert_eq!(SessionQuery::items(20).length(), SessionLength::Items(20));
+    assert_eq!(SessionQuery { minutes: Some(5), ..Default::default() }.length(), SessionLength::Minutes(5));
+    assert_eq!(SessionQuery { items: Some(0), minutes: Some(5), spacing: None }.length(), SessionLength::Minutes(5));
+    assert_eq!(SessionQuery::default().spacing(), DEFAULT_REQUEUE_SPACING);
+    assert_eq!(SessionQuery { spacing: Some(5), ..Default::default() }.spacing(), 5);
+    assert_eq!(SessionQuery::default().length_or(SessionLength::Minutes(3)), SessionLength::Minutes(3));
+    assert_eq!(SessionQuery::items(20).length_or(SessionLength::Minutes(3)), SessionLength::Items(20));
+}
+
+#[test]
+fn item_sessions_complete_after_enough_answers() {
+    let mut session = StudySession::new(SessionLength::Items(2), 0.0);
+    assert!(!session.is_complete(0.0));
+
+    session.record(&challenge(1, "dog"), &result(true, 100.0), &[]);
+    assert!(!session.is_complete(0.0));
+
+    session.record(&challenge(2, "cat"), &result(true, 50.0), &[]);
+    assert!(session.is_complete(0.0));
+}
+
+#[test]
+fn sessions_are_not_complete_while_misses_are_outstanding() {
+    let mut session = StudySession::new(SessionLength::Items(2), 0.0);
+    session.record(&challenge(1, "dog"), &result(true, 100.0), &[]);
+    session.record(&challenge(2, "cat"), &result(false, 50.0), &[]);
+
+    assert!(session.length_reached(0.0));
+    assert!(!session.is_complete(0.0));
+    assert_eq!(session.num_outstanding(), 1);
+
+    session.record(&challenge(2, "cat"), &result(true, 60.0), &[]);
+    assert!(session.is_complete(0.0));
+}
+
+#[test]
+fn missed_challenges_are_requeued_after_spacing() {
+    let mut session = StudySession::new(SessionLength::Items(10), 0.0).with_spacing(2);
+    session.enqueue((1..=5).map(|id| challenge(id, "word")).collect());
+
+    let first = session.next_challenge(0.0).unwrap();
+    session.record(&first, &result(false, 0.0), &[]);
+
+    let order: Vec<i32> = std::iter::from_fn(|| session.next_challenge(0.0))
+        .map(|c| c.vocab_study_id)
+        .collect();
+    assert_eq!(order, vec![2, 3, 1, 4, 5]);
+}
+
+#[test]
+fn requeue_spacing_is_capped_by_queue_length() {
+    let mut session = StudySession::new(SessionLength::Items(10), 0.0).with_spacing(5);
+    session.enqueue(vec![challenge(1, "dog"), challenge(2, "cat")]);
+
+    let first = session.next_challenge(0.0).unwrap();
+    session.record(&first, &result(false, 0.0), &[]);
+
+    let order: Vec<i32> = std::iter::from_fn(|| session.next_challenge(0.0))
+        .map(|c| c.vocab_study_id)
+        .collect();
+    assert_eq!(order, vec![2, 1]);
+}
+
+#[test]
+fn only_missed_challenges_are_served_once_length_is_reached() {
+    let mut session = StudySession::new(SessionLength::Items(2), 0.0).with_spacing(1);
+    session.enqueue((1..=4).map(|id| challenge(id, "word")).collect());
+
+    let first = session.next_challenge(0.0).unwrap();
+    session.record(&first, &result(false, 0.0), &[]);
+    let second = session.next_challenge(0.0).unwrap();
+    session.record(&second, &result(true, 100.0), &[]);
+
+    assert_eq!(session.next_challenge(0.0).map(|c| c.vocab_study_id), Some(1));
+    assert_eq!(session.next_challenge(0.0), None);
+}
+
+#[test]
+fn attempts_are_counted_per_challenge() {
+    let mut session = StudySession::new(SessionLength::Items(10), 0.0);
+    session.record(&challenge(1, "dog"), &result(false, 0.0), &[]);
+    session.record(&challenge(2, "cat"), &result(true, 100.0), &[]);
+    session.record(&challenge(1, "dog"), &result(true, 50.0), &[]);
+
+    assert_eq!(session.attempts_for(1), 2);
+    assert_eq!(session.attempts_for(2), 1);
+    assert_eq!(session.attempts_for(3), 0);
+    assert!(!session.is_outstanding(1));
+}
+
+#[test]
+fn minute_sessions_complete_after_enough_time() {
+    let session = StudySession::new(SessionLength::Minutes(5), 1_000.0);
+    assert!(!session.is_complete(1_000.0 + 4.0 * 60_000.0));
+    assert!(session.is_complete(1_000.0 + 5.0 * 60_000.0));
+}
+
+#[test]
+fn summary_counts_answers_and_time() {
+    let mut session = StudySession::new(SessionLength::Items(3), 0.0);
+    session.record(&challenge(1, "dog"), &result(true, 100.0), &[]);
+    session.record(&challenge(2, "cat"), &result(false, 50.0), &[]);
+    session.record(&challenge(3, "bird"), &CheckResult::from("Checked".to_string()), &[]);
+
+    let summary = session.summary(125_000.0);
+    assert_eq!(summary.attempted, 3);
+    assert_eq!(summary.correct, 1);
+    assert_eq!(summary.incorrect, 1);
+    assert_eq!(summary.elapsed_display(), "2:05");
+}
+
+#[test]
+fn summary_ranks_hardest_words() {
+    let mut session = StudySession::new(SessionLength::Items(10), 0.0);
+    session.record(&challenge(1, "dog"), &result(false, 80.0), &[]);
+    session.record(&challenge(2, "cat"), &result(false, 40.0), &[]);
+    session.record(&challenge(3, "bird"), &result(true, 90.0), &[]);
+    session.record(&challenge(1, "dog"), &result(false, 70.0), &[]);
+
+    let summary = session.summary(0.0);
+    let hardest: Vec<&str> = summary.hardest.iter().map(|w| w.challenge.first_lang.as_str()).collect();
+    assert_eq!(hardest, vec!["dog", "cat"]);
+    assert_eq!(summary.hardest[0].misses, 2);
+    assert_eq!(summary.hardest[0].stats.as_ref().unwrap().percentage_correct, 70.0);
+
+    let missed: Vec<i32> = summary.missed.iter().map(|c| c.vocab_study_id).collect();
+    assert_eq!(missed, vec![1, 2]);
+}
+
+#[test]
+fn hints_reduce_the_points_of_an_answer() {
+    let mut session = StudySession::new(SessionLength::Items(10), 0.0);
+    session.record(&challenge(1, "dog"), &result(true, 100.0), &[]);
+    session.record(&challenge(2, "cat"), &result(true, 100.0), &[HintKind::FirstLetter, HintKind::MaskedLetters]);
+    session.record(&challenge(3, "bird"), &CheckResult { score: Some(0.5), ..result(false, 0.0) }, &[]);
+
+    assert!((session.attempts[1].score - 0.75).abs() < 1e-9);
+
+    let summary = session.summary(0.0);
+    assert!((summary.points - 2.25).abs() < 1e-9);
+    assert_eq!(summary.hints_used, 2);
+}
+
+#[test]
+fn notes_are_updated_on_queued_challenges() {
+    let mut session = StudySession::new(SessionLength::Items(10), 0.0);
+    session.enqueue(vec![challenge(1, "dog"), challenge(2, "cat"), challenge(1, "dog")]);
+
+    session.update_notes(1, "perro");
+
+    let notes: Vec<&str> = session.queue.iter().map(|c| c.user_notes.as_str()).collect();
+    assert_eq!(notes, vec!["perro", "", "perro"]);
+}
+
+#[test]
+fn notes_are_updated_on_answered_challenges() {
+    let mut session = StudySession::new(SessionLength::Items(10), 0.0);
+    session.record(&challenge(1, "dog"), &result(false, 0.0), &[]);
+
+    session.update_notes(1, "perro");
+
+    assert_eq!(session.attempts[0].challenge.user_notes, "perro");
+    assert_eq!(session.summary(0.0).missed[0].user_notes, "perro");
+}
+
+#[test]
+fn recent_mistakes_are_kept_most_recent_first_without_repeats() {
+    let mut mistakes = Vec::new();
+    for id in 0..(RECENT_MISTAKES_LIMIT as i32 + 2) {
+        remember_mistake(&mut mistakes, &challenge(id, "word"));
+    }
+    remember_mistake(&mut mistakes, &challenge(5, "word"));
+
+    let ids: Vec<i32> = mistakes.iter().map(|c| c.vocab_study_id).collect();
+    assert_eq!(ids.len(), RECENT_MISTAKES_LIMIT);
+    assert_eq!(&ids[..3], &[5, 11, 10]);
+    assert!(!ids.contains(&0) && !ids.contains(&1));
+}
+
+#[test]
+fn timed_queries_set_only_the_minutes() {
+    let query = SessionQuery::minutes(5);
+    assert_eq!(query.minutes, Some(5));
+    assert_eq!(query.items, None);
+}
@@ -0,0 +1,106 @@
+use serde_json::json;
+use yew_study::sl::hints::{HintKind, DEFAULT_HINT_ORDER};
+use yew_study::sl::i18n::{language_name, translate};
+use yew_study::sl::session::SessionLength;
+use yew_study::sl::settings::{migrate, to_store, Settings, DEFAULT_BATCH_SIZE, MAX_BATCH_SIZE, SETTINGS_VERSION};
+use yew_study::sl::theme::{FontSize, Theme, ThemeSettings};
+
+#[test]
+fn defaults_study_in_english() {
+    let settings = Settings::default();
+
+    assert_eq!(settings.version, SETTINGS_VERSION);
+    assert_eq!(settings.batch_size, DEFAULT_BATCH_SIZE);
+    assert_eq!(settings.session_length, SessionLength::default());
+    assert_eq!(settings.hint_order, DEFAULT_HINT_ORDER.to_vec());
+    assert!(settings.auto_focus);
+    assert!(!settings.auto_speak);
+    assert_eq!(settings.language, "en");
+}
+
+#[test]
+fn validation_brings_fields_back_into_range() {
+    let settings = Settings {
+        batch_size: 500,
+        session_length: SessionLength::Minutes(0),
+        hint_order: vec![HintKind::FirstLetter, HintKind::Infinitive, HintKind::FirstLetter],
+        language: "xx".to_string(),
+        ..Default::default()
+    }.validated();
+
+    assert_eq!(settings.batch_size, MAX_BATCH_SIZE);
+    assert_eq!(settings.session_length, SessionLength::default());
+    assert_eq!(settings.hint_order, vec![HintKind::FirstLetter, HintKind::Infinitive]);
+    assert_eq!(settings.language, "en");
+    assert_eq!(Settings { batch_size: 0, ..Default::default() }.validated().batch_size, 1);
+}
+
+#[test]
+fn first_load_keeps_the_legacy_theme() {
+    let legacy = ThemeSettings { theme: Theme::Dark, font_size: FontSize::Large };
+
+    assert_eq!(migrate(None, Some(legacy)), Settings { theme: legacy, ..Default::default() });
+    assert_eq!(migrate(None, None), Settings::default());
+}
+
+#[test]
+fn missing_fields_are_defaulted() {
+    let settings = migrate(Some(json!({ "version": SETTINGS_VERSION, "batch_size": 10 })), None);
+
+    assert_eq!(settings.batch_size, 10);
+    assert_eq!(settings.hint_order, DEFAULT_HINT_ORDER.to_vec());
+    assert!(settings.auto_focus);
+}
+
+#[test]
+fn version_one_settings_are_upgraded() {
+    let stored = json!({ "version": 1, "batch_size": 10, "session_mode": "Review", "direction": "Reverse", "auto_speak": true });
+    let settings = migrate(Some(stored.clone()), None);
+
+    assert_eq!(settings, Settings { batch_size: 10, auto_speak: true, ..Default::default() });
+    let saved = to_store(Some(stored), &settings);
+    assert_eq!(saved.get("version"), Some(&json!(SETTINGS_VERSION)));
+    assert_eq!(saved.get("direction"), None);
+    assert_eq!(saved.get("session_mode"), None);
+}
+
+#[test]
+fn unreadable_fields_are_defaulted_one_by_one() {
+    let settings = migrate(Some(json!({ "batch_size": "many", "auto_speak": true })), None);
+
+    assert_eq!(settings, Settings { auto_speak: true, ..Default::default() });
+    assert_eq!(migrate(Some(json!([1, 2])), None), Settings::default());
+}
+
+#[test]
+fn newer_settings_are_read_as_far_as_understood() {
+    let newer = SETTINGS_VERSION + 1;
+    let stored = json!({ "version": newer, "batch_size": 10, "hint_order": "shuffled", "dictation": true });
+    let settings = migrate(Some(stored.clone()), None);
+
+    assert_eq!(settings, Settings { version: newer, batch_size: 10, ..Default::default() });
+
+    let saved = to_store(Some(stored), &Settings { auto_speak: true, ..settings });
+    assert_eq!(saved, json!({ "version": newer, "batch_size": 10, "hint_order": "shuffled", "dictation": true, "auto_speak": true }));
+}
+
+#[test]
+fn settings_round_trip_through_json() {
+    let settings = Settings {
+        batch_size: 12,
+        session_length: SessionLength::Minutes(10),
+        auto_speak: true,
+        language: "fr".to_string(),
+        ..Default::default()
+    };
+    let stored = serde_json::to_value(&settings).unwrap();
+    assert_eq!(migrate(Some(stored), None), settings);
+}
+
+#[test]
+fn texts_are_translated_with_an_english_fallback() {
+    assert_eq!(translate("es", "Check"), "Verificar");
+    assert_eq!(translate("es", "Not translated"), "Not translated");
+    assert_eq!(translate("xx", "Check"), "Check");
+    assert_eq!(language_name("de"), "Deutsch");
+}
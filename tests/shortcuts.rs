@@ -0,0 +1,80 @@
+use yew_study::sl::shortcuts::{ignores_key, normalize_key, KeyBuffer, KeySequence, ShortcutAction, ShortcutMap, SEQUENCE_TIMEOUT_MS};
+
+const ALL: [ShortcutAction; 8] = ShortcutAction::ALL;
+
+fn keys(text: &str) -> KeySequence {
+    KeySequence::parse(text).unwrap()
+}
+
+#[test]
+fn sequences_are_parsed_with_named_keys() {
+    assert_eq!(keys("g  p"), KeySequence(vec!["g".to_string(), "p".to_string()]));
+    assert_eq!(keys("Esc").to_string(), "Escape");
+    assert_eq!(normalize_key(" "), "Space");
+    assert_eq!(KeySequence::parse("   "), None);
+}
+
+#[test]
+fn default_shortcuts_do_not_conflict() {
+    let map = ShortcutMap::default();
+    assert!(map.conflicts().is_empty());
+    assert_eq!(map.keys_for(ShortcutAction::Next), vec![&keys("n"), &keys("Enter")]);
+}
+
+#[test]
+fn rebinding_refuses_conflicts() {
+    let map = ShortcutMap::default();
+
+    let conflict = map.rebind(ShortcutAction::Hint, vec![keys("g")]).unwrap_err();
+    assert_eq!(conflict.second.action, ShortcutAction::Hint);
+    assert_eq!(conflict.first.keys, keys("g h"));
+
+    assert!(map.rebind(ShortcutAction::Speak, vec![keys("n")]).is_err());
+    assert!(map.rebind(ShortcutAction::Hint, vec![keys("i"), keys("?")]).is_err());
+
+    let map = map.rebind(ShortcutAction::Hint, vec![keys("i"), keys("x h")]).unwrap();
+    assert_eq!(map.keys_for(ShortcutAction::Hint), vec![&keys("i"), &keys("x h")]);
+}
+
+#[test]
+fn sequences_complete_within_the_timeout() {
+    let map = ShortcutMap::default();
+    let mut buffer = KeyBuffer::default();
+
+    assert_eq!(buffer.press(&map, &ALL, "g", 1_000.0), None);
+    assert_eq!(buffer.press(&map, &ALL, "p", 1_500.0), Some(ShortcutAction::GoProfile));
+    assert!(buffer.pending.is_empty());
+
+    assert_eq!(buffer.press(&map, &ALL, "g", 3_000.0), None);
+    assert_eq!(buffer.press(&map, &ALL, "p", 3_000.0 + SEQUENCE_TIMEOUT_MS + 1.0), None);
+}
+
+#[test]
+fn a_key_breaking_a_sequence_is_tried_on_its_own() {
+    let map = ShortcutMap::default();
+    let mut buffer = KeyBuffer::default();
+
+    assert_eq!(buffer.press(&map, &ALL, "g", 1_000.0), None);
+    assert_eq!(buffer.press(&map, &ALL, "h", 1_100.0), Some(ShortcutAction::GoHome));
+    assert_eq!(buffer.press(&map, &ALL, "g", 1_200.0), None);
+    assert_eq!(buffer.press(&map, &ALL, "?", 1_300.0), Some(ShortcutAction::Help));
+}
+
+#[test]
+fn only_active_actions_are_triggered() {
+    let map = ShortcutMap::default();
+    let mut buffer = KeyBuffer::default();
+
+    assert_eq!(buffer.press(&map, &ShortcutAction::GLOBAL, "h", 1_000.0), None);
+    assert_eq!(buffer.press(&map, &ALL, "h", 1_100.0), Some(ShortcutAction::Hint));
+}
+
+#[test]
+fn keys_typed_into_fields_are_not_shortcuts() {
+    assert!(ignores_key("INPUT", false, "h"));
+    assert!(ignores_key("textarea", false, "?"));
+    assert!(ignores_key("DIV", true, "h"));
+    assert!(ignores_key("BUTTON", false, "Enter"));
+    assert!(!ignores_key("BUTTON", false, "n"));
+    assert!(!ignores_key("BODY", false, "h"));
+}
@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::{Callback, Reducible};
+use yew_study::sl::awesome::AwesomeProfile;
+use yew_study::sl::gql::{CacheTag, Cached, QueryKey};
+use yew_study::sl::session::{SessionLength, StudySession};
+use yew_study::sl::settings::{Settings, MAX_BATCH_SIZE};
+use yew_study::sl::store::{Action, AppState, Store};
+use yew_study::sl::theme::{Theme, ThemeSettings};
+
+fn profile(id: i32) -> AwesomeProfile {
+    AwesomeProfile { id, name: format!("Learner {}", id), ..Default::default() }
+}
+
+fn learner_state() -> AppState {
+    AppState {
+        learner: Some(7),
+        profile: Some(profile(7)),
+        session: Some(StudySession::new(SessionLength::Items(5), 0.0)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn switching_learner_drops_their_profile_and_session_but_keeps_the_settings() {
+    let state = Rc::new(AppState { settings: Settings { batch_size: 9, ..Default::default() }, ..learner_state() });
+
+    let switched = state.clone().reduce(Action::SelectLearner(8));
+    assert_eq!(switched.learner, Some(8));
+    assert_eq!(switched.profile, None);
+    assert_eq!(switched.session, None);
+    assert_eq!(switched.settings.batch_size, 9);
+
+    let forgotten = state.reduce(Action::ForgetLearner);
+    assert_eq!(forgotten.learner, None);
+    assert_eq!(forgotten.settings.batch_size, 9);
+}
+
+#[test]
+fn only_the_selected_learners_profile_is_kept() {
+    let state = Rc::new(AppState { learner: Some(7), ..Default::default() });
+
+    assert_eq!(state.clone().reduce(Action::ProfileLoaded(profile(8))).profile, None);
+    let loaded = state.reduce(Action::ProfileLoaded(profile(7)));
+    assert_eq!(loaded.profile, Some(profile(7)));
+    let reloaded = loaded.reduce(Action::ProfileLoaded(AwesomeProfile { num_known: 3, ..profile(7) }));
+    assert_eq!(reloaded.profile.as_ref().map(|profile| profile.num_known), Some(3));
+}
+
+#[test]
+fn unchanged_state_is_not_replaced() {
+    let state = Rc::new(learner_state());
+
+    assert!(Rc::ptr_eq(&state, &state.clone().reduce(Action::SelectLearner(7))));
+    assert!(Rc::ptr_eq(&state, &state.clone().reduce(Action::ProfileLoaded(profile(7)))));
+    assert!(!Rc::ptr_eq(&state, &state.clone().reduce(Action::EndSession)));
+}
+
+#[test]
+fn settings_are_validated_and_themes_changed_on_their_own() {
+    let state = Rc::new(AppState::default());
+
+    let changed = state.reduce(Action::ChangeSettings(Settings { batch_size: 1000, ..Default::default() }));
+    assert_eq!(changed.settings.batch_size, MAX_BATCH_SIZE);
+
+    let theme = ThemeSettings { theme: Theme::Dark, ..Default::default() };
+    let themed = changed.reduce(Action::ChangeTheme(theme));
+    assert_eq!(themed.settings.theme, theme);
+    assert_eq!(themed.settings.batch_size, MAX_BATCH_SIZE);
+}
+
+#[test]
+fn subscribers_are_notified_only_when_their_part_changes() {
+    let store = Store::new(AppState { learner: Some(7), ..Default::default() });
+    let profiles = Rc::new(RefCell::new(Vec::new()));
+    let themes = Rc::new(RefCell::new(Vec::new()));
+
+    let _profile_subscription = store.select(|state| state.profile.clone(), {
+        let profiles = profiles.clone();
+        Callback::from(move |profile| profiles.borrow_mut().push(profile))
+    });
+    let _theme_subscription = store.select(|state| state.settings.theme, {
+        let themes = themes.clone();
+        Callback::from(move |theme| themes.borrow_mut().push(theme))
+    });
+
+    store.dispatch(Action::ProfileLoaded(profile(7)));
+    store.dispatch(Action::ProfileLoaded(profile(7)));
+    store.dispatch(Action::ForgetLearner);
+
+    assert_eq!(*profiles.borrow(), vec![Some(profile(7)), None]);
+    assert!(themes.borrow().is_empty());
+    assert_eq!(store.state().profile, None);
+}
+
+#[test]
+fn dropping_a_subscription_unsubscribes() {
+    let store = Store::new(AppState::default());
+    let calls = Rc::new(RefCell::new(0));
+
+    let subscription = store.select(|state| state.session.clone(), {
+        let calls = calls.clone();
+        Callback::from(move |_| *calls.borrow_mut() += 1)
+    });
+    store.dispatch(Action::SaveSession(StudySession::new(SessionLength::Items(5), 0.0)));
+    drop(subscription);
+    store.dispatch(Action::EndSession);
+
+    assert_eq!(*calls.borrow(), 1);
+    assert!(store.clone() == store);
+    assert!(Store::new(AppState::default()) != store);
+}
+
+#[test]
+fn query_results_are_shared_by_clones_of_the_store() {
+    let store = Store::new(AppState::default());
+    let key = QueryKey { operation: "AwesomePersonQuery".to_string(), variables: "{\"id\":7}".to_string() };
+
+    store.clone().with_queries(|cache| cache.insert(key.clone(), &profile(7), 1000.0, vec![CacheTag::Learner(7)], 0.0));
+    store.dispatch(Action::SelectLearner(3));
+
+    let cached = store.with_queries(|cache| cache.get::<AwesomeProfile>(&key, 10.0));
+    assert_eq!(cached, Some(Cached { value: profile(7), stale: false }));
+    assert!(Store::new(AppState::default()).with_queries(|cache| cache.is_empty()));
+}
@@ -0,0 +1,30 @@
+use yew_study::sl::theme::{FontSize, Theme, ThemeSettings};
+
+#[test]
+fn system_theme_follows_the_browser() {
+    assert_eq!(Theme::System.resolve(true), Theme::Dark);
+    assert_eq!(Theme::System.resolve(false), Theme::Light);
+    assert_eq!(Theme::System.attribute(true), "dark");
+    assert_eq!(Theme::System.attribute(false), "light");
+}
+
+#[test]
+fn chosen_themes_override_the_browser() {
+    assert_eq!(Theme::Light.attribute(true), "light");
+    assert_eq!(Theme::Dark.attribute(false), "dark");
+    assert_eq!(Theme::HighContrast.attribute(true), "high-contrast");
+}
+
+#[test]
+fn settings_default_to_the_system_theme_and_medium_text() {
+    let settings = ThemeSettings::default();
+    assert_eq!(settings, ThemeSettings { theme: Theme::System, font_size: FontSize::Medium });
+    assert_eq!(settings.font_size.attribute(), "medium");
+}
+
+#[test]
+fn settings_round_trip_through_json() {
+    let settings = ThemeSettings { theme: Theme::HighContrast, font_size: FontSize::ExtraLarge };
+    let json = serde_json::to_string(&settings).unwrap();
+    assert_eq!(serde_json::from_str::<ThemeSettings>(&json).unwrap(), settings);
+}
@@ -0,0 +1,57 @@
+use yew_study::sl::vocab::{VocabFilter, VocabPageResponseWrapper, VocabQuery, VocabSort, DEFAULT_PAGE_SIZE, WEAKEST_WORDS};
+
+#[test]
+fn query_pages_through_results() {
+    let mut query = VocabQuery::default();
+    assert_eq!(query.offset(), 0);
+    assert_eq!(query.num_pages(0), 1);
+    assert_eq!(query.num_pages(DEFAULT_PAGE_SIZE), 1);
+    assert_eq!(query.num_pages(DEFAULT_PAGE_SIZE + 1), 2);
+
+    query.page = 2;
+    assert_eq!(query.offset(), 2 * DEFAULT_PAGE_SIZE);
+}
+
+#[test]
+fn sorting_twice_flips_the_direction() {
+    let mut query = VocabQuery { page: 3, ..Default::default() };
+
+    query.sort_by(VocabSort::PercentageCorrect);
+    assert_eq!((query.sort, query.descending, query.page), (VocabSort::PercentageCorrect, false, 0));
+
+    query.sort_by(VocabSort::PercentageCorrect);
+    assert!(query.descending);
+
+    query.sort_by(VocabSort::LastTested);
+    assert_eq!((query.sort, query.descending), (VocabSort::LastTested, false));
+}
+
+#[test]
+fn filtering_goes_back_to_the_first_page() {
+    let mut query = VocabQuery { page: 3, ..Default::default() };
+    query.filter_by(VocabFilter { pos: Some("noun".to_string()), ..Default::default() });
+
+    assert_eq!(query.page, 0);
+    assert_eq!(query.filter.pos.as_deref(), Some("noun"));
+}
+
+#[test]
+fn weakest_words_are_the_least_accurate_first() {
+    let query = VocabQuery::weakest(WEAKEST_WORDS);
+
+    assert_eq!((query.sort, query.descending, query.page), (VocabSort::PercentageCorrect, false, 0));
+    assert_eq!(query.page_size, WEAKEST_WORDS);
+    assert_eq!(query.filter, VocabFilter::default());
+}
+
+#[test]
+fn vocab_page_deserializes() {
+    let json = r#"{"data":{"getVocabList":{"entries":[{"vocabId":1,"vocabStudyId":7,"firstLang":"bed",
+        "learning":"cama","pos":"noun","knownLangCode":"en","learningLangCode":"es","attempts":4,
+        "correctAttempts":3,"percentageCorrect":75.0,"lastTested":"2024-03-20"}],"total":41}}}"#;
+
+    let page = serde_json::from_str::<VocabPageResponseWrapper>(json).unwrap().data.get_vocab_list;
+    assert_eq!(page.total, 41);
+    assert_eq!(page.entries[0].learning, "cama");
+    assert_eq!(page.entries[0].vocab_study_id, 7);
+}
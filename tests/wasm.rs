@@ -3,6 +3,7 @@ use wasm_bindgen_test::*;
 
 /// Test the test
 #[wasm_bindgen_test]
+#[allow(clippy::eq_op)]
 fn pass() {
     assert_eq!(1 + 1, 2);
 }